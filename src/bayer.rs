@@ -0,0 +1,627 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::statistics::Statistics;
+use ndarray;
+
+// Bayer配列 (左上2x2の並び)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum BayerPattern {
+    Rggb,
+    Grbg,
+    Gbrg,
+    Bggr,
+}
+
+// Bayerチャネル (Gr: R行のG, Gb: B行のG)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum BayerChannel {
+    R,
+    Gr,
+    Gb,
+    B,
+}
+
+impl BayerChannel {
+    pub const ALL: [BayerChannel; 4] = [
+        BayerChannel::R,
+        BayerChannel::Gr,
+        BayerChannel::Gb,
+        BayerChannel::B,
+    ];
+}
+
+impl BayerPattern {
+    pub const ALL: [BayerPattern; 4] = [
+        BayerPattern::Rggb,
+        BayerPattern::Grbg,
+        BayerPattern::Gbrg,
+        BayerPattern::Bggr,
+    ];
+
+    // 座標(x, y)のチャネル取得
+    pub fn channel_at(&self, x: usize, y: usize) -> BayerChannel {
+        let quad = match self {
+            BayerPattern::Rggb => [
+                BayerChannel::R,
+                BayerChannel::Gr,
+                BayerChannel::Gb,
+                BayerChannel::B,
+            ],
+            BayerPattern::Grbg => [
+                BayerChannel::Gr,
+                BayerChannel::R,
+                BayerChannel::B,
+                BayerChannel::Gb,
+            ],
+            BayerPattern::Gbrg => [
+                BayerChannel::Gb,
+                BayerChannel::B,
+                BayerChannel::R,
+                BayerChannel::Gr,
+            ],
+            BayerPattern::Bggr => [
+                BayerChannel::B,
+                BayerChannel::Gb,
+                BayerChannel::Gr,
+                BayerChannel::R,
+            ],
+        };
+        quad[(y % 2) * 2 + (x % 2)]
+    }
+
+    // チャネルの2x2内オフセット(x, y)取得
+    pub fn offset(&self, channel: BayerChannel) -> (usize, usize) {
+        for y in 0..2 {
+            for x in 0..2 {
+                if self.channel_at(x, y) == channel {
+                    return (x, y);
+                }
+            }
+        }
+        unreachable!()
+    }
+}
+
+// 切り出し後のBayer配列 (切り出し開始座標(x, y)の偶奇で決まる)
+pub fn pattern_after_crop(pattern: BayerPattern, x: usize, y: usize) -> BayerPattern {
+    let top_left = pattern.channel_at(x, y);
+    BayerPattern::ALL
+        .into_iter()
+        .find(|p| p.channel_at(0, 0) == top_left)
+        .unwrap()
+}
+
+// チャネル別統計量
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BayerStatistics {
+    pub r: Statistics<f64>,
+    pub gr: Statistics<f64>,
+    pub gb: Statistics<f64>,
+    pub b: Statistics<f64>,
+}
+
+// チャネル面 (1/4解像度)
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct BayerPlanes<T: PixelType> {
+    pub r: NDRaw<T>,
+    pub gr: NDRaw<T>,
+    pub gb: NDRaw<T>,
+    pub b: NDRaw<T>,
+}
+
+impl<T: PixelType> BayerPlanes<T> {
+    // チャネル面取得
+    pub fn plane(&self, channel: BayerChannel) -> &NDRaw<T> {
+        match channel {
+            BayerChannel::R => &self.r,
+            BayerChannel::Gr => &self.gr,
+            BayerChannel::Gb => &self.gb,
+            BayerChannel::B => &self.b,
+        }
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // チャネル面分離
+    pub fn extract_bayer_planes(&self, pattern: BayerPattern) -> BayerPlanes<T> {
+        BayerPlanes {
+            r: self.bayer_plane(pattern, BayerChannel::R),
+            gr: self.bayer_plane(pattern, BayerChannel::Gr),
+            gb: self.bayer_plane(pattern, BayerChannel::Gb),
+            b: self.bayer_plane(pattern, BayerChannel::B),
+        }
+    }
+
+    // チャネル面からのモザイク再構成 (extract_bayer_planesの逆変換)
+    pub fn reconstruct_from_bayer_planes(planes: &BayerPlanes<T>, pattern: BayerPattern) -> Self {
+        // 奇数サイズでは先頭の行/列側の面が1画素大きい
+        let width = planes.plane(pattern.channel_at(0, 0)).width()
+            + planes.plane(pattern.channel_at(1, 0)).width();
+        let height = planes.plane(pattern.channel_at(0, 0)).height()
+            + planes.plane(pattern.channel_at(0, 1)).height();
+        let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            *planes.plane(pattern.channel_at(x, y)).pix(x / 2, y / 2)
+        });
+        NDRaw::from_ndarray(data)
+    }
+
+    // 2x2ブロック走査 => (左上x, 左上y, [R, Gr, Gb, B]) (奇数サイズの端数行/列は含まない)
+    pub fn quads(
+        &self,
+        pattern: BayerPattern,
+    ) -> impl Iterator<Item = (usize, usize, [T; 4])> + '_ {
+        let offsets = BayerChannel::ALL.map(|channel| pattern.offset(channel));
+        (0..self.height() / 2).flat_map(move |qy| {
+            (0..self.width() / 2).map(move |qx| {
+                let (x, y) = (qx * 2, qy * 2);
+                (x, y, offsets.map(|(ox, oy)| self.data[[y + oy, x + ox]]))
+            })
+        })
+    }
+
+    // チャネル別統計量計算
+    pub fn compute_bayer_statistics(&self, pattern: BayerPattern) -> BayerStatistics {
+        BayerStatistics {
+            r: self
+                .bayer_plane(pattern, BayerChannel::R)
+                .compute_statistics(),
+            gr: self
+                .bayer_plane(pattern, BayerChannel::Gr)
+                .compute_statistics(),
+            gb: self
+                .bayer_plane(pattern, BayerChannel::Gb)
+                .compute_statistics(),
+            b: self
+                .bayer_plane(pattern, BayerChannel::B)
+                .compute_statistics(),
+        }
+    }
+
+    // Gr/Gb感度比測定 (飽和画素を除くGr平均/Gb平均, 測定不能時は1.0)
+    pub fn measure_green_imbalance(&self, pattern: BayerPattern) -> f64 {
+        let (mean_gr, mean_gb) = self.green_means(pattern, 0, 0, self.width(), self.height());
+        match (mean_gr, mean_gb) {
+            (Some(gr), Some(gb)) if gb > 0.0 => gr / gb,
+            _ => 1.0,
+        }
+    }
+
+    // Gr/Gb段差補正 (画像全体, strength: 0.0=補正なし 〜 1.0=完全一致)
+    pub fn correct_green_imbalance(&mut self, pattern: BayerPattern, strength: f64) {
+        let (width, height) = (self.width(), self.height());
+        self.correct_green_imbalance_region(pattern, strength, 0, 0, width, height);
+    }
+
+    // Gr/Gb段差補正 (block_size四方のブロック毎)
+    pub fn correct_green_imbalance_local(
+        &mut self,
+        pattern: BayerPattern,
+        strength: f64,
+        block_size: usize,
+    ) {
+        let block_size = block_size.max(2);
+        for y0 in (0..self.height()).step_by(block_size) {
+            for x0 in (0..self.width()).step_by(block_size) {
+                let x1 = (x0 + block_size).min(self.width());
+                let y1 = (y0 + block_size).min(self.height());
+                self.correct_green_imbalance_region(pattern, strength, x0, y0, x1, y1);
+            }
+        }
+    }
+
+    // 領域内のGr/Gbを両者の平均へ寄せる (R/B及び飽和画素は変更しない)
+    fn correct_green_imbalance_region(
+        &mut self,
+        pattern: BayerPattern,
+        strength: f64,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    ) {
+        let (Some(mean_gr), Some(mean_gb)) = self.green_means(pattern, x0, y0, x1, y1) else {
+            return;
+        };
+        if mean_gr <= 0.0 || mean_gb <= 0.0 {
+            return;
+        }
+        let target = (mean_gr + mean_gb) / 2.0;
+        let gain_gr = 1.0 + strength * (target / mean_gr - 1.0);
+        let gain_gb = 1.0 + strength * (target / mean_gb - 1.0);
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let gain = match pattern.channel_at(x, y) {
+                    BayerChannel::Gr => gain_gr,
+                    BayerChannel::Gb => gain_gb,
+                    _ => continue,
+                };
+                let pix = &mut self.data[[y, x]];
+                if *pix < T::max_value() {
+                    *pix = T::from_f64_saturating(pix.to_f64().unwrap() * gain);
+                }
+            }
+        }
+    }
+
+    // 領域内の非飽和Gr/Gb平均
+    fn green_means(
+        &self,
+        pattern: BayerPattern,
+        x0: usize,
+        y0: usize,
+        x1: usize,
+        y1: usize,
+    ) -> (Option<f64>, Option<f64>) {
+        let mut sum = [0.0; 2];
+        let mut count = [0usize; 2];
+        for y in y0..y1 {
+            for x in x0..x1 {
+                let i = match pattern.channel_at(x, y) {
+                    BayerChannel::Gr => 0,
+                    BayerChannel::Gb => 1,
+                    _ => continue,
+                };
+                let pix = self.data[[y, x]];
+                if pix < T::max_value() {
+                    sum[i] += pix.to_f64().unwrap();
+                    count[i] += 1;
+                }
+            }
+        }
+        let mean = |i: usize| (count[i] > 0).then(|| sum[i] / count[i] as f64);
+        (mean(0), mean(1))
+    }
+
+    // チャネル別ヒストグラム ([R, Gr, Gb, B]の順, 全チャネル共通で画素値のmin〜maxをbins等分)
+    pub fn histogram_bayer(&self, pattern: BayerPattern, bins: usize) -> [Vec<u64>; 4] {
+        let stats = self.compute_statistics();
+        BayerChannel::ALL.map(|channel| {
+            self.bayer_plane(pattern, channel)
+                .histogram_in_range(bins, stats.min, stats.max)
+        })
+    }
+
+    // Bayer配列変換 (1画素ずらしで配列を合わせ画サイズは維持)
+    //   先頭の行/列は失われ, 末尾の行/列は2画素手前の同色画素で補完する
+    pub fn convert_pattern(&self, from: BayerPattern, to: BayerPattern) -> Self {
+        let (dx, dy) = from.offset(to.channel_at(0, 0));
+        let (width, height) = (self.width(), self.height());
+        let source = |v: usize, d: usize, len: usize| {
+            if v + d < len {
+                v + d
+            } else {
+                (v + d).saturating_sub(2)
+            }
+        };
+        let data = ndarray::Array2::from_shape_fn(self.data.dim(), |(y, x)| {
+            self.data[[source(y, dy, height), source(x, dx, width)]]
+        });
+        NDRaw::from_ndarray(data)
+    }
+
+    // 1/4解像度のチャネル面抽出
+    pub(crate) fn bayer_plane(&self, pattern: BayerPattern, channel: BayerChannel) -> NDRaw<T> {
+        let (ox, oy) = pattern.offset(channel);
+        let data = self.data.slice(ndarray::s![oy..;2, ox..;2]).to_owned();
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{pattern_after_crop, BayerChannel, BayerPattern};
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_channel_at() {
+        println!("bayer::test::test_channel_at()  {{");
+
+        for pattern in BayerPattern::ALL {
+            for channel in BayerChannel::ALL {
+                let (x, y) = pattern.offset(channel);
+                println!(
+                    "  [bayer][test_channel_at()] {:?}: {:?} at ({}, {})",
+                    pattern, channel, x, y
+                );
+                assert_eq!(channel, pattern.channel_at(x, y));
+                assert_eq!(channel, pattern.channel_at(x + 2, y + 4));
+            }
+        }
+        assert_eq!(BayerChannel::Gr, BayerPattern::Rggb.channel_at(1, 0));
+        assert_eq!(BayerChannel::Gb, BayerPattern::Grbg.channel_at(1, 1));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_bayer_statistics() {
+        println!("bayer::test::test_compute_bayer_statistics()  {{");
+
+        // R: 10/12, Gr: 20, Gb: 30, B: 40
+        let vec2d: Vec<Vec<u16>> = vec![
+            vec![10, 20, 12, 20],
+            vec![30, 40, 30, 40],
+            vec![12, 20, 10, 20],
+            vec![30, 40, 30, 40],
+        ];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let stats = raw_in.compute_bayer_statistics(BayerPattern::Rggb);
+        println!(
+            "  [bayer][test_compute_bayer_statistics()] stats = {:?}",
+            stats
+        );
+        assert_eq!(4, stats.r.count);
+        assert_eq!(10.0, stats.r.min);
+        assert_eq!(12.0, stats.r.max);
+        assert_eq!(11.0, stats.r.mean);
+        assert_eq!(1.0, stats.r.std_dev);
+        assert_eq!(20.0, stats.gr.mean);
+        assert_eq!(0.0, stats.gr.std_dev);
+        assert_eq!(30.0, stats.gb.mean);
+        assert_eq!(40.0, stats.b.mean);
+
+        // パターン違いではチャネルが入れ替わる
+        let stats = raw_in.compute_bayer_statistics(BayerPattern::Bggr);
+        assert_eq!(11.0, stats.b.mean);
+        assert_eq!(40.0, stats.r.mean);
+        assert_eq!(20.0, stats.gb.mean);
+        assert_eq!(30.0, stats.gr.mean);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_bayer_statistics_vertical_gradient() {
+        println!("bayer::test::test_compute_bayer_statistics_vertical_gradient()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..6).map(|y| vec![y * 10; 6]).collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let stats = raw_in.compute_bayer_statistics(BayerPattern::Rggb);
+        println!(
+            "  [bayer][test_compute_bayer_statistics_vertical_gradient()] gr.mean = {}, gb.mean = {}",
+            stats.gr.mean, stats.gb.mean
+        );
+        assert_eq!(20.0, stats.gr.mean);
+        assert_eq!(30.0, stats.gb.mean);
+        assert_ne!(stats.gr, stats.gb);
+
+        println!("}}");
+    }
+
+    // Gb = Gr * 1.02 の合成モザイク
+    fn green_imbalanced_mosaic(pattern: BayerPattern) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..16)
+            .map(|y| {
+                (0..16)
+                    .map(|x| {
+                        let base = 1000.0 + ((x / 2 * 7 + y / 2 * 3) % 11) as f64 * 10.0;
+                        match pattern.channel_at(x, y) {
+                            BayerChannel::R => 500,
+                            BayerChannel::Gr => base as u16,
+                            BayerChannel::Gb => (base * 1.02).round() as u16,
+                            BayerChannel::B => 700,
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_green_imbalance() {
+        println!("bayer::test::test_green_imbalance()  {{");
+
+        let pattern = BayerPattern::Grbg;
+        let mut raw_in = green_imbalanced_mosaic(pattern);
+        let ratio = raw_in.measure_green_imbalance(pattern);
+        println!("  [bayer][test_green_imbalance()] ratio = {}", ratio);
+        assert!((ratio - 1.0 / 1.02).abs() < 1e-3);
+
+        let before = raw_in.clone();
+        raw_in.correct_green_imbalance(pattern, 1.0);
+        let stats = raw_in.compute_bayer_statistics(pattern);
+        println!(
+            "  [bayer][test_green_imbalance()] gr.mean = {}, gb.mean = {}",
+            stats.gr.mean, stats.gb.mean
+        );
+        assert!((stats.gr.mean - stats.gb.mean).abs() < 1.0);
+        assert!((raw_in.measure_green_imbalance(pattern) - 1.0).abs() < 1e-3);
+
+        // R/Bは変更しない
+        for y in 0..raw_in.height() {
+            for x in 0..raw_in.width() {
+                if matches!(pattern.channel_at(x, y), BayerChannel::R | BayerChannel::B) {
+                    assert_eq!(before.pix(x, y), raw_in.pix(x, y));
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_green_imbalance_local() {
+        println!("bayer::test::test_green_imbalance_local()  {{");
+
+        let pattern = BayerPattern::Rggb;
+        let mut raw_in = green_imbalanced_mosaic(pattern);
+        raw_in.correct_green_imbalance_local(pattern, 1.0, 8);
+        let stats = raw_in.compute_bayer_statistics(pattern);
+        println!(
+            "  [bayer][test_green_imbalance_local()] gr.mean = {}, gb.mean = {}",
+            stats.gr.mean, stats.gb.mean
+        );
+        assert!((stats.gr.mean - stats.gb.mean).abs() < 1.0);
+        assert_eq!(500.0, stats.r.mean);
+        assert_eq!(700.0, stats.b.mean);
+
+        // strength 0は変更なし
+        let mut raw_in = green_imbalanced_mosaic(pattern);
+        raw_in.correct_green_imbalance(pattern, 0.0);
+        assert_eq!(green_imbalanced_mosaic(pattern).data(), raw_in.data());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_histogram_bayer() {
+        println!("bayer::test::test_histogram_bayer()  {{");
+
+        // R: 0〜99, Gr: 100〜199, Gb: 200〜299, B: 300〜399 (4binで各チャネル1binに収まる)
+        let pattern = BayerPattern::Grbg;
+        let vec2d: Vec<Vec<u16>> = (0..6)
+            .map(|y| {
+                (0..8)
+                    .map(|x| {
+                        let channel = pattern.channel_at(x, y) as u16;
+                        channel * 100 + ((x * 7 + y * 13) % 100) as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        // 範囲を0〜399に固定
+        *raw_in.pix_mut(1, 0) = 0;
+        *raw_in.pix_mut(0, 1) = 399;
+
+        let hists = raw_in.histogram_bayer(pattern, 4);
+        println!("  [bayer][test_histogram_bayer()] hists = {:?}", hists);
+        for channel in BayerChannel::ALL {
+            let hist = &hists[channel as usize];
+            for (bin, count) in hist.iter().enumerate() {
+                if bin == channel as usize {
+                    assert_eq!(12, *count, "{:?}", channel);
+                } else {
+                    assert_eq!(0, *count, "{:?} bin {}", channel, bin);
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_pattern_after_crop() {
+        println!("bayer::test::test_pattern_after_crop()  {{");
+
+        assert_eq!(
+            BayerPattern::Grbg,
+            pattern_after_crop(BayerPattern::Rggb, 1, 0)
+        );
+        assert_eq!(
+            BayerPattern::Bggr,
+            pattern_after_crop(BayerPattern::Rggb, 3, 5)
+        );
+        for pattern in BayerPattern::ALL {
+            for (cx, cy) in [(0, 0), (1, 0), (0, 1), (1, 1), (4, 3)] {
+                let cropped = pattern_after_crop(pattern, cx, cy);
+                for y in 0..2 {
+                    for x in 0..2 {
+                        assert_eq!(pattern.channel_at(cx + x, cy + y), cropped.channel_at(x, y));
+                    }
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_convert_pattern() {
+        println!("bayer::test::test_convert_pattern()  {{");
+
+        // 画素値 = チャネル番号 * 1000 + 通し番号
+        let (width, height) = (6, 4);
+        for from in BayerPattern::ALL {
+            let vec2d: Vec<Vec<u16>> = (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| from.channel_at(x, y) as u16 * 1000 + (y * width + x) as u16)
+                        .collect()
+                })
+                .collect();
+            let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+            for to in BayerPattern::ALL {
+                let raw_out = raw_in.convert_pattern(from, to);
+                assert_eq!(raw_in.shape(), raw_out.shape());
+                for y in 0..height {
+                    for x in 0..width {
+                        let channel = *raw_out.pix(x, y) / 1000;
+                        assert_eq!(
+                            to.channel_at(x, y) as u16,
+                            channel,
+                            "{:?} -> {:?} at ({}, {})",
+                            from,
+                            to,
+                            x,
+                            y
+                        );
+                    }
+                }
+                if from == to {
+                    assert_eq!(raw_in.data(), raw_out.data());
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bayer_planes_roundtrip() {
+        println!("bayer::test::test_bayer_planes_roundtrip()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..6)
+            .map(|y| (0..8).map(|x| (y * 8 + x) as u16).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        for pattern in BayerPattern::ALL {
+            let planes = raw_in.extract_bayer_planes(pattern);
+            for channel in BayerChannel::ALL {
+                let plane = planes.plane(channel);
+                assert_eq!(
+                    (raw_in.width() / 2, raw_in.height() / 2),
+                    (plane.width(), plane.height())
+                );
+                let (ox, oy) = pattern.offset(channel);
+                assert_eq!(*raw_in.pix(ox + 2, oy + 2), *plane.pix(1, 1));
+            }
+            let raw_out = NDRaw::reconstruct_from_bayer_planes(&planes, pattern);
+            assert_eq!(raw_in.data(), raw_out.data());
+        }
+
+        // 奇数サイズ
+        let vec2d: Vec<Vec<u16>> = (0..5)
+            .map(|y| (0..7).map(|x| (y * 7 + x) as u16).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let planes = raw_in.extract_bayer_planes(BayerPattern::Bggr);
+        println!(
+            "  [bayer][test_bayer_planes_roundtrip()] b = \n{}",
+            planes.b.data()
+        );
+        let raw_out = NDRaw::reconstruct_from_bayer_planes(&planes, BayerPattern::Bggr);
+        assert_eq!(raw_in.data(), raw_out.data());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_quads() {
+        println!("bayer::test::test_quads()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| (0..5).map(|x| (y * 5 + x) as u16).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let quads: Vec<(usize, usize, [u16; 4])> = raw_in.quads(BayerPattern::Gbrg).collect();
+        println!("  [bayer][test_quads()] quads = {:?}", quads);
+        assert_eq!(4, quads.len());
+        // GBRG: Gb(0, 0), B(1, 0), R(0, 1), Gr(1, 1)
+        assert_eq!((0, 0, [5, 6, 0, 1]), quads[0]);
+        assert_eq!((2, 2, [17, 18, 12, 13]), quads[3]);
+
+        println!("}}");
+    }
+}
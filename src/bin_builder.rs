@@ -0,0 +1,266 @@
+use crate::binfmt::BinOptions;
+use crate::error::SensorIoError;
+use crate::headerless::Endianness;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::raw::RawImage;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+// 画素ブロックの圧縮方式 (Deflateはflate2 feature有効時のみ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinCompression {
+    None,
+    Deflate,
+}
+
+// bin画像の書き込み (各設定は省略時に既定値, 既定値はwrite_binimageと同じ形式)
+//   フォーマットはbinfmtを参照, write_binimage/write_binimage_compressed等もここを通す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BinWriter {
+    options: BinOptions,
+}
+
+impl Default for BinWriter {
+    fn default() -> Self {
+        BinWriter {
+            options: BinOptions {
+                checksum: true,
+                ..BinOptions::default()
+            },
+        }
+    }
+}
+
+impl BinWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // バイトオーダー (既定: Little)
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.options.endianness = endianness;
+        self
+    }
+
+    // 画素のビット深度 (1〜16, 既定: 16, 8以下は1画素1バイトで保存, i32/i64の画素には適用しない)
+    pub fn bit_depth(mut self, bit_depth: u8) -> Self {
+        self.options.bit_depth = bit_depth;
+        self
+    }
+
+    // 圧縮方式 (既定: None)
+    pub fn compression(mut self, compression: BinCompression) -> Self {
+        self.options.compression = compression;
+        self
+    }
+
+    // CRC32の付加 (既定: true)
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.options.checksum = checksum;
+        self
+    }
+
+    // 書き込み (u16を超えるサイズ・ビット深度を超える画素値はエラー)
+    pub fn write<R: RawImage + ?Sized>(
+        &self,
+        raw: &R,
+        path: impl AsRef<Path>,
+    ) -> Result<(), SensorIoError> {
+        let mut f_write = BufWriter::new(File::create(path)?);
+        self.write_to_stream(raw, &mut f_write)
+    }
+
+    // ストリームへの書き込み
+    pub fn write_to_stream<R: RawImage + ?Sized, W: Write + ?Sized>(
+        &self,
+        raw: &R,
+        writer: &mut W,
+    ) -> Result<(), SensorIoError> {
+        self.options.write(raw, writer)
+    }
+}
+
+// bin画像の読み込み (圧縮・CRC32の有無はヘッダのflagsで判定, バイトオーダー・ビット深度は書き込み時と同じ設定を指定する)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BinReader {
+    options: BinOptions,
+}
+
+impl BinReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // バイトオーダー (既定: Little)
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.options.endianness = endianness;
+        self
+    }
+
+    // 画素のビット深度 (1〜16, 既定: 16)
+    pub fn bit_depth(mut self, bit_depth: u8) -> Self {
+        self.options.bit_depth = bit_depth;
+        self
+    }
+
+    // CRC32の必須化 (既定: false, CRC32があれば常に検証し不一致はChecksumMismatchエラー)
+    pub fn checksum(mut self, checksum: bool) -> Self {
+        self.options.checksum = checksum;
+        self
+    }
+
+    // 読み込み
+    pub fn read<T: PixelType>(&self, path: impl AsRef<Path>) -> Result<NDRaw<T>, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path)?);
+        self.read_from_stream(&mut f_read)
+    }
+
+    // ストリームからの読み込み
+    pub fn read_from_stream<T: PixelType, R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<NDRaw<T>, SensorIoError> {
+        let (width, height, pixels) = self.read_pixels(reader)?;
+        let data = ndarray::Array2::from_shape_vec((height, width), pixels).unwrap();
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    // => (width, height, 行優先の画素値)
+    pub(crate) fn read_pixels<T: PixelType, R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(usize, usize, Vec<T>), SensorIoError> {
+        self.options.read(reader)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BinCompression, BinReader, BinWriter};
+    use crate::error::SensorIoError;
+    use crate::headerless::Endianness;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "sensor_io_bin_builder_{}_{}.bin",
+            name,
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn test_bin_writer_big_endian() {
+        println!("bin_builder::test::test_bin_writer_big_endian()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..3)
+            .map(|y| (0..5).map(|x| (y * 1000 + x * 7) as u16).collect())
+            .collect();
+        let raw = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let path = temp_path("big");
+        BinWriter::new()
+            .endianness(Endianness::Big)
+            .checksum(true)
+            .write(&raw, &path)
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        println!(
+            "  [bin_builder][test_bin_writer_big_endian()] header = {:?}",
+            &bytes[..8]
+        );
+        // marker, flags(CRC32付き), reserved, width, height
+        assert_eq!(&[0, 0, 2, 0, 0, 5, 0, 3], &bytes[..8]);
+        assert_eq!(8 + 15 * 2 + 4, bytes.len());
+
+        let reader = BinReader::new().endianness(Endianness::Big).checksum(true);
+        let restored = reader.read::<u16>(&path).unwrap();
+        assert_eq!(raw.data(), restored.data());
+
+        // バイトオーダー違い => ヘッダのサイズに対して画素が足りない, 破損 => チェックサム不一致
+        assert!(matches!(
+            BinReader::new().read::<u16>(&path),
+            Err(SensorIoError::Io(_))
+        ));
+        let mut corrupted = bytes.clone();
+        corrupted[14] ^= 0x01;
+        std::fs::write(&path, corrupted).unwrap();
+        assert!(matches!(
+            reader.read::<u16>(&path),
+            Err(SensorIoError::ChecksumMismatch { .. })
+        ));
+        std::fs::remove_file(&path).unwrap();
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bin_writer_options() {
+        println!("bin_builder::test::test_bin_writer_options()  {{");
+
+        // 既定値はwrite_binimageと同じ形式
+        let raw = NARaw::<u16>::new_from_vector2d(&[vec![1, 200, 3], vec![40, 5, 255]]);
+        let path = temp_path("default");
+        let path_str = path.to_str().unwrap().to_string();
+        BinWriter::new().write(&raw, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        raw.write_binimage(path_str.clone()).unwrap();
+        assert_eq!(bytes, std::fs::read(&path).unwrap());
+        let restored = NARaw::<u16>::new_from_binimage(path_str.clone()).unwrap();
+        assert_eq!(raw.data(), restored.data());
+
+        // 8bit・チェックサムなし
+        BinWriter::new()
+            .bit_depth(8)
+            .checksum(false)
+            .write(&raw, &path)
+            .unwrap();
+        assert_eq!(8 + 6, std::fs::metadata(&path).unwrap().len());
+        let restored = BinReader::new().bit_depth(8).read::<u16>(&path).unwrap();
+        println!(
+            "  [bin_builder][test_bin_writer_options()] restored = \n{}",
+            restored.data()
+        );
+        assert_eq!(255, *restored.pix(2, 1));
+
+        // ビット深度を超える画素値・範囲外のビット深度
+        assert!(matches!(
+            BinWriter::new().bit_depth(6).write(&raw, &path),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            BinWriter::new().bit_depth(17).write(&raw, &path),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        // u16を超えるサイズ
+        let wide = NDRaw::<u16>::new(70000, 1);
+        assert!(matches!(
+            BinWriter::new().write(&wide, &path),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        // 圧縮 (flate2 feature無効時は非対応エラー)
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 32]; 16]);
+        let writer = BinWriter::new().compression(BinCompression::Deflate);
+        if cfg!(feature = "flate2") {
+            // 圧縮有無・CRC32有無はヘッダのflagsで判定, 通常の読み込みと同じ形式
+            writer.checksum(true).write(&flat, &path).unwrap();
+            assert!(std::fs::metadata(&path).unwrap().len() < 4 + 32 * 16 * 2);
+            let restored = BinReader::new().checksum(true).read::<u16>(&path).unwrap();
+            assert_eq!(flat.data(), restored.data());
+            let restored = NDRaw::<u16>::new_from_binimage(path_str.clone()).unwrap();
+            assert_eq!(flat.data(), restored.data());
+        } else {
+            assert!(matches!(
+                writer.write(&flat, &path),
+                Err(SensorIoError::Unsupported(_))
+            ));
+        }
+        std::fs::remove_file(&path).unwrap();
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,438 @@
+use crate::bin_builder::BinCompression;
+use crate::error::SensorIoError;
+use crate::headerless::Endianness;
+use crate::pixel::{BinWordKind, PixelType};
+use crate::raw::RawImage;
+use std::fs::File;
+use std::io::{BufReader, Read, Write};
+use std::path::Path;
+
+// bin画像フォーマット (bin画像の読み書きは全てここを通す)
+//   marker(u16: 0), flags(u8), word(u8), width(u16), height(u16), 画素ブロック部
+//   flags: bit0 = deflate圧縮, bit1 = CRC32付き
+//   word: 画素ワードの種類 (0 = u16, 1 = i16, 2 = i32, PixelType::BIN_WORD_KINDで決まる)
+//   画素ブロック部: pixels(i32: 4バイト, bit_depth <= 8: u8, それ以外: u16, 行優先) [+ CRC32(u32, pixels部)]
+//                   deflate圧縮時は 圧縮後のバイト数(u32) + 圧縮した(pixels [+ CRC32])
+//   数値は全てendiannessで指定したバイトオーダー (既定はLittle Endian, 16bit, 非圧縮, 書き込みはCRC32付き)
+//   markerのない旧形式 width(u16), height(u16), pixels も読み込める (非圧縮・CRC32なし・u16)
+//   読み込みはwordに従って解釈し, 読み込み側の型に収まらない値はエラー
+
+// 1画素あたりのバイト数 (既定の16bit)
+pub(crate) const PIXEL_BYTE_SIZE: usize = 2;
+
+// CRC32のバイト数
+pub(crate) const CHECKSUM_BYTE_SIZE: usize = 4;
+
+// ヘッダ先頭のmarker (旧形式ではwidthの位置のため, 幅0の旧形式画像とは区別できない)
+const HEADER_MARKER: u16 = 0;
+
+// ヘッダのflags
+pub(crate) const FLAG_DEFLATE: u8 = 0b01;
+pub(crate) const FLAG_CHECKSUM: u8 = 0b10;
+
+// ヘッダのword
+fn word_kind_tag(kind: BinWordKind) -> u8 {
+    match kind {
+        BinWordKind::Unsigned16 => 0,
+        BinWordKind::Signed16 => 1,
+        BinWordKind::Signed32 => 2,
+    }
+}
+
+fn word_kind_from_tag(tag: u8) -> Option<BinWordKind> {
+    match tag {
+        0 => Some(BinWordKind::Unsigned16),
+        1 => Some(BinWordKind::Signed16),
+        2 => Some(BinWordKind::Signed32),
+        _ => None,
+    }
+}
+
+// ヘッダのみ読み込み => (width, height)
+pub fn read_bin_header(path: impl AsRef<Path>) -> Result<(usize, usize), SensorIoError> {
+    let mut f_read = BufReader::new(File::open(path)?);
+    let (width, height, _, _) = BinOptions::default().read_header(&mut f_read)?;
+    Ok((width, height))
+}
+
+#[cfg(feature = "flate2")]
+fn compress(payload: &[u8]) -> Result<Vec<u8>, SensorIoError> {
+    let mut encoder =
+        flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(payload)?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(not(feature = "flate2"))]
+fn compress(_payload: &[u8]) -> Result<Vec<u8>, SensorIoError> {
+    Err(SensorIoError::Unsupported(String::from(
+        "deflate compression requires the flate2 feature",
+    )))
+}
+
+// 展開 (limitバイトを超える分は読み込まない)
+#[cfg(feature = "flate2")]
+fn decompress(payload: &[u8], limit: usize) -> Result<Vec<u8>, SensorIoError> {
+    let mut decoded = Vec::new();
+    flate2::read::DeflateDecoder::new(payload)
+        .take(limit as u64)
+        .read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+#[cfg(not(feature = "flate2"))]
+fn decompress(_payload: &[u8], _limit: usize) -> Result<Vec<u8>, SensorIoError> {
+    Err(SensorIoError::Unsupported(String::from(
+        "deflate compression requires the flate2 feature",
+    )))
+}
+
+// bin画像の書き込み/読み込み設定 (BinWriter/BinReaderが保持する)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BinOptions {
+    pub(crate) endianness: Endianness,
+    pub(crate) bit_depth: u8,
+    pub(crate) compression: BinCompression,
+    pub(crate) checksum: bool,
+}
+
+impl Default for BinOptions {
+    fn default() -> Self {
+        BinOptions {
+            endianness: Endianness::Little,
+            bit_depth: 16,
+            compression: BinCompression::None,
+            checksum: false,
+        }
+    }
+}
+
+impl BinOptions {
+    fn check(&self) -> Result<(), SensorIoError> {
+        if !(1..=16).contains(&self.bit_depth) {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "bit depth {} must be 1 to 16",
+                self.bit_depth
+            )));
+        }
+        Ok(())
+    }
+
+    fn word_size(&self, kind: BinWordKind) -> usize {
+        match kind {
+            BinWordKind::Signed32 => 4,
+            _ if self.bit_depth <= 8 => 1,
+            _ => PIXEL_BYTE_SIZE,
+        }
+    }
+
+    fn encode_u16(&self, v: u16) -> [u8; 2] {
+        match self.endianness {
+            Endianness::Little => v.to_le_bytes(),
+            Endianness::Big => v.to_be_bytes(),
+        }
+    }
+
+    fn encode_u32(&self, v: u32) -> [u8; 4] {
+        match self.endianness {
+            Endianness::Little => v.to_le_bytes(),
+            Endianness::Big => v.to_be_bytes(),
+        }
+    }
+
+    fn decode_u16(&self, b: [u8; 2]) -> u16 {
+        match self.endianness {
+            Endianness::Little => u16::from_le_bytes(b),
+            Endianness::Big => u16::from_be_bytes(b),
+        }
+    }
+
+    fn decode_u32(&self, b: [u8; 4]) -> u32 {
+        match self.endianness {
+            Endianness::Little => u32::from_le_bytes(b),
+            Endianness::Big => u32::from_be_bytes(b),
+        }
+    }
+
+    fn read_u8<R: Read + ?Sized>(&self, reader: &mut R) -> std::io::Result<u8> {
+        let mut b = [0u8; 1];
+        reader.read_exact(&mut b)?;
+        Ok(b[0])
+    }
+
+    fn read_u16<R: Read + ?Sized>(&self, reader: &mut R) -> std::io::Result<u16> {
+        let mut b = [0u8; 2];
+        reader.read_exact(&mut b)?;
+        Ok(self.decode_u16(b))
+    }
+
+    fn read_u32<R: Read + ?Sized>(&self, reader: &mut R) -> std::io::Result<u32> {
+        let mut b = [0u8; 4];
+        reader.read_exact(&mut b)?;
+        Ok(self.decode_u32(b))
+    }
+
+    // ヘッダ読み込み => (width, height, flags, word) (旧形式はflags 0, word u16)
+    fn read_header<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(usize, usize, u8, BinWordKind), SensorIoError> {
+        let first = self.read_u16(reader)?;
+        if first != HEADER_MARKER {
+            let height = self.read_u16(reader)?;
+            return Ok((first as usize, height as usize, 0, BinWordKind::Unsigned16));
+        }
+        let flags = self.read_u8(reader)?;
+        let tag = self.read_u8(reader)?;
+        let kind = word_kind_from_tag(tag);
+        if flags & !(FLAG_DEFLATE | FLAG_CHECKSUM) != 0 || kind.is_none() {
+            return Err(SensorIoError::Parse(format!(
+                "unknown bin header flags {:#04x} / word {:#04x}",
+                flags, tag
+            )));
+        }
+        let width = self.read_u16(reader)? as usize;
+        let height = self.read_u16(reader)? as usize;
+        Ok((width, height, flags, kind.unwrap()))
+    }
+
+    // ヘッダ + 画素ブロック部書き込み (u16を超えるサイズ・ワード/ビット深度を超える画素値はエラー)
+    //   i32のワードにはビット深度を適用しない
+    //   圧縮は非圧縮の画素ブロックより短くなる場合のみ, それ以外は非圧縮で書き込む
+    pub(crate) fn write<R: RawImage + ?Sized, W: Write + ?Sized>(
+        &self,
+        raw: &R,
+        writer: &mut W,
+    ) -> Result<(), SensorIoError> {
+        self.check()?;
+        let (width, height) = (raw.width(), raw.height());
+        let size_error = |name: &str, v: usize| {
+            SensorIoError::InvalidArgument(format!("{} {} does not fit the u16 header", name, v))
+        };
+        let header_width = u16::try_from(width).map_err(|_| size_error("width", width))?;
+        let header_height = u16::try_from(height).map_err(|_| size_error("height", height))?;
+        let max_word = ((1u32 << self.bit_depth) - 1) as u16;
+        let kind = R::Pixel::BIN_WORD_KIND;
+        let word_size = self.word_size(kind);
+
+        let mut payload = Vec::with_capacity(width * height * word_size + CHECKSUM_BYTE_SIZE);
+        for y in 0..height {
+            for x in 0..width {
+                let v = *raw.pix(x, y);
+                if kind == BinWordKind::Signed32 {
+                    let word = v.to_bin_word32().ok_or_else(|| {
+                        SensorIoError::InvalidArgument(format!(
+                            "pixel value {} does not fit a 32-bit bin word",
+                            v
+                        ))
+                    })?;
+                    payload.extend_from_slice(&self.encode_u32(word as u32));
+                    continue;
+                }
+                let word = convert_word(v)?;
+                if word > max_word {
+                    return Err(SensorIoError::InvalidArgument(format!(
+                        "pixel value {} does not fit {} bits",
+                        v, self.bit_depth
+                    )));
+                }
+                match word_size {
+                    1 => payload.push(word as u8),
+                    _ => payload.extend_from_slice(&self.encode_u16(word)),
+                }
+            }
+        }
+        let mut flags = 0;
+        if self.checksum {
+            let crc = crc32fast::hash(&payload);
+            payload.extend_from_slice(&self.encode_u32(crc));
+            flags |= FLAG_CHECKSUM;
+        }
+        let compressed = match self.compression {
+            BinCompression::None => None,
+            BinCompression::Deflate => {
+                // 圧縮後のバイト数(u32)込みで短くなる場合のみ
+                let compressed = compress(&payload)?;
+                (4 + compressed.len() < payload.len()).then_some(compressed)
+            }
+        };
+        if compressed.is_some() {
+            flags |= FLAG_DEFLATE;
+        }
+
+        writer.write_all(&self.encode_u16(HEADER_MARKER))?;
+        writer.write_all(&[flags, word_kind_tag(kind)])?;
+        writer.write_all(&self.encode_u16(header_width))?;
+        writer.write_all(&self.encode_u16(header_height))?;
+        match compressed {
+            Some(compressed) => {
+                // 圧縮後のバイト数はpayload以下のためu32に収まる
+                writer.write_all(&self.encode_u32(compressed.len() as u32))?;
+                writer.write_all(&compressed)?;
+            }
+            None => writer.write_all(&payload)?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    // ヘッダ + 画素ブロック部読み込み => (width, height, 行優先の画素値)
+    //   ヘッダのflagsに従い画素ブロック部のみを読み込む (続くデータは読み込まない)
+    //   CRC32があれば検証 (checksum指定時はCRC32がなければエラー)
+    pub(crate) fn read<T: PixelType, R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<(usize, usize, Vec<T>), SensorIoError> {
+        self.check()?;
+        let (width, height, flags, kind) = self.read_header(reader)?;
+        let has_checksum = flags & FLAG_CHECKSUM != 0;
+        if self.checksum && !has_checksum {
+            return Err(SensorIoError::Parse(String::from(
+                "bin image has no CRC32 trailer",
+            )));
+        }
+        let word_size = self.word_size(kind);
+        let block_len = width * height * word_size;
+        let payload_len = block_len + if has_checksum { CHECKSUM_BYTE_SIZE } else { 0 };
+
+        let payload = if flags & FLAG_DEFLATE != 0 {
+            let compressed_len = self.read_u32(reader)? as usize;
+            if compressed_len > payload_len {
+                return Err(SensorIoError::Parse(format!(
+                    "compressed block of {} bytes exceeds the {} byte pixel block",
+                    compressed_len, payload_len
+                )));
+            }
+            let mut compressed = vec![0u8; compressed_len];
+            reader.read_exact(&mut compressed)?;
+            let payload = decompress(&compressed, payload_len)?;
+            if payload.len() != payload_len {
+                return Err(SensorIoError::Parse(format!(
+                    "compressed block expands to {} bytes, expected {}",
+                    payload.len(),
+                    payload_len
+                )));
+            }
+            payload
+        } else {
+            let mut payload = vec![0u8; payload_len];
+            reader.read_exact(&mut payload)?;
+            payload
+        };
+
+        let (block, trailer) = payload.split_at(block_len);
+        if has_checksum {
+            let expected = self.decode_u32([trailer[0], trailer[1], trailer[2], trailer[3]]);
+            let actual = crc32fast::hash(block);
+            if expected != actual {
+                return Err(SensorIoError::ChecksumMismatch { expected, actual });
+            }
+        }
+        let pixels = block
+            .chunks_exact(word_size)
+            .map(|b| {
+                let word = match (kind, b) {
+                    (BinWordKind::Signed32, _) => self.decode_u32([b[0], b[1], b[2], b[3]]) as i32,
+                    (BinWordKind::Signed16, [v]) => *v as i16 as i32,
+                    (BinWordKind::Signed16, _) => self.decode_u16([b[0], b[1]]) as i16 as i32,
+                    (BinWordKind::Unsigned16, [v]) => *v as i32,
+                    (BinWordKind::Unsigned16, _) => self.decode_u16([b[0], b[1]]) as i32,
+                };
+                T::from_bin_word32(word).ok_or_else(|| {
+                    SensorIoError::Parse(format!(
+                        "pixel value {} does not fit the pixel type",
+                        word
+                    ))
+                })
+            })
+            .collect::<Result<Vec<T>, SensorIoError>>()?;
+        Ok((width, height, pixels))
+    }
+}
+
+// T => 16bit画素値変換 (16bitに収まらない値はエラー)
+pub(crate) fn convert_word<T: PixelType>(v: T) -> Result<u16, SensorIoError> {
+    v.to_bin_word().ok_or_else(|| {
+        SensorIoError::InvalidArgument(format!("pixel value {} does not fit a 16-bit bin word", v))
+    })
+}
+
+// 16bit画素値 => T変換
+pub(crate) fn convert_pixel<T: PixelType>(v: u16) -> Result<T, SensorIoError> {
+    T::from_bin_word(v).ok_or_else(|| {
+        SensorIoError::Parse(format!("pixel value {} does not fit the pixel type", v))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_bin_header;
+    use crate::bin_builder::BinWriter;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_read_bin_header() {
+        println!("binfmt::test::test_read_bin_header()  {{");
+
+        let raw_in = NDRaw::<u16>::new(4, 3);
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_header_{}.bin", std::process::id()));
+        raw_in
+            .write_binimage(path.to_str().unwrap().to_string())
+            .unwrap();
+
+        // 画素部を切り詰めてもヘッダは読める
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..8]).unwrap();
+        let header = read_bin_header(&path);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [binfmt][test_read_bin_header()] header = {:?}",
+            header.as_ref().ok()
+        );
+        assert_eq!((4, 3), header.unwrap());
+
+        // 旧形式(markerなし)のヘッダ
+        assert_eq!((4, 3), read_bin_header("testdata/test.bin").unwrap());
+
+        assert!(matches!(
+            read_bin_header(std::env::temp_dir().join("sensor_io_no_such_file.bin")),
+            Err(SensorIoError::Io(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_consecutive_frames() {
+        println!("binfmt::test::test_consecutive_frames()  {{");
+
+        // 1つのストリームに続けて書き込んだフレームを順に読み込める
+        let frame1 = NDRaw::<u16>::new_from_vector2d(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        let frame2 = NDRaw::<u16>::new_from_vector2d(&[vec![7, 8], vec![9, 10], vec![11, 12]]);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        frame1.write_to_stream(&mut cursor).unwrap();
+        BinWriter::new()
+            .checksum(false)
+            .write_to_stream(&frame2, &mut cursor)
+            .unwrap();
+
+        cursor.set_position(0);
+        let read1 = NDRaw::<u16>::read_from_stream(&mut cursor).unwrap();
+        println!(
+            "  [binfmt][test_consecutive_frames()] position = {}",
+            cursor.position()
+        );
+        let read2 = NDRaw::<u16>::read_from_stream(&mut cursor).unwrap();
+        assert_eq!(frame1.data(), read1.data());
+        assert_eq!(frame2.data(), read2.data());
+        assert_eq!(cursor.get_ref().len() as u64, cursor.position());
+
+        // 3フレーム目はない
+        let result = NDRaw::<u16>::read_from_stream(&mut cursor);
+        assert!(matches!(result, Err(SensorIoError::Io(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,159 @@
+use crate::bayer::BayerPattern;
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 4x4スーパーセル未満の端数行・列の扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinEdge {
+    // 端数を切り捨て
+    Truncate,
+    // 端数があればエラー
+    Error,
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // Bayer配列を保った2x2ビニング (同色4画素の平均, 最近接丸め)
+    pub fn bin2x2(&self, pattern: BayerPattern, edge: BinEdge) -> Result<Self, SensorIoError> {
+        let data = self.bin2x2_with(pattern, edge, |sum| T::from_f64_saturating(sum / 4.0))?;
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    // Bayer配列を保った2x2ビニング (同色4画素の和, 出力型の範囲で飽和)
+    pub fn bin2x2_sum<U: PixelType>(
+        &self,
+        pattern: BayerPattern,
+        edge: BinEdge,
+    ) -> Result<NDRaw<U>, SensorIoError> {
+        let data = self.bin2x2_with(pattern, edge, U::from_f64_saturating)?;
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    fn bin2x2_with<U>(
+        &self,
+        pattern: BayerPattern,
+        edge: BinEdge,
+        convert: impl Fn(f64) -> U,
+    ) -> Result<ndarray::Array2<U>, SensorIoError> {
+        let (width, height) = (self.width(), self.height());
+        if edge == BinEdge::Error && (width % 4 != 0 || height % 4 != 0) {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "{}x{} is not a multiple of the 4x4 binning super-cell",
+                width, height
+            )));
+        }
+
+        let (out_w, out_h) = (width / 4 * 2, height / 4 * 2);
+        Ok(ndarray::Array2::from_shape_fn(
+            (out_h, out_w),
+            |(oy, ox)| {
+                // 出力画素と同じ色の, 4x4スーパーセル内の画素を積算
+                let (cx, cy) = pattern.offset(pattern.channel_at(ox, oy));
+                let (x0, y0) = (ox / 2 * 4 + cx, oy / 2 * 4 + cy);
+                let sum: f64 = [(0, 0), (2, 0), (0, 2), (2, 2)]
+                    .iter()
+                    .map(|(dx, dy)| self.data[[y0 + dy, x0 + dx]].to_f64().unwrap())
+                    .sum();
+                convert(sum)
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BinEdge;
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 色毎に値域の異なるモザイク (R: 1000台, Gr: 2000台, Gb: 3000台, B: 4000台)
+    fn channel_mosaic(pattern: BayerPattern, width: usize, height: usize) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let base = 1000 * (pattern.channel_at(x, y) as u16 + 1);
+                        base + (x / 2 + y / 2 * 8) as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_bin2x2() {
+        println!("binning::test::test_bin2x2()  {{");
+
+        for pattern in [BayerPattern::Rggb, BayerPattern::Gbrg] {
+            let raw_in = channel_mosaic(pattern, 8, 8);
+            let raw_out = raw_in.bin2x2(pattern, BinEdge::Error).unwrap();
+            println!(
+                "  [binning][test_bin2x2()] {:?} raw_out = \n{}",
+                pattern,
+                raw_out.data()
+            );
+            assert_eq!((4, 4), (raw_out.width(), raw_out.height()));
+            for y in 0..4 {
+                for x in 0..4 {
+                    let channel = pattern.channel_at(x, y);
+                    let base = 1000 * (channel as u16 + 1);
+                    // 4画素の(x/2 + y/2*8)の平均: 左上 + 0.5 + 4 -> 丸めで +5
+                    let expected = base + (x / 2 * 2 + y / 2 * 16) as u16 + 5;
+                    assert_eq!(
+                        expected,
+                        *raw_out.pix(x, y),
+                        "{:?} at ({}, {})",
+                        channel,
+                        x,
+                        y
+                    );
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bin2x2_edge() {
+        println!("binning::test::test_bin2x2_edge()  {{");
+
+        let raw_in = channel_mosaic(BayerPattern::Rggb, 10, 7);
+        let result = raw_in.bin2x2(BayerPattern::Rggb, BinEdge::Error);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        let raw_out = raw_in
+            .bin2x2(BayerPattern::Rggb, BinEdge::Truncate)
+            .unwrap();
+        assert_eq!((4, 2), (raw_out.width(), raw_out.height()));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bin2x2_sum() {
+        println!("binning::test::test_bin2x2_sum()  {{");
+
+        let raw_in = channel_mosaic(BayerPattern::Rggb, 4, 4);
+        let raw_sum = raw_in
+            .bin2x2_sum::<u32>(BayerPattern::Rggb, BinEdge::Error)
+            .unwrap();
+        println!(
+            "  [binning][test_bin2x2_sum()] raw_sum = \n{}",
+            raw_sum.data()
+        );
+        // R: 1000 + {0, 1, 8, 9}
+        assert_eq!(4018, *raw_sum.pix(0, 0));
+        assert_eq!(16018, *raw_sum.pix(1, 1));
+
+        // 狭い出力型では飽和
+        let raw_sat = raw_in
+            .bin2x2_sum::<u8>(BayerPattern::Rggb, BinEdge::Error)
+            .unwrap();
+        assert!(raw_sat.data().iter().all(|v| *v == u8::MAX));
+
+        println!("}}");
+    }
+}
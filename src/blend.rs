@@ -0,0 +1,65 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::pixel::PixelType;
+use crate::raw::RawImage;
+use num_traits::ToPrimitive;
+
+// 重み付き合成 (alpha*a + (1-alpha)*b, 結果はaに格納)
+pub(crate) fn blend_into<R: RawImage + ?Sized>(
+    a: &mut R,
+    b: &R,
+    alpha: f64,
+) -> Result<(), SensorIoError> {
+    check_shape((a.width(), a.height()), (b.width(), b.height()))?;
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "alpha {} is outside [0, 1]",
+            alpha
+        )));
+    }
+
+    for y in 0..a.height() {
+        for x in 0..a.width() {
+            let va = a.pix(x, y).to_f64().unwrap();
+            let vb = b.pix(x, y).to_f64().unwrap();
+            *a.pix_mut(x, y) = R::Pixel::from_f64_saturating(alpha * va + (1.0 - alpha) * vb);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    #[test]
+    fn test_blend() {
+        println!("blend::test::test_blend()  {{");
+
+        let a = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 4]; 3]);
+        let b = NDRaw::<u16>::new_from_vector2d(&vec![vec![301; 4]; 3]);
+        let blended = a.blend(&b, 0.5).unwrap();
+        println!("  [blend][test_blend()] blended = \n{}", blended.data());
+        // 200.5は最近接丸めで201
+        assert!(blended.data().iter().all(|v| *v == 201));
+        assert_eq!(a.data(), a.blend(&b, 1.0).unwrap().data());
+        assert_eq!(b.data(), a.blend(&b, 0.0).unwrap().data());
+
+        let a = NARaw::<f32>::new_from_vector2d(&vec![vec![0.0; 2]; 2]);
+        let b = NARaw::<f32>::new_from_vector2d(&vec![vec![8.0; 2]; 2]);
+        assert_eq!(6.0, *a.blend(&b, 0.25).unwrap().pix(1, 1));
+
+        assert!(matches!(
+            a.blend(&b, 1.5),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            a.blend(&NARaw::<f32>::new(3, 2), 0.5),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+
+        println!("}}");
+    }
+}
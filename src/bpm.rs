@@ -0,0 +1,253 @@
+use crate::defect::{DefectCorrection, DefectKind, DefectPixel};
+use crate::error::{check_shape, SensorIoError};
+use crate::raw::RawImage;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+// 欠陥画素マップ (テキスト形式)
+//   1行目: BPM <width> <height> <count>
+//   以降 : <x> <y> <hot|dead|stuck>
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct BadPixelMap {
+    pub width: usize,
+    pub height: usize,
+    pub defects: Vec<DefectPixel>,
+}
+
+impl BadPixelMap {
+    const MAGIC: &'static str = "BPM";
+
+    pub fn new(width: usize, height: usize, defects: Vec<DefectPixel>) -> Self {
+        BadPixelMap {
+            width,
+            height,
+            defects,
+        }
+    }
+
+    // ファイル読み込み (重複・範囲外の座標はエラー)
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SensorIoError> {
+        let f_read = BufReader::new(File::open(path)?);
+        let mut lines = f_read.lines();
+
+        let header = lines
+            .next()
+            .ok_or_else(|| SensorIoError::Parse("missing header".to_string()))??;
+        let fields: Vec<&str> = header.split_whitespace().collect();
+        if fields.len() != 4 || fields[0] != Self::MAGIC {
+            return Err(SensorIoError::Parse(format!("invalid header: {}", header)));
+        }
+        let width = Self::parse_usize(fields[1], 1)?;
+        let height = Self::parse_usize(fields[2], 1)?;
+        let count = Self::parse_usize(fields[3], 1)?;
+        // 重複不可のため件数は画素数以下 (破損したヘッダで巨大な確保をしない)
+        let pixel_count = width.checked_mul(height).ok_or_else(|| {
+            SensorIoError::Parse(format!("image size {}x{} is too large", width, height))
+        })?;
+        if count > pixel_count {
+            return Err(SensorIoError::Parse(format!(
+                "{} entries exceed the {} pixels of a {}x{} image",
+                count, pixel_count, width, height
+            )));
+        }
+
+        let mut defects = Vec::with_capacity(count);
+        let mut seen = HashSet::new();
+        for (i, line) in lines.enumerate() {
+            let line = line?;
+            let line_no = i + 2;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 3 {
+                return Err(SensorIoError::Parse(format!(
+                    "line {}: expected `x y type`",
+                    line_no
+                )));
+            }
+            let x = Self::parse_usize(fields[0], line_no)?;
+            let y = Self::parse_usize(fields[1], line_no)?;
+            let kind = Self::parse_kind(fields[2], line_no)?;
+            if x >= width || y >= height {
+                return Err(SensorIoError::Parse(format!(
+                    "line {}: ({}, {}) is out of range for {}x{}",
+                    line_no, x, y, width, height
+                )));
+            }
+            if !seen.insert((x, y)) {
+                return Err(SensorIoError::Parse(format!(
+                    "line {}: duplicate entry ({}, {})",
+                    line_no, x, y
+                )));
+            }
+            defects.push(DefectPixel::new(x, y, kind));
+        }
+        if defects.len() != count {
+            return Err(SensorIoError::Parse(format!(
+                "expected {} entries, found {}",
+                count,
+                defects.len()
+            )));
+        }
+
+        Ok(BadPixelMap {
+            width,
+            height,
+            defects,
+        })
+    }
+
+    // ファイル書き込み
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SensorIoError> {
+        let mut f_write = BufWriter::new(File::create(path)?);
+        writeln!(
+            f_write,
+            "{} {} {} {}",
+            Self::MAGIC,
+            self.width,
+            self.height,
+            self.defects.len()
+        )?;
+        for d in self.defects.iter() {
+            writeln!(f_write, "{} {} {}", d.x, d.y, Self::kind_name(d.kind))?;
+        }
+        f_write.flush()?;
+        Ok(())
+    }
+
+    // 画サイズ一致確認
+    pub fn validate_against<R: RawImage>(&self, raw: &R) -> Result<(), SensorIoError> {
+        check_shape((self.width, self.height), (raw.width(), raw.height()))
+    }
+
+    // 欠陥補正適用
+    pub fn apply_to<R: RawImage>(
+        &self,
+        raw: &mut R,
+        method: DefectCorrection,
+    ) -> Result<(), SensorIoError> {
+        self.validate_against(raw)?;
+        raw.correct_defects(&self.defects, method);
+        Ok(())
+    }
+
+    fn parse_usize(field: &str, line_no: usize) -> Result<usize, SensorIoError> {
+        field.parse::<usize>().map_err(|_| {
+            SensorIoError::Parse(format!("line {}: invalid number `{}`", line_no, field))
+        })
+    }
+
+    fn parse_kind(field: &str, line_no: usize) -> Result<DefectKind, SensorIoError> {
+        match field {
+            "hot" => Ok(DefectKind::Hot),
+            "dead" => Ok(DefectKind::Dead),
+            "stuck" => Ok(DefectKind::Stuck),
+            _ => Err(SensorIoError::Parse(format!(
+                "line {}: unknown defect type `{}`",
+                line_no, field
+            ))),
+        }
+    }
+
+    fn kind_name(kind: DefectKind) -> &'static str {
+        match kind {
+            DefectKind::Hot => "hot",
+            DefectKind::Dead => "dead",
+            DefectKind::Stuck => "stuck",
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BadPixelMap;
+    use crate::defect::{DefectCorrection, DefectKind, DefectPixel};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sensor_io_bpm_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        println!("bpm::test::test_roundtrip()  {{");
+
+        let bpm = BadPixelMap::new(
+            8,
+            6,
+            vec![
+                DefectPixel::new(1, 2, DefectKind::Hot),
+                DefectPixel::new(7, 5, DefectKind::Dead),
+                DefectPixel::new(0, 0, DefectKind::Stuck),
+            ],
+        );
+        let path = temp_path("roundtrip.bpm");
+        bpm.write(&path).unwrap();
+        println!(
+            "  [bpm][test_roundtrip()] file = \n{}",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        let bpm_read = BadPixelMap::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(bpm, bpm_read);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_read_errors() {
+        println!("bpm::test::test_read_errors()  {{");
+
+        let cases = [
+            ("duplicate.bpm", "BPM 4 3 2\n1 1 hot\n1 1 dead\n"),
+            ("out_of_range.bpm", "BPM 4 3 1\n4 0 hot\n"),
+            ("count.bpm", "BPM 4 3 2\n1 1 hot\n"),
+            ("kind.bpm", "BPM 4 3 1\n1 1 warm\n"),
+            ("huge_count.bpm", "BPM 4 3 18446744073709551615\n1 1 hot\n"),
+            ("huge_size.bpm", "BPM 18446744073709551615 2 1\n1 1 hot\n"),
+        ];
+        for (name, content) in cases {
+            let path = temp_path(name);
+            std::fs::write(&path, content).unwrap();
+            let result = BadPixelMap::read(&path);
+            std::fs::remove_file(&path).unwrap();
+            println!("  [bpm][test_read_errors()] {} => {:?}", name, result);
+            assert!(matches!(result, Err(SensorIoError::Parse(_))));
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_to() {
+        println!("bpm::test::test_apply_to()  {{");
+
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 8]; 6]);
+        *raw_in.pix_mut(2, 2) = 4095;
+        *raw_in.pix_mut(5, 3) = 0;
+        let bpm = BadPixelMap::new(
+            8,
+            6,
+            vec![
+                DefectPixel::new(2, 2, DefectKind::Hot),
+                DefectPixel::new(5, 3, DefectKind::Dead),
+            ],
+        );
+        bpm.apply_to(&mut raw_in, DefectCorrection::Median).unwrap();
+        println!(
+            "  [bpm][test_apply_to()] raw_in.data() = \n{}",
+            raw_in.data()
+        );
+        assert!(raw_in.data().iter().all(|v| *v == 100));
+
+        let mut raw_small = NDRaw::<u16>::new(4, 3);
+        let result = bpm.apply_to(&mut raw_small, DefectCorrection::Median);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
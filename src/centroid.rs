@@ -0,0 +1,172 @@
+use crate::raw::RawImage;
+use crate::rect::Rect;
+use num_traits::ToPrimitive;
+
+// 領域内の輝度重心(x, y) (画像座標, 重み合計0の場合はNaN)
+pub(crate) fn centroid_in<R: RawImage + ?Sized>(raw: &R, rect: Rect) -> (f64, f64) {
+    let (mut sum, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0);
+    for y in rect.y..rect.bottom() {
+        for x in rect.x..rect.right() {
+            let v = raw.pix(x, y).to_f64().unwrap();
+            sum += v;
+            sum_x += v * x as f64;
+            sum_y += v * y as f64;
+        }
+    }
+    if sum == 0.0 {
+        return (f64::NAN, f64::NAN);
+    }
+    (sum_x / sum, sum_y / sum)
+}
+
+// 局所最大検出
+//   (2*min_distance+1)四方の窓内で最大の画素を候補とし,
+//   輝度の高い順に, 採用済みピークからmin_distance画素以内(チェビシェフ距離)の候補を除外
+pub(crate) fn local_maxima<R: RawImage + ?Sized>(
+    raw: &R,
+    min_distance: usize,
+    threshold: R::Pixel,
+) -> Vec<(usize, usize)> {
+    let (width, height) = (raw.width(), raw.height());
+    let mut candidates = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let v = *raw.pix(x, y);
+            if v <= threshold {
+                continue;
+            }
+            let (x0, x1) = (
+                x.saturating_sub(min_distance),
+                (x + min_distance).min(width - 1),
+            );
+            let (y0, y1) = (
+                y.saturating_sub(min_distance),
+                (y + min_distance).min(height - 1),
+            );
+            let is_max = (y0..=y1).all(|ny| (x0..=x1).all(|nx| *raw.pix(nx, ny) <= v));
+            if is_max {
+                candidates.push((x, y));
+            }
+        }
+    }
+
+    // 同値は走査順を維持
+    candidates.sort_by(|a, b| {
+        raw.pix(b.0, b.1)
+            .partial_cmp(raw.pix(a.0, a.1))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    let mut peaks: Vec<(usize, usize)> = Vec::new();
+    for (x, y) in candidates {
+        let separated = peaks
+            .iter()
+            .all(|(px, py)| x.abs_diff(*px).max(y.abs_diff(*py)) > min_distance);
+        if separated {
+            peaks.push((x, y));
+        }
+    }
+    peaks
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+    use crate::rect::Rect;
+
+    fn gaussian_spot(width: usize, height: usize, center: (f64, f64), sigma: f64) -> Vec<Vec<f64>> {
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let dx = x as f64 - center.0;
+                        let dy = y as f64 - center.1;
+                        1000.0 * (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_find_centroid_subpixel() {
+        println!("centroid::test::test_find_centroid_subpixel()  {{");
+
+        let center = (20.3, 15.7);
+        let vec2d = gaussian_spot(40, 32, center, 2.0);
+        let nd = NDRaw::<f64>::new_from_vector2d(&vec2d);
+        let (cx, cy) = nd.find_centroid_subpixel();
+        println!(
+            "  [centroid][test_find_centroid_subpixel()] centroid = ({}, {})",
+            cx, cy
+        );
+        assert!((cx - center.0).abs() < 0.01);
+        assert!((cy - center.1).abs() < 0.01);
+
+        let mut na = NARaw::<f64>::new(40, 32);
+        for (y, row) in vec2d.iter().enumerate() {
+            for (x, v) in row.iter().enumerate() {
+                *na.pix_mut(x, y) = *v;
+            }
+        }
+        let (cx, cy) = na.find_centroid_subpixel();
+        assert!((cx - center.0).abs() < 0.01);
+        assert!((cy - center.1).abs() < 0.01);
+
+        let (cx, cy) = nd.find_centroid_in_roi(Rect::new(10, 6, 20, 20)).unwrap();
+        assert!((cx - center.0).abs() < 0.01);
+        assert!((cy - center.1).abs() < 0.01);
+        assert!(matches!(
+            nd.find_centroid_in_roi(Rect::new(30, 0, 20, 20)),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        let (cx, _) = NDRaw::<u16>::new(4, 4).find_centroid_subpixel();
+        assert!(cx.is_nan());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_centroid_single_pixel() {
+        println!("centroid::test::test_centroid_single_pixel()  {{");
+
+        let mut nd = NDRaw::<u16>::new(16, 12);
+        *nd.pix_mut(11, 4) = 4095;
+        assert_eq!((11.0, 4.0), nd.centroid());
+
+        let mut na = NARaw::<u16>::new(16, 12);
+        *na.pix_mut(3, 9) = 1;
+        assert_eq!((3.0, 9.0), na.centroid());
+
+        let (cx, cy) = NARaw::<u16>::new(4, 4).centroid();
+        assert!(cx.is_nan() && cy.is_nan());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_find_local_maxima() {
+        println!("centroid::test::test_find_local_maxima()  {{");
+
+        let mut raw = NDRaw::<u16>::new(16, 12);
+        *raw.pix_mut(3, 3) = 500;
+        *raw.pix_mut(5, 4) = 300; // (3, 3)から2画素 -> min_distance 3で除外
+        *raw.pix_mut(12, 8) = 800;
+        *raw.pix_mut(8, 10) = 50; // threshold以下
+                                  // 同値の平坦部は1点のみ
+        *raw.pix_mut(12, 1) = 400;
+        *raw.pix_mut(13, 1) = 400;
+
+        let peaks = raw.find_local_maxima(3, 100);
+        println!("  [centroid][test_find_local_maxima()] peaks = {:?}", peaks);
+        assert_eq!(vec![(12, 8), (3, 3), (12, 1)], peaks);
+
+        let peaks = raw.find_local_maxima(1, 100);
+        assert_eq!(vec![(12, 8), (3, 3), (12, 1), (5, 4)], peaks);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,743 @@
+use crate::bayer::{BayerChannel, BayerPattern, BayerPlanes};
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rgb::RgbSource;
+use crate::statistics::{statistics_of, Statistics};
+
+// CFAの色 (GrとGbを区別しない)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum CfaChannel {
+    R,
+    G,
+    B,
+    // 赤外 (RGB-IR)
+    Ir,
+    // 白/クリア (RGBW, RCCB)
+    White,
+    // 黄 (RYYB)
+    Yellow,
+}
+
+impl CfaChannel {
+    pub const ALL: [CfaChannel; 6] = [
+        CfaChannel::R,
+        CfaChannel::G,
+        CfaChannel::B,
+        CfaChannel::Ir,
+        CfaChannel::White,
+        CfaChannel::Yellow,
+    ];
+
+    // 可視光チャネル判定 (Ir以外)
+    pub fn is_visible(&self) -> bool {
+        *self != CfaChannel::Ir
+    }
+}
+
+// 色毎の統計量 (CfaChannelで参照, 配列にない色はcount 0)
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CfaStatistics {
+    stats: [Statistics<f64>; CfaChannel::ALL.len()],
+}
+
+impl CfaStatistics {
+    pub fn get(&self, channel: CfaChannel) -> &Statistics<f64> {
+        &self.stats[channel as usize]
+    }
+}
+
+impl std::ops::Index<CfaChannel> for CfaStatistics {
+    type Output = Statistics<f64>;
+
+    fn index(&self, channel: CfaChannel) -> &Statistics<f64> {
+        self.get(channel)
+    }
+}
+
+impl From<BayerChannel> for CfaChannel {
+    fn from(channel: BayerChannel) -> Self {
+        match channel {
+            BayerChannel::R => CfaChannel::R,
+            BayerChannel::Gr | BayerChannel::Gb => CfaChannel::G,
+            BayerChannel::B => CfaChannel::B,
+        }
+    }
+}
+
+// カラーフィルタ配列の並び
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum CfaLayout {
+    // 通常のBayer (2x2周期)
+    Bayer(BayerPattern),
+    // Quad Bayer / Tetracell (同色2x2ブロックをBayer状に並べた4x4周期)
+    QuadBayer(BayerPattern),
+    // RCCB (BayerのG位置がクリア)
+    Rccb(BayerPattern),
+    // RYYB (BayerのG位置が黄)
+    Ryyb(BayerPattern),
+    // 任意の繰り返しタイル
+    Tile(CfaTile),
+    // モノクロセンサ (カラーフィルタなし, 全画素White扱い, CFA処理は非対応)
+    Mono,
+}
+
+// 任意の繰り返しタイル (channelsは行優先, 長さwidth * height)
+//   生成・デシリアライズ時に検証するためフィールドは非公開
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "CfaTileFields")]
+pub struct CfaTile {
+    width: usize,
+    height: usize,
+    channels: Vec<CfaChannel>,
+}
+
+// デシリアライズ用 (検証前)
+#[derive(serde::Deserialize)]
+struct CfaTileFields {
+    width: usize,
+    height: usize,
+    channels: Vec<CfaChannel>,
+}
+
+impl CfaTile {
+    // コンストラクタ (サイズ0・要素数不一致はエラー)
+    pub fn new(
+        width: usize,
+        height: usize,
+        channels: Vec<CfaChannel>,
+    ) -> Result<Self, SensorIoError> {
+        if width == 0 || height == 0 || channels.len() != width * height {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "{} channels for {}x{} tile",
+                channels.len(),
+                width,
+                height
+            )));
+        }
+        Ok(CfaTile {
+            width,
+            height,
+            channels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn channels(&self) -> &[CfaChannel] {
+        &self.channels
+    }
+
+    // 座標(x, y)の色取得 (タイルの繰り返し)
+    pub fn color_at(&self, x: usize, y: usize) -> CfaChannel {
+        self.channels[(y % self.height) * self.width + x % self.width]
+    }
+}
+
+impl TryFrom<CfaTileFields> for CfaTile {
+    type Error = SensorIoError;
+
+    fn try_from(fields: CfaTileFields) -> Result<Self, Self::Error> {
+        CfaTile::new(fields.width, fields.height, fields.channels)
+    }
+}
+
+impl CfaLayout {
+    // 任意タイル指定コンストラクタ (サイズ0・要素数不一致はエラー)
+    pub fn tile(
+        width: usize,
+        height: usize,
+        channels: Vec<CfaChannel>,
+    ) -> Result<Self, SensorIoError> {
+        Ok(CfaLayout::Tile(CfaTile::new(width, height, channels)?))
+    }
+
+    // 標準的なX-Trans (6x6タイル, G: 20, R: 8, B: 8)
+    pub fn xtrans() -> Self {
+        use CfaChannel::{B, G, R};
+        CfaLayout::tile(
+            6,
+            6,
+            vec![
+                G, B, G, G, R, G, //
+                R, G, R, B, G, B, //
+                G, B, G, G, R, G, //
+                G, R, G, G, B, G, //
+                B, G, B, R, G, R, //
+                G, R, G, G, B, G, //
+            ],
+        )
+        .unwrap()
+    }
+
+    // 標準的な4x4 RGB-IR (IRは奇数行・奇数列)
+    //   B G R G
+    //   G I G I
+    //   R G B G
+    //   G I G I
+    pub fn rgbir() -> Self {
+        use CfaChannel::{Ir as I, B, G, R};
+        CfaLayout::tile(
+            4,
+            4,
+            vec![
+                B, G, R, G, //
+                G, I, G, I, //
+                R, G, B, G, //
+                G, I, G, I, //
+            ],
+        )
+        .unwrap()
+    }
+
+    // 座標(x, y)の色取得
+    pub fn color_at(&self, x: usize, y: usize) -> CfaChannel {
+        match self {
+            CfaLayout::Bayer(_) | CfaLayout::QuadBayer(_) => self.channel_at(x, y).unwrap().into(),
+            CfaLayout::Rccb(pattern) | CfaLayout::Ryyb(pattern) => {
+                match (self, pattern.channel_at(x, y)) {
+                    (_, BayerChannel::R) => CfaChannel::R,
+                    (_, BayerChannel::B) => CfaChannel::B,
+                    (CfaLayout::Rccb(_), _) => CfaChannel::White,
+                    _ => CfaChannel::Yellow,
+                }
+            }
+            CfaLayout::Tile(tile) => tile.color_at(x, y),
+            CfaLayout::Mono => CfaChannel::White,
+        }
+    }
+
+    // 座標(x, y)のBayerチャネル取得 (RGB Bayer系以外はNone)
+    pub fn channel_at(&self, x: usize, y: usize) -> Option<BayerChannel> {
+        match self {
+            CfaLayout::Bayer(pattern) => Some(pattern.channel_at(x, y)),
+            CfaLayout::QuadBayer(pattern) => Some(pattern.channel_at(x / 2, y / 2)),
+            CfaLayout::Rccb(_) | CfaLayout::Ryyb(_) | CfaLayout::Tile(_) | CfaLayout::Mono => None,
+        }
+    }
+
+    // 繰り返し周期 (width, height)
+    pub fn period(&self) -> (usize, usize) {
+        match self {
+            CfaLayout::Bayer(_) | CfaLayout::Rccb(_) | CfaLayout::Ryyb(_) => (2, 2),
+            CfaLayout::QuadBayer(_) => (4, 4),
+            CfaLayout::Tile(tile) => (tile.width(), tile.height()),
+            CfaLayout::Mono => (1, 1),
+        }
+    }
+
+    // Bayer系処理 (デモザイク等) 用のBayerPattern取得 (RCCB/RYYB/Tile/Monoは非対応エラー)
+    pub fn bayer_pattern(&self) -> Result<BayerPattern, SensorIoError> {
+        match self {
+            CfaLayout::Bayer(pattern) | CfaLayout::QuadBayer(pattern) => Ok(*pattern),
+            CfaLayout::Rccb(_) | CfaLayout::Ryyb(_) | CfaLayout::Mono => Err(
+                SensorIoError::Unsupported(format!("{:?} is not an RGB Bayer layout", self)),
+            ),
+            CfaLayout::Tile(tile) => Err(SensorIoError::Unsupported(format!(
+                "{}x{} CFA tile is not a Bayer layout",
+                tile.width(),
+                tile.height()
+            ))),
+        }
+    }
+}
+
+// RGB画像 => CFAモザイク (ビット深度はRgbSource, Tの範囲に飽和)
+fn convert_rgb_to_cfa<T: PixelType>(img_in: &image::DynamicImage, layout: &CfaLayout) -> NDRaw<T> {
+    let source = RgbSource::new::<T>(img_in);
+    let (width, height) = (img_in.width() as usize, img_in.height() as usize);
+    let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+        let [r, g, b] = source.rgb_at(x, y);
+        let v = match layout.color_at(x, y) {
+            CfaChannel::R => r,
+            CfaChannel::G => g,
+            CfaChannel::B => b,
+            // RGB画像に赤外の情報はない
+            CfaChannel::Ir => 0.0,
+            CfaChannel::White => (r + g + b) / 3.0,
+            // 黄フィルタはR + Gを通す (値域を保つため平均で近似)
+            CfaChannel::Yellow => (r + g) / 2.0,
+        };
+        T::from_f64_saturating(v)
+    });
+    NDRaw::from_ndarray(data)
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // image(RGB)変換コンストラクタ (CFA配列指定)
+    //   RGBから合成できない画素は近似: White(クリア)はRGBの平均, YellowはRとGの平均, Irは0
+    pub fn new_from_rgbimage_cfa(path_image_in: String, layout: &CfaLayout) -> Self {
+        let img_in = image::open(path_image_in).unwrap();
+        convert_rgb_to_cfa(&img_in, layout)
+    }
+
+    // 色毎の統計量 (GrとGbはまとめてG, 配列にない色はcount 0)
+    pub fn compute_cfa_statistics(&self, layout: &CfaLayout) -> CfaStatistics {
+        CfaStatistics {
+            stats: CfaChannel::ALL.map(|channel| {
+                statistics_of(
+                    self.data
+                        .indexed_iter()
+                        .filter(|((y, x), _)| layout.color_at(*x, *y) == channel)
+                        .map(|(_, v)| v.to_f64().unwrap()),
+                )
+            }),
+        }
+    }
+
+    // 色毎の画素値変換 (f(色, 値))
+    pub fn map_cfa_channels(&mut self, layout: &CfaLayout, f: impl Fn(CfaChannel, T) -> T) {
+        for ((y, x), v) in self.data.indexed_iter_mut() {
+            *v = f(layout.color_at(x, y), *v);
+        }
+    }
+
+    // 色毎のゲイン適用 (指定のない色はそのまま, 結果は最近接丸め・型の範囲に飽和)
+    pub fn apply_cfa_gains(&mut self, layout: &CfaLayout, gains: &[(CfaChannel, f64)]) {
+        self.map_cfa_channels(layout, |channel, v| {
+            match gains.iter().find(|(c, _)| *c == channel) {
+                Some((_, gain)) => T::from_f64_saturating(v.to_f64().unwrap() * gain),
+                None => v,
+            }
+        });
+    }
+
+    // 1色の面分離
+    //   周期内のその色の画素が等間隔の格子 (x座標の組 × y座標の組) をなす場合のみ対応 (それ以外・Monoは非対応エラー)
+    pub fn extract_cfa_plane(
+        &self,
+        layout: &CfaLayout,
+        channel: CfaChannel,
+    ) -> Result<NDRaw<T>, SensorIoError> {
+        if *layout == CfaLayout::Mono {
+            return Err(SensorIoError::Unsupported(
+                "monochrome layout has no CFA planes".to_string(),
+            ));
+        }
+        let (period_x, period_y) = layout.period();
+        let sites: Vec<(usize, usize)> = (0..period_y)
+            .flat_map(|y| (0..period_x).map(move |x| (x, y)))
+            .filter(|&(x, y)| layout.color_at(x, y) == channel)
+            .collect();
+        let unsupported = || {
+            SensorIoError::Unsupported(format!(
+                "{:?} sites do not form a regular grid in the CFA period",
+                channel
+            ))
+        };
+        // 等間隔の座標の組 => (先頭, 間隔)
+        let lattice = |mut coords: Vec<usize>, period: usize| {
+            coords.sort_unstable();
+            coords.dedup();
+            let step = period / coords.len().max(1);
+            let regular = !coords.is_empty()
+                && period.is_multiple_of(coords.len())
+                && coords
+                    .iter()
+                    .enumerate()
+                    .all(|(i, c)| *c == coords[0] + i * step);
+            regular.then_some((coords[0], step))
+        };
+        let xs: Vec<usize> = sites.iter().map(|s| s.0).collect();
+        let ys: Vec<usize> = sites.iter().map(|s| s.1).collect();
+        let ((x0, step_x), (y0, step_y)) = match (lattice(xs, period_x), lattice(ys, period_y)) {
+            (Some(lx), Some(ly)) => (lx, ly),
+            _ => return Err(unsupported()),
+        };
+        if sites.len() != (period_x / step_x) * (period_y / step_y) {
+            return Err(unsupported());
+        }
+
+        let width = (self.width() + step_x - 1 - x0) / step_x;
+        let height = (self.height() + step_y - 1 - y0) / step_y;
+        let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            self.data[[y0 + y * step_y, x0 + x * step_x]]
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    // チャネル面分離
+    //   Bayer    : extract_bayer_planesと同じ
+    //   QuadBayer: 同色2x2ブロックを隣接させたまま詰めた面 (1/4解像度)
+    //   その他   : 非対応エラー
+    pub fn extract_cfa_planes(&self, layout: &CfaLayout) -> Result<BayerPlanes<T>, SensorIoError> {
+        let pattern = match layout {
+            CfaLayout::Bayer(pattern) => return Ok(self.extract_bayer_planes(*pattern)),
+            CfaLayout::QuadBayer(pattern) => *pattern,
+            CfaLayout::Rccb(_) | CfaLayout::Ryyb(_) | CfaLayout::Tile(_) | CfaLayout::Mono => {
+                return Err(SensorIoError::Unsupported(
+                    "channel planes require a Bayer layout".to_string(),
+                ))
+            }
+        };
+        let plane = |channel: BayerChannel| {
+            let (ox, oy) = pattern.offset(channel);
+            // 面の座標 => 元画像の座標 (4画素周期の中の2画素ブロック)
+            let to_src = |p: usize, o: usize| (p / 2) * 4 + o * 2 + p % 2;
+            let count = |len: usize, o: usize| (0..len).filter(|i| (i / 2) % 2 == o).count();
+            let (width, height) = (count(self.width(), ox), count(self.height(), oy));
+            let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+                self.data[[to_src(y, oy), to_src(x, ox)]]
+            });
+            NDRaw::from_ndarray(data)
+        };
+        Ok(BayerPlanes {
+            r: plane(BayerChannel::R),
+            gr: plane(BayerChannel::Gr),
+            gb: plane(BayerChannel::Gb),
+            b: plane(BayerChannel::B),
+        })
+    }
+
+    // Quad Bayer => Bayer リモザイク (単純な画素入れ替え, 同じBayerPatternの全解像度モザイク)
+    //   4x4周期毎に1列目と2列目, 1行目と2行目を入れ替える (4に満たない端数の行/列はそのまま)
+    pub fn remosaic_quad_to_bayer(&self) -> Self {
+        let (width, height) = (self.width(), self.height());
+        let shuffle = |p: usize, len: usize| {
+            if p / 4 * 4 + 4 > len {
+                return p;
+            }
+            match p % 4 {
+                1 => p + 1,
+                2 => p - 1,
+                _ => p,
+            }
+        };
+        let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            self.data[[shuffle(y, height), shuffle(x, width)]]
+        });
+        NDRaw::from_ndarray(data)
+    }
+
+    // Quad Bayer => Bayer ビニング (同色2x2ブロックの平均, 最近接丸め, 1/2解像度, 奇数の端数は切り捨て)
+    pub fn bin_quad_to_bayer(&self) -> Self {
+        let data =
+            ndarray::Array2::from_shape_fn((self.height() / 2, self.width() / 2), |(y, x)| {
+                let sum: f64 = self
+                    .data
+                    .slice(ndarray::s![y * 2..y * 2 + 2, x * 2..x * 2 + 2])
+                    .iter()
+                    .map(|v| v.to_f64().unwrap())
+                    .sum();
+                T::from_f64_saturating(sum / 4.0)
+            });
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CfaChannel, CfaLayout};
+    use crate::bayer::{BayerChannel, BayerPattern};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 色毎に値域の異なるQuad Bayerモザイク (R: 1000台, Gr: 2000台, Gb: 3000台, B: 4000台, 下位は座標 x + y * 10)
+    fn labeled_quad(layout: &CfaLayout, width: usize, height: usize) -> NDRaw<u16> {
+        let mut raw = NDRaw::<u16>::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let channel = layout.channel_at(x, y).unwrap() as u16;
+                *raw.pix_mut(x, y) = (channel + 1) * 1000 + (x + y * 10) as u16;
+            }
+        }
+        raw
+    }
+
+    #[test]
+    fn test_cfa_layout() {
+        println!("cfa::test::test_cfa_layout()  {{");
+
+        let layout = CfaLayout::QuadBayer(BayerPattern::Rggb);
+        assert_eq!((4, 4), layout.period());
+        assert_eq!(Some(BayerChannel::R), layout.channel_at(1, 1));
+        assert_eq!(Some(BayerChannel::Gr), layout.channel_at(2, 1));
+        assert_eq!(Some(BayerChannel::Gb), layout.channel_at(1, 2));
+        assert_eq!(Some(BayerChannel::B), layout.channel_at(3, 3));
+        assert_eq!(Some(BayerChannel::R), layout.channel_at(5, 4));
+        assert_eq!(
+            Some(BayerChannel::Gr),
+            CfaLayout::Bayer(BayerPattern::Rggb).channel_at(1, 0)
+        );
+
+        // RGB画像からのモザイク化
+        let img = image::ImageBuffer::from_pixel(8, 4, image::Rgb([10u8, 20, 30]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_cfa_rgb_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let raw = NDRaw::<u16>::new_from_rgbimage_cfa(path.to_str().unwrap().to_string(), &layout);
+        std::fs::remove_file(&path).unwrap();
+        println!("  [cfa][test_cfa_layout()] raw = \n{}", raw.data());
+        assert_eq!(
+            &[10, 10, 20, 20, 10, 10, 20, 20],
+            raw.data().row(1).as_slice().unwrap()
+        );
+        assert_eq!(
+            &[20, 20, 30, 30, 20, 20, 30, 30],
+            raw.data().row(2).as_slice().unwrap()
+        );
+
+        // チャネル面分離 (同色ブロックは隣接したまま)
+        let raw = labeled_quad(&layout, 8, 8);
+        let planes = raw.extract_cfa_planes(&layout).unwrap();
+        assert_eq!((4, 4), (planes.r.width(), planes.r.height()));
+        assert!(planes.r.data().iter().all(|v| *v / 1000 == 1));
+        assert!(planes.b.data().iter().all(|v| *v / 1000 == 4));
+        // R面(2, 1) = 元画像(4, 1)
+        assert_eq!(1000 + 4 + 10, *planes.r.pix(2, 1));
+        // Gb面(1, 3) = 元画像(1, 7)
+        assert_eq!(3000 + 1 + 70, *planes.gb.pix(1, 3));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_remosaic_quad_to_bayer() {
+        println!("cfa::test::test_remosaic_quad_to_bayer()  {{");
+
+        let raw = labeled_quad(&CfaLayout::QuadBayer(BayerPattern::Rggb), 8, 4);
+        let bayer = raw.remosaic_quad_to_bayer();
+        println!(
+            "  [cfa][test_remosaic_quad_to_bayer()] bayer = \n{}",
+            bayer.data()
+        );
+        // 全画素が通常のRGGBの色になる
+        for y in 0..4 {
+            for x in 0..8 {
+                let channel = BayerPattern::Rggb.channel_at(x, y) as u16;
+                assert_eq!(channel + 1, *bayer.pix(x, y) / 1000);
+            }
+        }
+        // 入れ替え先: (1, 0) <= (2, 0), (0, 1) <= (0, 2), (1, 1) <= (2, 2), (5, 2) <= (6, 1)
+        assert_eq!(2000 + 2, *bayer.pix(1, 0));
+        assert_eq!(3000 + 20, *bayer.pix(0, 1));
+        assert_eq!(4000 + 22, *bayer.pix(1, 1));
+        assert_eq!(2000 + 16, *bayer.pix(5, 2));
+        // 入れ替えない画素
+        assert_eq!(1000, *bayer.pix(0, 0));
+        assert_eq!(4000 + 33, *bayer.pix(3, 3));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bin_quad_to_bayer() {
+        println!("cfa::test::test_bin_quad_to_bayer()  {{");
+
+        let raw = labeled_quad(&CfaLayout::QuadBayer(BayerPattern::Grbg), 8, 4);
+        let binned = raw.bin_quad_to_bayer();
+        println!(
+            "  [cfa][test_bin_quad_to_bayer()] binned = \n{}",
+            binned.data()
+        );
+        assert_eq!((4, 2), (binned.width(), binned.height()));
+        for y in 0..2 {
+            for x in 0..4 {
+                let channel = BayerPattern::Grbg.channel_at(x, y) as u16;
+                // 2x2ブロックの座標ラベル平均: 左上 + (0 + 1 + 10 + 11) / 4 = 左上 + 5.5 -> 丸めで +6
+                let top_left = (x * 2 + y * 20) as u16;
+                assert_eq!((channel + 1) * 1000 + top_left + 6, *binned.pix(x, y));
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_xtrans() {
+        println!("cfa::test::test_xtrans()  {{");
+
+        let layout = CfaLayout::xtrans();
+        assert_eq!((6, 6), layout.period());
+
+        // 1周期分の色分類
+        let expected = ["GBGGRG", "RGRBGB", "GBGGRG", "GRGGBG", "BGBRGR", "GRGGBG"];
+        for (y, row) in expected.iter().enumerate() {
+            let classified: String = (0..6)
+                .map(|x| match layout.color_at(x, y) {
+                    CfaChannel::R => 'R',
+                    CfaChannel::G => 'G',
+                    CfaChannel::B => 'B',
+                    _ => '?',
+                })
+                .collect();
+            println!("  [cfa][test_xtrans()] row {} = {}", y, classified);
+            assert_eq!(*row, classified);
+            // 次の周期も同じ
+            for x in 0..6 {
+                assert_eq!(layout.color_at(x, y), layout.color_at(x + 6, y + 12));
+            }
+        }
+        let count = |c: CfaChannel| {
+            (0..36)
+                .filter(|i| layout.color_at(i % 6, i / 6) == c)
+                .count()
+        };
+        assert_eq!(
+            (8, 20, 8),
+            (
+                count(CfaChannel::R),
+                count(CfaChannel::G),
+                count(CfaChannel::B)
+            )
+        );
+
+        // 非Bayer処理は非対応エラー
+        assert!(matches!(
+            layout.bayer_pattern(),
+            Err(SensorIoError::Unsupported(_))
+        ));
+        assert!(matches!(
+            NDRaw::<u16>::new(12, 12).extract_cfa_planes(&layout),
+            Err(SensorIoError::Unsupported(_))
+        ));
+        assert_eq!(None, layout.channel_at(0, 0));
+        assert!(matches!(
+            CfaLayout::tile(2, 2, vec![CfaChannel::R]),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        // シリアライズ往復, 不正なタイルはデシリアライズ時にエラー
+        let json = serde_json::to_string(&layout).unwrap();
+        assert_eq!(layout, serde_json::from_str::<CfaLayout>(&json).unwrap());
+        for invalid in [
+            r#"{"Tile":{"width":0,"height":0,"channels":[]}}"#,
+            r#"{"Tile":{"width":2,"height":2,"channels":["R"]}}"#,
+        ] {
+            assert!(serde_json::from_str::<CfaLayout>(invalid).is_err());
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_cfa_statistics() {
+        println!("cfa::test::test_compute_cfa_statistics()  {{");
+
+        // 一様なRGB画像をX-Transでモザイク化 => 色毎の平均は元のRGB
+        let layout = CfaLayout::xtrans();
+        let img = image::ImageBuffer::from_pixel(12, 12, image::Rgb([100u8, 150, 200]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_cfa_xtrans_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let raw = NDRaw::<u16>::new_from_rgbimage_cfa(path.to_str().unwrap().to_string(), &layout);
+        std::fs::remove_file(&path).unwrap();
+
+        let stats = raw.compute_cfa_statistics(&layout);
+        let (r, g, b, ir) = (
+            stats[CfaChannel::R],
+            stats[CfaChannel::G],
+            stats[CfaChannel::B],
+            stats[CfaChannel::Ir],
+        );
+        println!(
+            "  [cfa][test_compute_cfa_statistics()] mean = ({}, {}, {})",
+            r.mean, g.mean, b.mean
+        );
+        assert_eq!((100.0, 150.0, 200.0), (r.mean, g.mean, b.mean));
+        assert_eq!((32, 80, 32), (r.count, g.count, b.count));
+        assert_eq!(0.0, g.std_dev);
+        assert_eq!(0, ir.count);
+
+        // Bayerでも同様 (GrとGbはまとめてG)
+        let mut raw = NDRaw::<u16>::new(4, 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                *raw.pix_mut(x, y) = (BayerPattern::Rggb.channel_at(x, y) as u16 + 1) * 10;
+            }
+        }
+        let stats = raw.compute_cfa_statistics(&CfaLayout::Bayer(BayerPattern::Rggb));
+        assert_eq!(
+            (10.0, 25.0, 40.0),
+            (
+                stats[CfaChannel::R].mean,
+                stats.get(CfaChannel::G).mean,
+                stats[CfaChannel::B].mean
+            )
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rccb_ryyb() {
+        println!("cfa::test::test_rccb_ryyb()  {{");
+
+        let rccb = CfaLayout::Rccb(BayerPattern::Rggb);
+        let ryyb = CfaLayout::Ryyb(BayerPattern::Bggr);
+        assert_eq!(
+            [
+                CfaChannel::R,
+                CfaChannel::White,
+                CfaChannel::White,
+                CfaChannel::B
+            ],
+            [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| rccb.color_at(x, y))
+        );
+        assert_eq!(
+            [
+                CfaChannel::B,
+                CfaChannel::Yellow,
+                CfaChannel::Yellow,
+                CfaChannel::R
+            ],
+            [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| ryyb.color_at(x, y))
+        );
+        assert_eq!((2, 2), rccb.period());
+        assert_eq!(None, rccb.channel_at(1, 0));
+        assert!(matches!(
+            ryyb.bayer_pattern(),
+            Err(SensorIoError::Unsupported(_))
+        ));
+
+        // RGB画像からの合成 (C = RGBの平均, Y = RとGの平均)
+        let img = image::ImageBuffer::from_pixel(4, 4, image::Rgb([90u8, 150, 30]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_cfa_rccb_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+        let mut raw_rccb = NDRaw::<u16>::new_from_rgbimage_cfa(path_str.clone(), &rccb);
+        let raw_ryyb = NDRaw::<u16>::new_from_rgbimage_cfa(path_str, &ryyb);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [cfa][test_rccb_ryyb()] rccb = \n{}\n  ryyb = \n{}",
+            raw_rccb.data(),
+            raw_ryyb.data()
+        );
+        assert_eq!(
+            [90, 90, 90, 30],
+            [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(x, y)| *raw_rccb.pix(x, y))
+        );
+        assert_eq!(120, *raw_ryyb.pix(1, 0));
+        assert_eq!(90, *raw_ryyb.pix(1, 1));
+
+        // 色毎の統計量とゲイン
+        let stats = raw_ryyb.compute_cfa_statistics(&ryyb);
+        assert_eq!(
+            (8, 120.0),
+            (
+                stats[CfaChannel::Yellow].count,
+                stats[CfaChannel::Yellow].mean
+            )
+        );
+        assert_eq!(0, stats[CfaChannel::G].count);
+        raw_rccb.apply_cfa_gains(&rccb, &[(CfaChannel::White, 0.5), (CfaChannel::B, 2.0)]);
+        let stats = raw_rccb.compute_cfa_statistics(&rccb);
+        assert_eq!(
+            (90.0, 45.0, 60.0),
+            (
+                stats[CfaChannel::R].mean,
+                stats[CfaChannel::White].mean,
+                stats[CfaChannel::B].mean
+            )
+        );
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,137 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// ゼブラ表示の縞の周期(画素)と色
+const ZEBRA_PERIOD: usize = 8;
+const ZEBRA_COLORS: [[u8; 3]; 2] = [[255, 0, 0], [255, 255, 0]];
+
+// チャネル別白飛び画素数 ([R, Gr, Gb, B]の順)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct ClippingStats {
+    pub clipped: [usize; 4],
+    pub total: [usize; 4],
+}
+
+impl ClippingStats {
+    // チャネルの白飛び画素率 (画素がない場合は0)
+    pub fn fraction(&self, channel: BayerChannel) -> f64 {
+        let i = channel as usize;
+        self.clipped[i] as f64 / self.total[i].max(1) as f64
+    }
+
+    // 全体の白飛び画素数
+    pub fn total_clipped(&self) -> usize {
+        self.clipped.iter().sum()
+    }
+
+    // 全体の白飛び画素率 (画素がない場合は0)
+    pub fn total_fraction(&self) -> f64 {
+        self.total_clipped() as f64 / self.total.iter().sum::<usize>().max(1) as f64
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 白飛びマスク (threshold以上の画素がtrue, [[y, x]]でアクセス)
+    pub fn clipping_mask(&self, threshold: T) -> ndarray::Array2<bool> {
+        self.data.mapv(|p| p >= threshold)
+    }
+
+    // チャネル別白飛び統計 (threshold以上の画素を白飛びとする)
+    pub fn clipping_stats(&self, threshold: T, pattern: BayerPattern) -> ClippingStats {
+        let mut stats = ClippingStats {
+            clipped: [0; 4],
+            total: [0; 4],
+        };
+        for ((y, x), p) in self.data.indexed_iter() {
+            let i = pattern.channel_at(x, y) as usize;
+            stats.total[i] += 1;
+            if *p >= threshold {
+                stats.clipped[i] += 1;
+            }
+        }
+        stats
+    }
+
+    // ゼブラ表示 (0〜thresholdをグレーで表示し, 白飛び画素を斜め縞で塗る)
+    pub fn zebra_overlay(&self, threshold: T) -> image::RgbImage {
+        let scale = 255.0 / threshold.to_f64().unwrap().max(f64::MIN_POSITIVE);
+        image::RgbImage::from_fn(self.width() as u32, self.height() as u32, |x, y| {
+            let (x, y) = (x as usize, y as usize);
+            let p = self.data[[y, x]];
+            if p >= threshold {
+                image::Rgb(ZEBRA_COLORS[(x + y) / (ZEBRA_PERIOD / 2) % 2])
+            } else {
+                let v = (p.to_f64().unwrap() * scale).clamp(0.0, 255.0).round() as u8;
+                image::Rgb([v, v, v])
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::{BayerChannel, BayerPattern};
+    use crate::ndraw::NDRaw;
+
+    // (4, 2)から6x4の領域が白飛びした12x8画像
+    fn clipped_frame() -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| {
+                (0..12)
+                    .map(|x| {
+                        if (4..10).contains(&x) && (2..6).contains(&y) {
+                            4095
+                        } else {
+                            (x * 100 + y * 10) as u16
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_clipping_stats() {
+        println!("clipping::test::test_clipping_stats()  {{");
+
+        let raw = clipped_frame();
+        let mask = raw.clipping_mask(4000);
+        assert_eq!(24, mask.iter().filter(|m| **m).count());
+        assert!(mask[[2, 4]] && mask[[5, 9]]);
+        assert!(!mask[[1, 4]] && !mask[[2, 10]]);
+
+        let stats = raw.clipping_stats(4000, BayerPattern::Rggb);
+        println!("  [clipping][test_clipping_stats()] stats = {:?}", stats);
+        assert_eq!([6, 6, 6, 6], stats.clipped);
+        assert_eq!([24, 24, 24, 24], stats.total);
+        assert_eq!(0.25, stats.fraction(BayerChannel::R));
+        assert_eq!(24, stats.total_clipped());
+        assert_eq!(0.25, stats.total_fraction());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_zebra_overlay() {
+        println!("clipping::test::test_zebra_overlay()  {{");
+
+        let raw = clipped_frame();
+        let mask = raw.clipping_mask(4000);
+        let overlay = raw.zebra_overlay(4000);
+        assert_eq!((12, 8), overlay.dimensions());
+
+        // 白飛び領域のみ色付き(非グレー)
+        for ((y, x), clipped) in mask.indexed_iter() {
+            let [r, g, b] = overlay.get_pixel(x as u32, y as u32).0;
+            assert_eq!(*clipped, !(r == g && g == b), "(x, y) = ({}, {})", x, y);
+        }
+        // 縞は2色
+        assert_ne!(overlay.get_pixel(4, 2), overlay.get_pixel(8, 2));
+        // 110 * 255 / 4000 = 7.0
+        assert_eq!(image::Rgb([7, 7, 7]), *overlay.get_pixel(1, 1));
+
+        println!("}}");
+    }
+}
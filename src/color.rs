@@ -0,0 +1,380 @@
+use crate::pixel::PixelType;
+use crate::rgb::RgbRaw;
+
+// カラーマトリクスの行正規化
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum CcmNormalization {
+    // 指定値のまま
+    None,
+    // 各行の和を1にして白(R = G = B)を保つ (和が0の行はそのまま)
+    PreserveWhite,
+}
+
+// カラーマトリクス適用 ([R', G', B'] = matrix * [R, G, B], 計算はf64, 結果は0〜white_levelにクランプ)
+pub fn apply_ccm<T: PixelType>(
+    rgb: &mut RgbRaw<T>,
+    matrix: [[f64; 3]; 3],
+    white_level: T,
+    normalization: CcmNormalization,
+) {
+    let mut matrix = matrix;
+    if normalization == CcmNormalization::PreserveWhite {
+        for row in matrix.iter_mut() {
+            let sum: f64 = row.iter().sum();
+            if sum != 0.0 {
+                row.iter_mut().for_each(|m| *m /= sum);
+            }
+        }
+    }
+
+    let white_level = white_level.to_f64().unwrap();
+    let (width, height) = (rgb.width(), rgb.height());
+    for y in 0..height {
+        for x in 0..width {
+            let input = rgb.pix(x, y).map(|p| p.to_f64().unwrap());
+            let output = matrix.map(|row| {
+                let v: f64 = row.iter().zip(input.iter()).map(|(m, p)| m * p).sum();
+                T::from_f64_saturating(v.clamp(0.0, white_level))
+            });
+            rgb.set_pix(x, y, output);
+        }
+    }
+}
+
+// 白飛びハイライトの復元方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum HighlightReconstruction {
+    // 白飛びチャネルを含む画素を全チャネルwhite_levelにする (無彩色化)
+    Clip,
+    // 白飛びしていないチャネルから, 周囲の白飛びしていない画素の色比で白飛びチャネルを推定
+    //   推定値はwhite_level未満にしない (白飛びチャネルの真値はwhite_level以上)
+    //   全チャネル白飛び・画像内に白飛びしていない画素がない場合はClipと同じ
+    ClipAndPropagate,
+}
+
+// 白飛び画素毎の参照色 (白飛びしていない画素から外側の層より1画素ずつ伝搬, O(画素数))
+//   白飛びしていない画素は自身の値, 距離dの画素は距離d - 1の8近傍の参照色の平均, 到達しない画素はNone
+fn propagate_reference_colors<T: PixelType>(
+    rgb: &RgbRaw<T>,
+    is_clipped: impl Fn([T; 3]) -> bool,
+) -> ndarray::Array2<Option<[f64; 3]>> {
+    let (width, height) = (rgb.width(), rgb.height());
+    let mut distance = ndarray::Array2::<usize>::from_elem((height, width), usize::MAX);
+    let mut reference = ndarray::Array2::<Option<[f64; 3]>>::from_elem((height, width), None);
+    let mut queue = std::collections::VecDeque::new();
+    for y in 0..height {
+        for x in 0..width {
+            let p = rgb.pix(x, y);
+            if !is_clipped(p) {
+                distance[[y, x]] = 0;
+                reference[[y, x]] = Some(p.map(|v| v.to_f64().unwrap()));
+                queue.push_back((x, y));
+            }
+        }
+    }
+
+    let neighbors = |x: usize, y: usize| {
+        (y.saturating_sub(1)..(y + 2).min(height))
+            .flat_map(move |ny| (x.saturating_sub(1)..(x + 2).min(width)).map(move |nx| (nx, ny)))
+            .filter(move |&n| n != (x, y))
+    };
+    // 幅優先探索の順 = 距離の昇順なので, 1つ内側の層は外側の層の確定後に計算される
+    while let Some((x, y)) = queue.pop_front() {
+        let d = distance[[y, x]];
+        if d > 0 {
+            let (mut sum, mut count) = ([0.0; 3], 0);
+            for (nx, ny) in neighbors(x, y) {
+                if distance[[ny, nx]] == d - 1 {
+                    let r = reference[[ny, nx]].unwrap();
+                    sum.iter_mut().zip(r.iter()).for_each(|(s, v)| *s += v);
+                    count += 1;
+                }
+            }
+            reference[[y, x]] = Some(sum.map(|s| s / count as f64));
+        }
+        for (nx, ny) in neighbors(x, y) {
+            if distance[[ny, nx]] == usize::MAX {
+                distance[[ny, nx]] = d + 1;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+    reference
+}
+
+// 白飛びハイライト復元 (white_level以上のチャネルを白飛びとする, WBゲイン後のマゼンタ被り対策)
+pub fn reconstruct_highlights<T: PixelType>(
+    rgb: &mut RgbRaw<T>,
+    white_level: T,
+    method: HighlightReconstruction,
+) {
+    let (width, height) = (rgb.width(), rgb.height());
+    let original = rgb.clone();
+    let is_clipped = |p: [T; 3]| p.map(|v| v >= white_level);
+    let white = [white_level; 3];
+    let reference = match method {
+        HighlightReconstruction::Clip => None,
+        HighlightReconstruction::ClipAndPropagate => {
+            Some(propagate_reference_colors(&original, |p| {
+                is_clipped(p).contains(&true)
+            }))
+        }
+    };
+    let white_level_f64 = white_level.to_f64().unwrap();
+
+    for y in 0..height {
+        for x in 0..width {
+            let clipped = is_clipped(original.pix(x, y));
+            if !clipped.contains(&true) {
+                continue;
+            }
+            let ring = reference.as_ref().and_then(|r| r[[y, x]]);
+            let Some(ring) = ring.filter(|_| clipped.contains(&false)) else {
+                rgb.set_pix(x, y, white);
+                continue;
+            };
+
+            // 白飛びしていないチャネルの和と周囲の色比から推定
+            let p = original.pix(x, y).map(|v| v.to_f64().unwrap());
+            let (mut ref_ring, mut ref_pix) = (0.0, 0.0);
+            for c in 0..3 {
+                if !clipped[c] {
+                    ref_ring += ring[c];
+                    ref_pix += p[c];
+                }
+            }
+            if ref_ring <= 0.0 {
+                rgb.set_pix(x, y, white);
+                continue;
+            }
+            let output: [T; 3] = std::array::from_fn(|c| {
+                if clipped[c] {
+                    let estimate = ref_pix * ring[c] / ref_ring;
+                    T::from_f64_saturating(estimate.max(white_level_f64))
+                } else {
+                    original.pix(x, y)[c]
+                }
+            });
+            rgb.set_pix(x, y, output);
+        }
+    }
+}
+
+// 倍率色収差補正 (G基準, R/B面をサブ画素シフトして位置合わせ)
+//   r_shift/b_shift: Gに対するR/Bのずれ(x, y) [画素], 出力(x, y)には入力(x + dx, y + dy)をバイリニア補間で格納
+//   画像外を参照する画素は0
+pub fn correct_chromatic_aberration(
+    rgb: &image::RgbImage,
+    r_shift: (f32, f32),
+    b_shift: (f32, f32),
+) -> image::RgbImage {
+    let (width, height) = rgb.dimensions();
+    let sample = |channel: usize, x: f32, y: f32| -> u8 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let value_at = |xi: f32, yi: f32| -> f32 {
+            if xi < 0.0 || yi < 0.0 || xi >= width as f32 || yi >= height as f32 {
+                0.0
+            } else {
+                rgb.get_pixel(xi as u32, yi as u32)[channel] as f32
+            }
+        };
+        let v = value_at(x0, y0) * (1.0 - fx) * (1.0 - fy)
+            + value_at(x0 + 1.0, y0) * fx * (1.0 - fy)
+            + value_at(x0, y0 + 1.0) * (1.0 - fx) * fy
+            + value_at(x0 + 1.0, y0 + 1.0) * fx * fy;
+        v.round().clamp(0.0, 255.0) as u8
+    };
+    image::RgbImage::from_fn(width, height, |x, y| {
+        let (xf, yf) = (x as f32, y as f32);
+        image::Rgb([
+            sample(0, xf + r_shift.0, yf + r_shift.1),
+            rgb.get_pixel(x, y)[1],
+            sample(2, xf + b_shift.0, yf + b_shift.1),
+        ])
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        apply_ccm, correct_chromatic_aberration, reconstruct_highlights, CcmNormalization,
+        HighlightReconstruction,
+    };
+    use crate::rgb::RgbRaw;
+
+    fn patches() -> RgbRaw<u16> {
+        let mut rgb = RgbRaw::<u16>::new(3, 1);
+        rgb.set_pix(0, 0, [1000, 2000, 3000]);
+        rgb.set_pix(1, 0, [4095, 0, 17]);
+        rgb.set_pix(2, 0, [800, 800, 800]);
+        rgb
+    }
+
+    #[test]
+    fn test_apply_ccm() {
+        println!("color::test::test_apply_ccm()  {{");
+
+        // 単位行列 (ビット単位で一致)
+        let mut rgb = patches();
+        let identity = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        apply_ccm(&mut rgb, identity, 4095, CcmNormalization::None);
+        assert_eq!([1000, 2000, 3000], rgb.pix(0, 0));
+        assert_eq!([4095, 0, 17], rgb.pix(1, 0));
+
+        // チャネル入れ替え (R <=> B)
+        let mut rgb = patches();
+        let swap = [[0.0, 0.0, 1.0], [0.0, 1.0, 0.0], [1.0, 0.0, 0.0]];
+        apply_ccm(&mut rgb, swap, 4095, CcmNormalization::None);
+        assert_eq!([3000, 2000, 1000], rgb.pix(0, 0));
+
+        // 彩度低減 (各出力 = 0.5 * 自チャネル + 0.25 * 他チャネル)
+        let mut rgb = patches();
+        let desaturate = [[0.5, 0.25, 0.25], [0.25, 0.5, 0.25], [0.25, 0.25, 0.5]];
+        apply_ccm(&mut rgb, desaturate, 4095, CcmNormalization::None);
+        println!(
+            "  [color][test_apply_ccm()] desaturated = {:?}",
+            rgb.pix(0, 0)
+        );
+        assert_eq!([1750, 2000, 2250], rgb.pix(0, 0));
+        assert_eq!([800, 800, 800], rgb.pix(2, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_ccm_clamp() {
+        println!("color::test::test_apply_ccm_clamp()  {{");
+
+        // 負の係数で負になる値は0に, 上限を超える値はwhite_levelにクランプ
+        let mut rgb = patches();
+        let ccm = [[1.8, -0.4, -0.2], [-0.3, 1.5, -0.2], [-0.1, -0.5, 1.6]];
+        apply_ccm(&mut rgb, ccm, 4095, CcmNormalization::None);
+        println!(
+            "  [color][test_apply_ccm_clamp()] rgb = {:?} {:?}",
+            rgb.pix(0, 0),
+            rgb.pix(1, 0)
+        );
+        assert_eq!([4095, 0, 0], rgb.pix(1, 0));
+        // 行の和が1でないため白が変わる
+        assert_eq!([960, 800, 800], rgb.pix(2, 0));
+
+        // 行正規化で白を保つ
+        let mut rgb = patches();
+        apply_ccm(&mut rgb, ccm, 4095, CcmNormalization::PreserveWhite);
+        assert_eq!([800, 800, 800], rgb.pix(2, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_correct_chromatic_aberration() {
+        println!("color::test::test_correct_chromatic_aberration()  {{");
+
+        let rgb = image::RgbImage::from_fn(8, 6, |x, y| {
+            image::Rgb([
+                (x * 30 + y) as u8,
+                (x * 20 + y * 10) as u8,
+                (250 - x * 25 - y) as u8,
+            ])
+        });
+
+        // 整数シフト => 逆シフトで元に戻る (画像外を参照した端の列は0)
+        let shifted = correct_chromatic_aberration(&rgb, (1.0, 0.0), (-1.0, 0.0));
+        assert_eq!(rgb.get_pixel(3, 2)[0], shifted.get_pixel(2, 2)[0]);
+        assert_eq!(0, shifted.get_pixel(7, 2)[0]);
+        assert_eq!(0, shifted.get_pixel(0, 2)[2]);
+        let restored = correct_chromatic_aberration(&shifted, (-1.0, 0.0), (1.0, 0.0));
+        println!(
+            "  [color][test_correct_chromatic_aberration()] original = {:?}, restored = {:?}",
+            rgb.get_pixel(4, 3),
+            restored.get_pixel(4, 3)
+        );
+        for y in 0..6 {
+            for x in 1..7 {
+                let (a, b) = (rgb.get_pixel(x, y), restored.get_pixel(x, y));
+                for c in 0..3 {
+                    assert!((a[c] as i32 - b[c] as i32).abs() <= 1, "({}, {})", x, y);
+                }
+            }
+        }
+
+        // サブ画素シフト (水平0.5画素 => 隣接画素の平均)
+        let half = correct_chromatic_aberration(&rgb, (0.5, 0.0), (0.0, 0.0));
+        assert_eq!(45, half.get_pixel(1, 0)[0]);
+        assert_eq!(rgb.get_pixel(1, 0)[1], half.get_pixel(1, 0)[1]);
+        assert_eq!(rgb.get_pixel(1, 0)[2], half.get_pixel(1, 0)[2]);
+
+        println!("}}");
+    }
+
+    // 背景色bgの上に白飛び円盤 (半径2以内: 全チャネル白飛び, 半径4以内: R/Bのみ白飛びのマゼンタ)
+    fn blown_disc(bg: [u16; 3]) -> RgbRaw<u16> {
+        let mut rgb = RgbRaw::<u16>::new(16, 16);
+        for y in 0..16 {
+            for x in 0..16 {
+                let d2 = (x as i32 - 8).pow(2) + (y as i32 - 8).pow(2);
+                let p = if d2 <= 4 {
+                    [1000, 1000, 1000]
+                } else if d2 <= 16 {
+                    [1000, 700, 1000]
+                } else {
+                    bg
+                };
+                rgb.set_pix(x, y, p);
+            }
+        }
+        rgb
+    }
+
+    #[test]
+    fn test_reconstruct_highlights() {
+        println!("color::test::test_reconstruct_highlights()  {{");
+
+        // 灰色背景 => 推定値はwhite_level未満にならないので白飛びチャネルはそのまま
+        let mut rgb = blown_disc([400, 400, 400]);
+        reconstruct_highlights(&mut rgb, 1000, HighlightReconstruction::ClipAndPropagate);
+        println!(
+            "  [color][test_reconstruct_highlights()] edge = {:?}, center = {:?}",
+            rgb.pix(11, 8),
+            rgb.pix(8, 8)
+        );
+        assert_eq!([1000, 700, 1000], rgb.pix(11, 8));
+        assert_eq!([1000, 1000, 1000], rgb.pix(8, 8));
+        assert_eq!([400, 400, 400], rgb.pix(0, 0));
+
+        // 周囲の色比を使う (背景R/G = 1.5, B/G = 1.25 => Bの推定875はwhite_levelに引き上げ)
+        let mut rgb = blown_disc([600, 400, 500]);
+        reconstruct_highlights(&mut rgb, 1000, HighlightReconstruction::ClipAndPropagate);
+        println!(
+            "  [color][test_reconstruct_highlights()] tinted edge = {:?}",
+            rgb.pix(8, 12)
+        );
+        for y in 0..16 {
+            for x in 0..16 {
+                let d2 = (x as i32 - 8).pow(2) + (y as i32 - 8).pow(2);
+                if (5..=16).contains(&d2) {
+                    assert_eq!([1050, 700, 1000], rgb.pix(x, y), "({}, {})", x, y);
+                }
+            }
+        }
+
+        // 白飛びしていない画素がない場合はClipと同じ
+        let mut rgb = RgbRaw::<u16>::new(3, 2);
+        for y in 0..2 {
+            for x in 0..3 {
+                rgb.set_pix(x, y, [1000, 500, 1000]);
+            }
+        }
+        reconstruct_highlights(&mut rgb, 1000, HighlightReconstruction::ClipAndPropagate);
+        assert_eq!([1000, 1000, 1000], rgb.pix(2, 1));
+
+        // Clip => 白飛びを含む画素は全チャネル白
+        let mut rgb = blown_disc([400, 400, 400]);
+        reconstruct_highlights(&mut rgb, 1000, HighlightReconstruction::Clip);
+        assert_eq!([1000, 1000, 1000], rgb.pix(11, 8));
+        assert_eq!([400, 400, 400], rgb.pix(15, 15));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,128 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 画像差分レポート
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageDiffReport {
+    // 差分はf64で計算 (符号付き型でも桁あふれしない)
+    pub max_abs_diff: f64,
+    pub mean_abs_diff: f64,
+    pub num_differing_pixels: usize,
+    // 最大差分の座標(x, y)
+    pub largest_diff_location: (usize, usize),
+    pub are_equal: bool,
+}
+
+impl std::fmt::Display for ImageDiffReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "images differ in {} pixel(s)", self.num_differing_pixels)?;
+        writeln!(
+            f,
+            "  max |a - b|  = {} at (x: {}, y: {})",
+            self.max_abs_diff, self.largest_diff_location.0, self.largest_diff_location.1
+        )?;
+        write!(f, "  mean |a - b| = {}", self.mean_abs_diff)
+    }
+}
+
+// 画像比較
+pub fn compare_images<T: PixelType>(
+    a: &NDRaw<T>,
+    b: &NDRaw<T>,
+) -> Result<ImageDiffReport, SensorIoError> {
+    check_shape((a.width(), a.height()), (b.width(), b.height()))?;
+
+    let mut max_abs_diff = 0.0;
+    let mut sum_abs_diff = 0.0;
+    let mut num_differing_pixels = 0;
+    let mut largest_diff_location = (0, 0);
+    for ((y, x), pa) in a.data.indexed_iter() {
+        let diff = (pa.to_f64().unwrap() - b.data[[y, x]].to_f64().unwrap()).abs();
+        if diff != 0.0 {
+            num_differing_pixels += 1;
+        }
+        if diff > max_abs_diff {
+            max_abs_diff = diff;
+            largest_diff_location = (x, y);
+        }
+        sum_abs_diff += diff;
+    }
+
+    let count = a.data.len().max(1) as f64;
+    Ok(ImageDiffReport {
+        max_abs_diff,
+        mean_abs_diff: sum_abs_diff / count,
+        num_differing_pixels,
+        largest_diff_location,
+        are_equal: num_differing_pixels == 0,
+    })
+}
+
+// 画像一致確認 (不一致時は差分レポートを表示してpanic, テスト用)
+pub fn assert_images_equal<T: PixelType>(a: &NDRaw<T>, b: &NDRaw<T>) {
+    match compare_images(a, b) {
+        Ok(report) if report.are_equal => {}
+        Ok(report) => panic!("assertion `images equal` failed: {}", report),
+        Err(e) => panic!("assertion `images equal` failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{assert_images_equal, compare_images};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compare_images() {
+        println!("compare::test::test_compare_images()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+        let a = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let mut b = a.clone();
+        *b.pix_mut(2, 1) = 2;
+
+        let report = compare_images(&a, &b).unwrap();
+        println!("  [compare][test_compare_images()] report = {:?}", report);
+        assert_eq!(4.0, report.max_abs_diff);
+        assert_eq!(4.0 / 12.0, report.mean_abs_diff);
+        assert_eq!(1, report.num_differing_pixels);
+        assert_eq!((2, 1), report.largest_diff_location);
+        assert!(!report.are_equal);
+
+        let report = compare_images(&a, &a).unwrap();
+        assert!(report.are_equal);
+        assert_eq!(0, report.num_differing_pixels);
+
+        let c = NDRaw::<u16>::new(3, 3);
+        assert!(matches!(
+            compare_images(&a, &c),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+
+        // 符号付き型の全範囲差分
+        let a = NDRaw::<i8>::new_from_vector2d(&[vec![i8::MIN, 0]]);
+        let b = NDRaw::<i8>::new_from_vector2d(&[vec![i8::MAX, 0]]);
+        let report = compare_images(&a, &b).unwrap();
+        assert_eq!(255.0, report.max_abs_diff);
+        assert_eq!((0, 0), report.largest_diff_location);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_assert_images_equal() {
+        println!("compare::test::test_assert_images_equal()  {{");
+
+        let a = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        assert_images_equal(&a, &a.clone());
+
+        let mut b = a.clone();
+        *b.pix_mut(0, 0) = 100;
+        let result = std::panic::catch_unwind(|| assert_images_equal(&a, &b));
+        assert!(result.is_err());
+
+        println!("}}");
+    }
+}
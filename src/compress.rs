@@ -0,0 +1,161 @@
+use crate::bin_builder::{BinCompression, BinWriter};
+use crate::error::SensorIoError;
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::sidecar;
+use std::fs::File;
+use std::io::BufReader;
+
+// 圧縮bin画像 (BinWriterのDeflate圧縮と同じ形式, 読み込みはnew_from_binimage等でもヘッダのflagsで判定される)
+
+impl<T: PixelType> NDRaw<T> {
+    // 圧縮bin画像書き込み (ワードに収まらない画素値はエラー, メタデータはwrite_binimageと同様にサイドカーに保存)
+    pub fn write_binimage_compressed(&self, path_raw_out: String) -> Result<(), SensorIoError> {
+        BinWriter::new()
+            .compression(BinCompression::Deflate)
+            .write(self, &path_raw_out)?;
+        if !self.metadata.is_empty() {
+            sidecar::write_sidecar(&path_raw_out, &self.metadata)?;
+        }
+        Ok(())
+    }
+
+    // 圧縮bin画像変換コンストラクタ (ヘッダのflagsに応じて展開, サイドカーがあればメタデータとして読み込む)
+    pub fn new_from_binimage_compressed(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(&path_raw_in)?);
+        let mut raw = Self::read_from_stream(&mut f_read)?;
+        raw.metadata = sidecar::read_sidecar(&path_raw_in)?;
+        Ok(raw)
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // 圧縮bin画像書き込み (16bitに収まらない画素値はエラー)
+    pub fn write_binimage_compressed(&self, path_raw_out: String) -> Result<(), SensorIoError> {
+        BinWriter::new()
+            .compression(BinCompression::Deflate)
+            .write(self, path_raw_out)
+    }
+
+    // 圧縮bin画像変換コンストラクタ (ヘッダのflagsに応じて展開)
+    pub fn new_from_binimage_compressed(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        Self::read_from_stream(&mut f_read)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bin_builder::{BinCompression, BinWriter};
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    fn temp_path(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!(
+                "sensor_io_compress_{}_{}",
+                std::process::id(),
+                name
+            ))
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_compressed_roundtrip() {
+        println!("compress::test::test_compressed_roundtrip()  {{");
+
+        // ほぼ一様なフレーム
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![512; 64]; 48]);
+        *raw_in.pix_mut(10, 20) = 4095;
+        *raw_in.pix_mut(63, 47) = 1;
+
+        let path_plain = temp_path("plain.bin");
+        let path_compressed = temp_path("compressed.bin");
+        raw_in.write_binimage(path_plain.clone()).unwrap();
+        raw_in
+            .write_binimage_compressed(path_compressed.clone())
+            .unwrap();
+        let size_plain = std::fs::metadata(&path_plain).unwrap().len();
+        let size_compressed = std::fs::metadata(&path_compressed).unwrap().len();
+        println!(
+            "  [compress][test_compressed_roundtrip()] plain = {} bytes, compressed = {} bytes",
+            size_plain, size_compressed
+        );
+        assert!(size_compressed < size_plain);
+
+        let raw_read = NDRaw::<u16>::new_from_binimage_compressed(path_compressed.clone()).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+        let na_read = NARaw::<u16>::new_from_binimage_compressed(path_compressed.clone()).unwrap();
+        assert_eq!(4095, *na_read.pix(10, 20));
+        assert_eq!(1, *na_read.pix(63, 47));
+
+        // 通常の読み込みでもflagsを判定して展開する
+        let raw_read = NDRaw::<u16>::new_from_binimage(path_compressed.clone()).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+        let plain_read = NDRaw::<u16>::new_from_binimage_compressed(path_plain.clone()).unwrap();
+        assert_eq!(raw_in.data(), plain_read.data());
+
+        std::fs::remove_file(&path_plain).unwrap();
+        std::fs::remove_file(&path_compressed).unwrap();
+
+        // 圧縮フレームを続けて書き込んだストリームも順に読み込める
+        let writer = BinWriter::new().compression(BinCompression::Deflate);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        writer.write_to_stream(&raw_in, &mut cursor).unwrap();
+        writer.write_to_stream(&raw_in, &mut cursor).unwrap();
+        cursor.set_position(0);
+        for _ in 0..2 {
+            let raw_read = NDRaw::<u16>::read_from_stream(&mut cursor).unwrap();
+            assert_eq!(raw_in.data(), raw_read.data());
+        }
+        assert_eq!(cursor.get_ref().len() as u64, cursor.position());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_naraw_compressed_roundtrip() {
+        println!("compress::test::test_naraw_compressed_roundtrip()  {{");
+
+        let raw_in = NARaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let path = temp_path("naraw.bin");
+        raw_in.write_binimage_compressed(path.clone()).unwrap();
+        let raw_read = NARaw::<u16>::new_from_binimage_compressed(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compressed_fallback() {
+        println!("compress::test::test_compressed_fallback()  {{");
+
+        // 圧縮しても小さくならない画像は非圧縮で書き込む (CRC32付き)
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&[vec![0x1234, 0xfedc], vec![7, 0x8000]]);
+        let path = temp_path("fallback.bin");
+        raw_in.write_binimage_compressed(path.clone()).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        println!(
+            "  [compress][test_compressed_fallback()] bytes = {:?}",
+            bytes
+        );
+        assert_eq!(8 + 4 * 2 + 4, bytes.len());
+        // flagsはCRC32付きのみ
+        assert_eq!(2, bytes[2]);
+        let raw_read = NDRaw::<u16>::new_from_binimage(path.clone()).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        // ワードに収まらない画素値はエラー
+        let raw_i32 = NDRaw::<u32>::new_from_vector2d(&[vec![1, 70000]]);
+        let result = raw_i32.write_binimage_compressed(path.clone());
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        println!("}}");
+    }
+}
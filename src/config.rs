@@ -0,0 +1,211 @@
+use crate::error::SensorIoError;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::path::Path;
+
+// センサ幾何情報
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SensorGeometry {
+    pub width: usize,
+    pub height: usize,
+    // 画素ピッチ [um]
+    pub pixel_pitch_um: f64,
+}
+
+// センサ付帯情報
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Metadata {
+    pub serial_number: Option<String>,
+    pub calibration_date: Option<String>,
+}
+
+// XMLセンサ記述ファイル読み込み
+//   <Sensor>
+//     <Width>, <Height>, <PixelPitch unit="um|nm|mm">, <SerialNumber>, <CalibrationDate>
+//   </Sensor>
+pub fn decode_sensor_xml_config(
+    path: impl AsRef<Path>,
+) -> Result<(SensorGeometry, Metadata), SensorIoError> {
+    let xml = std::fs::read_to_string(path)?;
+    parse_sensor_xml(&xml)
+}
+
+// XMLセンサ記述ファイル書き込み
+pub fn encode_sensor_xml_config(
+    geom: &SensorGeometry,
+    meta: &Metadata,
+    path: impl AsRef<Path>,
+) -> Result<(), SensorIoError> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<Sensor>\n");
+    xml += &format!("  <Width>{}</Width>\n", geom.width);
+    xml += &format!("  <Height>{}</Height>\n", geom.height);
+    xml += &format!(
+        "  <PixelPitch unit=\"um\">{}</PixelPitch>\n",
+        geom.pixel_pitch_um
+    );
+    if let Some(serial_number) = &meta.serial_number {
+        xml += &format!(
+            "  <SerialNumber>{}</SerialNumber>\n",
+            quick_xml::escape::escape(serial_number)
+        );
+    }
+    if let Some(calibration_date) = &meta.calibration_date {
+        xml += &format!(
+            "  <CalibrationDate>{}</CalibrationDate>\n",
+            quick_xml::escape::escape(calibration_date)
+        );
+    }
+    xml += "</Sensor>\n";
+    std::fs::write(path, xml)?;
+    Ok(())
+}
+
+fn parse_sensor_xml(xml: &str) -> Result<(SensorGeometry, Metadata), SensorIoError> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let (mut width, mut height, mut pixel_pitch_um) = (None, None, None);
+    let mut meta = Metadata::default();
+    let mut in_sensor = false;
+    // 現在の要素名と単位換算係数([um]へ)
+    let mut current: Option<(String, f64)> = None;
+    loop {
+        match reader.read_event().map_err(to_parse_error)? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if name == "Sensor" {
+                    in_sensor = true;
+                    continue;
+                }
+                let mut scale = 1.0;
+                if name == "PixelPitch" {
+                    if let Some(unit) = e.try_get_attribute("unit").map_err(to_parse_error)? {
+                        scale = match unit.unescape_value().map_err(to_parse_error)?.as_ref() {
+                            "um" => 1.0,
+                            "nm" => 1e-3,
+                            "mm" => 1e3,
+                            other => {
+                                return Err(SensorIoError::Parse(format!(
+                                    "unknown pixel pitch unit '{}'",
+                                    other
+                                )))
+                            }
+                        };
+                    }
+                }
+                current = Some((name, scale));
+            }
+            Event::Text(t) if in_sensor => {
+                let Some((name, scale)) = &current else {
+                    continue;
+                };
+                let text = t.unescape().map_err(to_parse_error)?.into_owned();
+                match name.as_str() {
+                    "Width" => width = Some(parse_value::<usize>(name, &text)?),
+                    "Height" => height = Some(parse_value::<usize>(name, &text)?),
+                    "PixelPitch" => pixel_pitch_um = Some(parse_value::<f64>(name, &text)? * scale),
+                    "SerialNumber" => meta.serial_number = Some(text),
+                    "CalibrationDate" => meta.calibration_date = Some(text),
+                    // 未知の要素は無視
+                    _ => {}
+                }
+            }
+            Event::End(e) => {
+                if e.name().as_ref() == b"Sensor" {
+                    in_sensor = false;
+                }
+                current = None;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    let missing = |name: &str| SensorIoError::Parse(format!("missing <{}> element", name));
+    let geom = SensorGeometry {
+        width: width.ok_or_else(|| missing("Width"))?,
+        height: height.ok_or_else(|| missing("Height"))?,
+        pixel_pitch_um: pixel_pitch_um.ok_or_else(|| missing("PixelPitch"))?,
+    };
+    Ok((geom, meta))
+}
+
+fn parse_value<V: std::str::FromStr>(name: &str, text: &str) -> Result<V, SensorIoError> {
+    text.trim()
+        .parse()
+        .map_err(|_| SensorIoError::Parse(format!("invalid <{}> value '{}'", name, text)))
+}
+
+fn to_parse_error(e: impl std::fmt::Display) -> SensorIoError {
+    SensorIoError::Parse(format!("XML: {}", e))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_sensor_xml_config, encode_sensor_xml_config, Metadata, SensorGeometry};
+    use crate::error::SensorIoError;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sensor_io_config_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_decode_sensor_xml_config() {
+        println!("config::test::test_decode_sensor_xml_config()  {{");
+
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Sensor>
+  <Width>4096</Width>
+  <Height>3000</Height>
+  <PixelPitch unit="nm">3450</PixelPitch>
+  <SerialNumber>SN-0042 &amp; rev.B</SerialNumber>
+  <CalibrationDate>2024-03-01</CalibrationDate>
+  <Vendor>ignored</Vendor>
+</Sensor>
+"#;
+        let path = temp_path("decode.xml");
+        std::fs::write(&path, xml).unwrap();
+        let result = decode_sensor_xml_config(&path);
+        std::fs::remove_file(&path).unwrap();
+        let (geom, meta) = result.unwrap();
+        println!(
+            "  [config][test_decode_sensor_xml_config()] geom = {:?}, meta = {:?}",
+            geom, meta
+        );
+        assert_eq!((4096, 3000), (geom.width, geom.height));
+        assert!((geom.pixel_pitch_um - 3.45).abs() < 1e-12);
+        assert_eq!(Some("SN-0042 & rev.B"), meta.serial_number.as_deref());
+        assert_eq!(Some("2024-03-01"), meta.calibration_date.as_deref());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_encode_sensor_xml_config() {
+        println!("config::test::test_encode_sensor_xml_config()  {{");
+
+        let geom = SensorGeometry {
+            width: 1920,
+            height: 1080,
+            pixel_pitch_um: 2.9,
+        };
+        let meta = Metadata {
+            serial_number: Some(String::from("<A1>")),
+            calibration_date: None,
+        };
+        let path = temp_path("encode.xml");
+        encode_sensor_xml_config(&geom, &meta, &path).unwrap();
+        let result = decode_sensor_xml_config(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((geom, meta), result.unwrap());
+
+        // 必須要素の欠落
+        let path = temp_path("missing.xml");
+        std::fs::write(&path, "<Sensor><Width>4</Width></Sensor>").unwrap();
+        let result = decode_sensor_xml_config(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,119 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// パーセンタイル範囲 [low, high] => [0, max_code] の線形写像 (範囲外はクランプ, low == highならNone)
+fn stretch_map<T: PixelType>(low: T, high: T, max_code: T) -> Option<impl Fn(T) -> T> {
+    let (low, high) = (low.to_f64().unwrap(), high.to_f64().unwrap());
+    if high <= low {
+        return None;
+    }
+    let max_code = max_code.to_f64().unwrap();
+    let scale = max_code / (high - low);
+    Some(move |p: T| {
+        let v = (p.to_f64().unwrap() - low) * scale;
+        T::from_f64_saturating(v.clamp(0.0, max_code))
+    })
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // パーセンタイルによるコントラスト伸長 (low_pct〜high_pct[%]の画素値を0〜max_codeへ, 範囲外はクランプ)
+    //   両パーセンタイルの画素値が同じ場合はそのまま
+    pub fn stretch_contrast(&self, low_pct: f64, high_pct: f64, max_code: T) -> Self {
+        let low = self.compute_percentile((low_pct / 100.0) as f32);
+        let high = self.compute_percentile((high_pct / 100.0) as f32);
+        match stretch_map(low, high, max_code) {
+            Some(map) => NDRaw::from_ndarray(self.data.mapv(map)),
+            None => self.clone(),
+        }
+    }
+
+    // チャネル別コントラスト伸長 (Bayerチャネル毎のパーセンタイルで写像)
+    pub fn stretch_contrast_bayer(
+        &self,
+        low_pct: f64,
+        high_pct: f64,
+        max_code: T,
+        pattern: BayerPattern,
+    ) -> Self {
+        let mut raw = self.clone();
+        for channel in BayerChannel::ALL {
+            let plane = self.bayer_plane(pattern, channel);
+            let low = plane.compute_percentile((low_pct / 100.0) as f32);
+            let high = plane.compute_percentile((high_pct / 100.0) as f32);
+            let Some(map) = stretch_map(low, high, max_code) else {
+                continue;
+            };
+            for ((y, x), p) in raw.data.indexed_iter_mut() {
+                if pattern.channel_at(x, y) == channel {
+                    *p = map(*p);
+                }
+            }
+        }
+        raw
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::BayerPattern;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_stretch_contrast() {
+        println!("contrast::test::test_stretch_contrast()  {{");
+
+        // 0〜100の101画素 => 10%点は10, 90%点は90
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&[(0..=100).collect()]);
+        let raw_out = raw_in.stretch_contrast(10.0, 90.0, 1000);
+        println!(
+            "  [contrast][test_stretch_contrast()] raw_out = {}",
+            raw_out.data()
+        );
+        assert_eq!(0, *raw_out.pix(10, 0));
+        assert_eq!(1000, *raw_out.pix(90, 0));
+        assert_eq!(500, *raw_out.pix(50, 0));
+        assert_eq!(13, *raw_out.pix(11, 0));
+        // 裾はクランプ
+        assert!((0..10).all(|x| *raw_out.pix(x, 0) == 0));
+        assert!((91..=100).all(|x| *raw_out.pix(x, 0) == 1000));
+
+        // 一定値の画像はそのまま
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![300; 4]; 2]);
+        assert_eq!(
+            raw_in.data(),
+            raw_in.stretch_contrast(1.0, 99.0, 1000).data()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_stretch_contrast_bayer() {
+        println!("contrast::test::test_stretch_contrast_bayer()  {{");
+
+        // R画素は100〜103, それ以外は1000〜1003
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| {
+                (0..4)
+                    .map(|x| {
+                        let base = if x % 2 == 0 && y % 2 == 0 { 100 } else { 1000 };
+                        base + (x / 2 + y) as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let raw_out = raw_in.stretch_contrast_bayer(0.0, 100.0, 255, BayerPattern::Rggb);
+        println!(
+            "  [contrast][test_stretch_contrast_bayer()] raw_out = \n{}",
+            raw_out.data()
+        );
+        // Rチャネルも独立に0〜255へ
+        assert_eq!(0, *raw_out.pix(0, 0));
+        assert_eq!(255, *raw_out.pix(2, 2));
+        assert_eq!(0, *raw_out.pix(1, 0));
+
+        println!("}}");
+    }
+}
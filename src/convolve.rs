@@ -0,0 +1,192 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // 行方向(水平)の1次元畳み込み (out[i] = Σ kernel[k] * in[i + center - k])
+    //   kernelの中心はkernel.len() / 2, 範囲外は端の画素で補完, 係数の正規化は行わない
+    pub fn convolve_rows(&self, kernel: &[f64]) -> Self {
+        self.convolve_axis(kernel, ndarray::Axis(1))
+    }
+
+    // 列方向(垂直)の1次元畳み込み
+    //   kernelの中心はkernel.len() / 2, 範囲外は端の画素で補完, 係数の正規化は行わない
+    pub fn convolve_cols(&self, kernel: &[f64]) -> Self {
+        self.convolve_axis(kernel, ndarray::Axis(0))
+    }
+
+    // 座標(x, y)のみの2次元畳み込み値 (疎な点での評価用, convolve_rows/colsと同じ向き)
+    //   kernelの中心は(rows / 2, cols / 2), 範囲外は端の画素で補完, 係数の正規化は行わない
+    pub fn kernel_response_at(&self, x: usize, y: usize, kernel: &ndarray::Array2<f64>) -> f64 {
+        let (height, width) = self.data.dim();
+        if height == 0 || width == 0 {
+            return 0.0;
+        }
+        let (cy, cx) = ((kernel.nrows() / 2) as isize, (kernel.ncols() / 2) as isize);
+        kernel
+            .indexed_iter()
+            .map(|((ky, kx), w)| {
+                let sy = (y as isize + cy - ky as isize).clamp(0, height as isize - 1) as usize;
+                let sx = (x as isize + cx - kx as isize).clamp(0, width as isize - 1) as usize;
+                w * self.data[[sy, sx]].to_f64().unwrap()
+            })
+            .sum()
+    }
+
+    fn convolve_axis(&self, kernel: &[f64], axis: ndarray::Axis) -> Self {
+        let len = self.data.len_of(axis);
+        if len == 0 || kernel.is_empty() {
+            return self.clone();
+        }
+        let center = (kernel.len() / 2) as isize;
+        let data = ndarray::Array2::from_shape_fn(self.data.dim(), |(y, x)| {
+            let i = if axis.index() == 0 { y } else { x };
+            let sum: f64 = kernel
+                .iter()
+                .enumerate()
+                .map(|(k, w)| {
+                    let j = (i as isize + center - k as isize).clamp(0, len as isize - 1) as usize;
+                    let p = if axis.index() == 0 {
+                        self.data[[j, x]]
+                    } else {
+                        self.data[[y, j]]
+                    };
+                    w * p.to_f64().unwrap()
+                })
+                .sum();
+            T::from_f64_saturating(sum)
+        });
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_convolve_rows() {
+        println!("convolve::test::test_convolve_rows()  {{");
+
+        // 水平方向のステップ
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 0, 0, 400, 400, 400]; 2];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let raw_out = raw_in.convolve_rows(&[0.25, 0.5, 0.25]);
+        println!(
+            "  [convolve][test_convolve_rows()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!(
+            &[0, 0, 100, 300, 400, 400],
+            raw_out.data().row(1).as_slice().unwrap()
+        );
+        // 列方向には変化なし
+        assert_eq!(
+            raw_in.data(),
+            raw_in.convolve_cols(&[0.25, 0.5, 0.25]).data()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_convolve_cols() {
+        println!("convolve::test::test_convolve_cols()  {{");
+
+        // 垂直方向のステップ (端は端の画素で補完)
+        let vec2d: Vec<Vec<f32>> = vec![vec![8.0], vec![8.0], vec![0.0], vec![0.0]];
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&vec2d);
+        let raw_out = raw_in.convolve_cols(&[0.25, 0.5, 0.25]);
+        println!(
+            "  [convolve][test_convolve_cols()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!(
+            vec![8.0, 6.0, 2.0, 0.0],
+            raw_out.data().iter().copied().collect::<Vec<f32>>()
+        );
+
+        // 分離型フィルタ (行 => 列) は2次元畳み込みに一致
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&[
+            vec![0.0, 0.0, 0.0],
+            vec![0.0, 16.0, 0.0],
+            vec![0.0, 0.0, 0.0],
+        ]);
+        let blurred = raw_in
+            .convolve_rows(&[0.25, 0.5, 0.25])
+            .convolve_cols(&[0.25, 0.5, 0.25]);
+        assert_eq!(4.0, *blurred.pix(1, 1));
+        assert_eq!(2.0, *blurred.pix(0, 1));
+        assert_eq!(1.0, *blurred.pix(0, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_convolve_asymmetric() {
+        println!("convolve::test::test_convolve_asymmetric()  {{");
+
+        // インパルス応答はカーネルそのもの (相関なら左右反転する)
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&[vec![0.0, 0.0, 1.0, 0.0, 0.0]]);
+        let raw_out = raw_in.convolve_rows(&[1.0, 2.0, 3.0]);
+        println!(
+            "  [convolve][test_convolve_asymmetric()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!(
+            vec![0.0, 1.0, 2.0, 3.0, 0.0],
+            raw_out.data().iter().copied().collect::<Vec<f32>>()
+        );
+
+        // 微分カーネル[1, 0, -1]: out[i] = in[i + 1] - in[i - 1]
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&[vec![0.0], vec![2.0], vec![6.0]]);
+        let raw_out = raw_in.convolve_cols(&[1.0, 0.0, -1.0]);
+        assert_eq!(
+            vec![2.0, 6.0, 4.0],
+            raw_out.data().iter().copied().collect::<Vec<f32>>()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_kernel_response_at() {
+        println!("convolve::test::test_kernel_response_at()  {{");
+
+        let vec2d: Vec<Vec<f32>> = (0..5)
+            .map(|y| (0..6).map(|x| ((x * 7 + y * 13) % 10) as f32).collect())
+            .collect();
+        let raw = NDRaw::<f32>::new_from_vector2d(&vec2d);
+
+        // 分離型カーネルの外積 => 行・列の1次元畳み込み結果と一致 (端も含む)
+        let k1d = [0.25, 0.5, 0.25];
+        let kernel = ndarray::Array2::from_shape_fn((3, 3), |(y, x)| k1d[y] * k1d[x]);
+        let full = raw.convolve_rows(&k1d).convolve_cols(&k1d);
+        for &(x, y) in &[(0, 0), (2, 3), (5, 4), (5, 0)] {
+            let response = raw.kernel_response_at(x, y, &kernel);
+            println!(
+                "  [convolve][test_kernel_response_at()] ({}, {}): response = {}, full = {}",
+                x,
+                y,
+                response,
+                full.pix(x, y)
+            );
+            assert!((response - *full.pix(x, y) as f64).abs() < 1e-4);
+        }
+
+        // 非対称カーネル (畳み込みなので反転し, 左隣の画素を取り出す)
+        let shift = ndarray::array![[0.0, 0.0, 1.0]];
+        assert_eq!(*raw.pix(1, 2) as f64, raw.kernel_response_at(2, 2, &shift));
+        assert_eq!(*raw.pix(0, 2) as f64, raw.kernel_response_at(0, 2, &shift));
+
+        // 非対称な分離型カーネルでも1次元畳み込みと一致
+        let (kx, ky) = ([1.0, 2.0, 3.0], [0.5, 0.0, -0.5]);
+        let kernel = ndarray::Array2::from_shape_fn((3, 3), |(y, x)| ky[y] * kx[x]);
+        let full = raw.convolve_rows(&kx).convolve_cols(&ky);
+        for &(x, y) in &[(0, 0), (2, 3), (5, 4)] {
+            let response = raw.kernel_response_at(x, y, &kernel);
+            assert!((response - *full.pix(x, y) as f64).abs() < 1e-4);
+        }
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+
+// Bayer位相を保つための奇数座標・サイズの扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CropAlignment {
+    // 偶数に切り下げ
+    Snap,
+    // 奇数ならエラー
+    Error,
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 矩形切り出し
+    pub fn crop(&self, rect: Rect) -> Result<Self, SensorIoError> {
+        rect.check_within(self.width(), self.height())?;
+        let data = self
+            .data
+            .slice(ndarray::s![rect.y..rect.bottom(), rect.x..rect.right()])
+            .to_owned();
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    // Bayer位相を保つ切り出し (出力のBayer配列は入力と同じ)
+    pub fn crop_aligned(
+        &self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        alignment: CropAlignment,
+    ) -> Result<Self, SensorIoError> {
+        let rect = Rect::new(x, y, width, height);
+        let is_aligned = [x, y, width, height].iter().all(|v| v.is_multiple_of(2));
+        let rect = match alignment {
+            _ if is_aligned => rect,
+            CropAlignment::Snap => Rect::new(x & !1, y & !1, width & !1, height & !1),
+            CropAlignment::Error => {
+                return Err(SensorIoError::InvalidArgument(format!(
+                    "crop {}x{}+{}+{} is not aligned to the 2x2 CFA period",
+                    width, height, x, y
+                )))
+            }
+        };
+        self.crop(rect)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CropAlignment;
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+    use crate::rect::Rect;
+
+    fn labeled_mosaic(pattern: BayerPattern, width: usize, height: usize) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| pattern.channel_at(x, y) as u16 * 1000 + (y * width + x) as u16)
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_crop() {
+        println!("crop::test::test_crop()  {{");
+
+        let raw_in = labeled_mosaic(BayerPattern::Rggb, 8, 6);
+        let raw_out = raw_in.crop(Rect::new(3, 1, 4, 2)).unwrap();
+        println!("  [crop][test_crop()] raw_out = \n{}", raw_out.data());
+        assert_eq!((4, 2), (raw_out.width(), raw_out.height()));
+        assert_eq!(*raw_in.pix(3, 1), *raw_out.pix(0, 0));
+        assert!(matches!(
+            raw_in.crop(Rect::new(6, 0, 4, 2)),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_crop_aligned() {
+        println!("crop::test::test_crop_aligned()  {{");
+
+        let pattern = BayerPattern::Gbrg;
+        let raw_in = labeled_mosaic(pattern, 10, 8);
+
+        let raw_out = raw_in
+            .crop_aligned(3, 1, 5, 4, CropAlignment::Snap)
+            .unwrap();
+        println!(
+            "  [crop][test_crop_aligned()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!((4, 4), (raw_out.width(), raw_out.height()));
+        assert_eq!(*raw_in.pix(2, 0), *raw_out.pix(0, 0));
+        for y in 0..raw_out.height() {
+            for x in 0..raw_out.width() {
+                assert_eq!(pattern.channel_at(x, y) as u16, *raw_out.pix(x, y) / 1000);
+            }
+        }
+
+        let result = raw_in.crop_aligned(3, 1, 5, 4, CropAlignment::Error);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let raw_out = raw_in
+            .crop_aligned(2, 2, 4, 4, CropAlignment::Error)
+            .unwrap();
+        assert_eq!(*raw_in.pix(2, 2), *raw_out.pix(0, 0));
+
+        println!("}}");
+    }
+}
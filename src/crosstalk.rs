@@ -0,0 +1,144 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// チャネル間クロストーク行列
+//   matrix[i][j]: チャネルiの信号がチャネルjに漏れ込む割合 (添字はBayerChannel as usize, 対角は1)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CrossTalkMatrix {
+    pub matrix: [[f64; 4]; 4],
+}
+
+impl CrossTalkMatrix {
+    // 単位行列 (クロストークなし)
+    pub fn identity() -> Self {
+        let mut matrix = [[0.0; 4]; 4];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        CrossTalkMatrix { matrix }
+    }
+}
+
+// 単色照明のキャリブレーション画像からクロストーク測定
+//   照明チャネルの行に各チャネル平均 / 照明チャネル平均を格納 (他の行は単位行列のまま)
+//   全チャネル分を測定する場合は照明チャネル毎に測定して行を組み合わせる
+pub fn compute_cross_talk<T: PixelType>(
+    calibration_raw: &NDRaw<T>,
+    illuminated_channel: BayerChannel,
+    pattern: BayerPattern,
+) -> CrossTalkMatrix {
+    let stats = calibration_raw.compute_bayer_statistics(pattern);
+    let means = [stats.r.mean, stats.gr.mean, stats.gb.mean, stats.b.mean];
+    let dominant = means[illuminated_channel as usize];
+
+    let mut ctm = CrossTalkMatrix::identity();
+    if dominant > 0.0 {
+        ctm.matrix[illuminated_channel as usize] = means.map(|mean| mean / dominant);
+    }
+    ctm
+}
+
+// クロストーク補正 (2x2ブロック毎に観測値 = 行列^T x 真値 を解く)
+//   奇数サイズの端数行/列はそのまま, 行列が正則でない場合はエラー
+pub fn apply_cross_talk_correction<T: PixelType>(
+    raw: &NDRaw<T>,
+    ctm: &CrossTalkMatrix,
+    pattern: BayerPattern,
+) -> Result<NDRaw<f32>, SensorIoError> {
+    let mixing = nalgebra::Matrix4::from_fn(|i, j| ctm.matrix[j][i]);
+    let inverse = mixing.try_inverse().ok_or_else(|| {
+        SensorIoError::InvalidArgument(String::from("cross-talk matrix is not invertible"))
+    })?;
+
+    let mut corrected = NDRaw::from_ndarray(raw.data.mapv(|v| v.to_f64().unwrap() as f32));
+    let offsets = BayerChannel::ALL.map(|channel| pattern.offset(channel));
+    for (x, y, values) in raw.quads(pattern) {
+        let observed = nalgebra::Vector4::from_fn(|i, _| values[i].to_f64().unwrap());
+        let solved = inverse * observed;
+        for (i, (ox, oy)) in offsets.iter().enumerate() {
+            *corrected.pix_mut(x + ox, y + oy) = solved[i] as f32;
+        }
+    }
+    Ok(corrected)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{apply_cross_talk_correction, compute_cross_talk, CrossTalkMatrix};
+    use crate::bayer::{BayerChannel, BayerPattern};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 2x2ブロック毎に[R, Gr, Gb, B]を埋めたモザイク
+    fn mosaic(values: [u16; 4], pattern: BayerPattern) -> NDRaw<u16> {
+        let mut raw = NDRaw::<u16>::new(6, 4);
+        for y in 0..4 {
+            for x in 0..6 {
+                *raw.pix_mut(x, y) = values[pattern.channel_at(x, y) as usize] + (x / 2) as u16;
+            }
+        }
+        raw
+    }
+
+    #[test]
+    fn test_compute_cross_talk() {
+        println!("crosstalk::test::test_compute_cross_talk()  {{");
+
+        // 赤照明: Gr/Gbに10%, Bに5%漏れ込む
+        let pattern = BayerPattern::Grbg;
+        let raw = NDRaw::<u16>::from_ndarray(ndarray::Array2::from_shape_fn((4, 6), |(y, x)| {
+            [1000, 100, 100, 50][pattern.channel_at(x, y) as usize]
+        }));
+        let ctm = compute_cross_talk(&raw, BayerChannel::R, pattern);
+        println!(
+            "  [crosstalk][test_compute_cross_talk()] ctm = {:?}",
+            ctm.matrix
+        );
+        assert_eq!([1.0, 0.1, 0.1, 0.05], ctm.matrix[0]);
+        assert_eq!(CrossTalkMatrix::identity().matrix[1..], ctm.matrix[1..]);
+
+        // 測定した行列で補正すると漏れ込みが除去される
+        let corrected = apply_cross_talk_correction(&raw, &ctm, pattern).unwrap();
+        let (rx, ry) = pattern.offset(BayerChannel::R);
+        let (bx, by) = pattern.offset(BayerChannel::B);
+        assert!((*corrected.pix(rx, ry) - 1000.0).abs() < 1e-3);
+        assert!(corrected.pix(bx, by).abs() < 1e-3);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_cross_talk_correction() {
+        println!("crosstalk::test::test_apply_cross_talk_correction()  {{");
+
+        // 単位行列では元の値のまま
+        for pattern in BayerPattern::ALL {
+            let raw = mosaic([400, 900, 910, 300], pattern);
+            let corrected =
+                apply_cross_talk_correction(&raw, &CrossTalkMatrix::identity(), pattern).unwrap();
+            if pattern == BayerPattern::Rggb {
+                println!(
+                    "  [crosstalk][test_apply_cross_talk_correction()] corrected = \n{}",
+                    corrected.data()
+                );
+            }
+            for y in 0..4 {
+                for x in 0..6 {
+                    assert_eq!(*raw.pix(x, y) as f32, *corrected.pix(x, y));
+                }
+            }
+        }
+
+        // 正則でない行列はエラー
+        let singular = CrossTalkMatrix {
+            matrix: [[1.0; 4]; 4],
+        };
+        let raw = mosaic([400, 900, 910, 300], BayerPattern::Rggb);
+        let result = apply_cross_talk_correction(&raw, &singular, BayerPattern::Rggb);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,195 @@
+use crate::defect::median_of_neighbors;
+use crate::error::{check_shape, SensorIoError};
+use crate::pixel::PixelType;
+use crate::raw::RawImage;
+use num_traits::{Bounded, ToPrimitive};
+use std::collections::HashSet;
+
+// ダーク減算 (型の範囲に飽和, 符号なし型では0で止まる)
+//   hot_threshold: 指定時はこれを超えるダーク画素を, ダーク画像の同色近傍中央値で置き換えて減算
+pub(crate) fn subtract_dark<R: RawImage + ?Sized>(
+    raw: &mut R,
+    dark: &R,
+    hot_threshold: Option<R::Pixel>,
+) -> Result<(), SensorIoError> {
+    let (width, height) = (raw.width(), raw.height());
+    check_shape((width, height), (dark.width(), dark.height()))?;
+
+    let hot_set: HashSet<(usize, usize)> = match hot_threshold {
+        Some(threshold) => (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| *dark.pix(x, y) > threshold)
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    for y in 0..height {
+        for x in 0..width {
+            let d = if hot_set.contains(&(x, y)) {
+                // 正常な近傍が無ければ減算しない
+                median_of_neighbors(dark, x, y, &hot_set).unwrap_or(0.0)
+            } else {
+                dark.pix(x, y).to_f64().unwrap()
+            };
+            let v = raw.pix(x, y).to_f64().unwrap() - d;
+            *raw.pix_mut(x, y) = R::Pixel::from_f64_saturating(v);
+        }
+    }
+    Ok(())
+}
+
+// checked_subの失敗理由
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CheckedSubError {
+    // 形状(width, height)の不一致
+    ShapeMismatch(String),
+    // アンダーフローする画素の座標(x, y)一覧
+    Underflow(Vec<(usize, usize)>),
+}
+
+impl std::fmt::Display for CheckedSubError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CheckedSubError::ShapeMismatch(msg) => write!(f, "shape mismatch: {}", msg),
+            CheckedSubError::Underflow(pixels) => {
+                write!(f, "{} pixels underflow", pixels.len())
+            }
+        }
+    }
+}
+
+impl std::error::Error for CheckedSubError {}
+
+// 減算 (アンダーフローする画素があれば, その座標(x, y)の一覧を返す)
+pub(crate) fn checked_sub<R: RawImage + Clone>(raw: &R, other: &R) -> Result<R, CheckedSubError> {
+    let (width, height) = (raw.width(), raw.height());
+    if (width, height) != (other.width(), other.height()) {
+        return Err(CheckedSubError::ShapeMismatch(format!(
+            "expected {}x{}, got {}x{}",
+            width,
+            height,
+            other.width(),
+            other.height()
+        )));
+    }
+
+    let min = R::Pixel::min_value().to_f64().unwrap();
+    let mut diff = raw.clone();
+    let mut underflows = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let v = raw.pix(x, y).to_f64().unwrap() - other.pix(x, y).to_f64().unwrap();
+            if v < min {
+                underflows.push((x, y));
+            } else {
+                *diff.pix_mut(x, y) = R::Pixel::from_f64_saturating(v);
+            }
+        }
+    }
+    if underflows.is_empty() {
+        Ok(diff)
+    } else {
+        Err(CheckedSubError::Underflow(underflows))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CheckedSubError;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    #[test]
+    fn test_subtract_dark() {
+        println!("dark::test::test_subtract_dark()  {{");
+
+        let mut light = NDRaw::<u16>::new_from_vector2d(&[vec![100, 200, 300], vec![10, 64, 65]]);
+        let dark = NDRaw::<u16>::new_from_vector2d(&[vec![64, 64, 70], vec![64, 64, 64]]);
+        light.subtract_dark(&dark).unwrap();
+        println!("  [dark][test_subtract_dark()] light = \n{}", light.data());
+        // 負になる画素は0
+        assert_eq!(
+            vec![36, 136, 230, 0, 0, 1],
+            light.data().iter().copied().collect::<Vec<u16>>()
+        );
+
+        let mut light = NARaw::<i16>::new_from_vector2d(&[vec![10, 20]]);
+        let dark = NARaw::<i16>::new_from_vector2d(&[vec![15, 5]]);
+        light.subtract_dark(&dark).unwrap();
+        assert_eq!((-5, 15), (*light.pix(0, 0), *light.pix(1, 0)));
+
+        let result = light.subtract_dark(&NARaw::<i16>::new(3, 1));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_subtract_dark_ignoring_hot() {
+        println!("dark::test::test_subtract_dark_ignoring_hot()  {{");
+
+        let light = NDRaw::<u16>::new_from_vector2d(&vec![vec![1000; 7]; 7]);
+        let mut dark = NDRaw::<u16>::new_from_vector2d(&vec![vec![60; 7]; 7]);
+        *dark.pix_mut(3, 3) = 4000;
+
+        // 通常の減算ではダークのホット画素が穴になる
+        let mut plain = light.clone();
+        plain.subtract_dark(&dark).unwrap();
+        assert_eq!(0, *plain.pix(3, 3));
+
+        let mut hot_aware = light.clone();
+        hot_aware.subtract_dark_ignoring_hot(&dark, 1000).unwrap();
+        println!(
+            "  [dark][test_subtract_dark_ignoring_hot()] hot_aware = \n{}",
+            hot_aware.data()
+        );
+        assert!(hot_aware.data().iter().all(|v| *v == 940));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_checked_sub() {
+        println!("dark::test::test_checked_sub()  {{");
+
+        let small = NDRaw::<u16>::new_from_vector2d(&[vec![10, 20, 30], vec![40, 50, 60]]);
+        let large = NDRaw::<u16>::new_from_vector2d(&[vec![5, 25, 30], vec![41, 50, 70]]);
+
+        // アンダーフローする画素の座標一覧
+        let underflows = small.checked_sub(&large).err().unwrap();
+        println!("  [dark][test_checked_sub()] underflows = {:?}", underflows);
+        assert_eq!(
+            CheckedSubError::Underflow(vec![(1, 0), (0, 1), (2, 1)]),
+            underflows
+        );
+
+        // アンダーフローなし
+        let larger = NDRaw::<u16>::new_from_vector2d(&[vec![15, 25, 30], vec![41, 50, 70]]);
+        let diff = larger.checked_sub(&small).ok().unwrap();
+        assert_eq!(&[5, 5, 0, 1, 0, 10], diff.data().as_slice().unwrap());
+
+        // NARaw・符号付き型は型の最小値を下回る場合のみ
+        let a = NARaw::<i16>::new_from_vector2d(&[vec![-30000, 0], vec![100, -5]]);
+        let b = NARaw::<i16>::new_from_vector2d(&[vec![5000, 30000], vec![200, 10]]);
+        assert_eq!(
+            Some(CheckedSubError::Underflow(vec![(0, 0)])),
+            a.checked_sub(&b).err()
+        );
+        let diff = b.checked_sub(&a).ok().unwrap();
+        assert_eq!(30000, *diff.pix(1, 0));
+        assert_eq!(15, *diff.pix(1, 1));
+
+        // サイズ不一致はエラー
+        let other = NDRaw::<u16>::new_from_vector2d(&[vec![1, 2], vec![3, 4]]);
+        let result = small.checked_sub(&other);
+        println!(
+            "  [dark][test_checked_sub()] result = {:?}",
+            result.as_ref().err()
+        );
+        assert!(matches!(result, Err(CheckedSubError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
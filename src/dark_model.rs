@@ -0,0 +1,124 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 暗電流モデル (dark = baseline + slope_per_us * exposure_us)
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct DarkCurrentModel {
+    pub baseline: f64,
+    pub slope_per_us: f64,
+}
+
+impl DarkCurrentModel {
+    // 露光時間に対する暗レベル予測値
+    pub fn predict(&self, exposure_us: u32) -> f64 {
+        self.baseline + self.slope_per_us * exposure_us as f64
+    }
+
+    // 予測ダークフレーム (全画素一様)
+    pub fn predict_dark_frame(&self, width: usize, height: usize, exposure_us: u32) -> NDRaw<f32> {
+        let data = ndarray::Array2::from_elem((height, width), self.predict(exposure_us) as f32);
+        NDRaw::from_ndarray(data)
+    }
+}
+
+// 露光時間の異なるダークフレーム群の平均値から暗電流モデルを最小二乗推定
+pub fn compute_dark_current_model<T: PixelType>(
+    exposures_us: &[u32],
+    dark_frames: &[NDRaw<T>],
+) -> Result<DarkCurrentModel, SensorIoError> {
+    if exposures_us.len() != dark_frames.len() {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "{} exposures given for {} dark frames",
+            exposures_us.len(),
+            dark_frames.len()
+        )));
+    }
+    let n = exposures_us.len() as f64;
+    let xs: Vec<f64> = exposures_us.iter().map(|e| *e as f64).collect();
+    let ys: Vec<f64> = dark_frames
+        .iter()
+        .map(|frame| frame.compute_statistics().mean)
+        .collect();
+    let x_mean = xs.iter().sum::<f64>() / n;
+    let y_mean = ys.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - x_mean).powi(2)).sum();
+    if sxx <= 0.0 {
+        return Err(SensorIoError::InvalidArgument(String::from(
+            "at least two distinct exposure times are needed",
+        )));
+    }
+    let sxy: f64 = xs
+        .iter()
+        .zip(ys.iter())
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum();
+    let slope_per_us = sxy / sxx;
+    Ok(DarkCurrentModel {
+        baseline: y_mean - slope_per_us * x_mean,
+        slope_per_us,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compute_dark_current_model, DarkCurrentModel};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 既知モデルのダークフレーム (画素毎に±2のばらつき)
+    fn dark_frame(model: &DarkCurrentModel, exposure_us: u32) -> NDRaw<u16> {
+        let level = model.predict(exposure_us);
+        let vec2d: Vec<Vec<u16>> = (0..16)
+            .map(|y| {
+                (0..16)
+                    .map(|x| (level + [-2.0, -1.0, 0.0, 1.0, 2.0][(x + 2 * y) % 5]).round() as u16)
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_compute_dark_current_model() {
+        println!("dark_model::test::test_compute_dark_current_model()  {{");
+
+        let truth = DarkCurrentModel {
+            baseline: 64.0,
+            slope_per_us: 0.002,
+        };
+        let exposures_us = [10_000, 100_000];
+        let frames: Vec<NDRaw<u16>> = exposures_us
+            .iter()
+            .map(|e| dark_frame(&truth, *e))
+            .collect();
+        let model = compute_dark_current_model(&exposures_us, &frames).unwrap();
+        println!(
+            "  [dark_model][test_compute_dark_current_model()] model = {:?}",
+            model
+        );
+        assert!((model.baseline - truth.baseline).abs() / truth.baseline < 0.01);
+        assert!((model.slope_per_us - truth.slope_per_us).abs() / truth.slope_per_us < 0.01);
+
+        let predicted = model.predict_dark_frame(8, 4, 50_000);
+        assert_eq!((8, 4), (predicted.width(), predicted.height()));
+        assert!((*predicted.pix(3, 2) as f64 - 164.0).abs() < 1.64);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_dark_current_model_invalid() {
+        println!("dark_model::test::test_compute_dark_current_model_invalid()  {{");
+
+        let frame = NDRaw::<u16>::new(4, 4);
+        let result = compute_dark_current_model(&[100], &[frame.clone(), frame.clone()]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = compute_dark_current_model(&[100, 100], &[frame.clone(), frame]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = compute_dark_current_model::<u16>(&[], &[]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
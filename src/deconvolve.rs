@@ -0,0 +1,167 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::spectrum::fft2d;
+use rustfft::num_complex::Complex;
+
+impl<T: PixelType> NDRaw<T> {
+    // Wienerデコンボリューション (G = H* / (|H|^2 + 1/SNR), Hは画像サイズにゼロ埋めしたPSFのFFT)
+    //   PSFの中心(width/2, height/2)を原点に合わせるため位置ずれは生じない, 境界は周期境界として扱う
+    pub fn deconvolve_wiener(
+        &self,
+        psf: &NDRaw<f32>,
+        snr_db: f32,
+    ) -> Result<NDRaw<f32>, SensorIoError> {
+        let (width, height) = (self.width(), self.height());
+        if psf.width() > width || psf.height() > height {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "PSF {}x{} is larger than the image {}x{}",
+                psf.width(),
+                psf.height(),
+                width,
+                height
+            )));
+        }
+        if snr_db.is_nan() || snr_db < 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "snr_db must be non-negative, got {}",
+                snr_db
+            )));
+        }
+        if width == 0 || height == 0 {
+            return Ok(NDRaw::<f32>::new(width, height));
+        }
+
+        // PSFのゼロ埋め (中心を原点へ巡回シフト)
+        let mut h = vec![Complex::new(0.0, 0.0); width * height];
+        let (pcx, pcy) = (psf.width() / 2, psf.height() / 2);
+        for ((py, px), v) in psf.data.indexed_iter() {
+            let x = (px + width - pcx) % width;
+            let y = (py + height - pcy) % height;
+            h[y * width + x] = Complex::new(*v as f64, 0.0);
+        }
+        fft2d(&mut h, width, height, false);
+
+        let mut g: Vec<Complex<f64>> = self
+            .data
+            .iter()
+            .map(|p| Complex::new(p.to_f64().unwrap(), 0.0))
+            .collect();
+        fft2d(&mut g, width, height, false);
+
+        let inv_snr = 10f64.powf(-snr_db as f64 / 10.0);
+        for (gv, hv) in g.iter_mut().zip(h.iter()) {
+            *gv *= hv.conj() / (hv.norm_sqr() + inv_snr);
+        }
+        fft2d(&mut g, width, height, true);
+
+        let data =
+            ndarray::Array2::from_shape_fn((height, width), |(y, x)| g[y * width + x].re as f32);
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 正規化ガウシアンPSF
+    fn gaussian_psf(half_size: usize, sigma: f64) -> NDRaw<f32> {
+        let size = 2 * half_size + 1;
+        let weight = |x: usize, y: usize| {
+            let (dx, dy) = (x as f64 - half_size as f64, y as f64 - half_size as f64);
+            (-(dx * dx + dy * dy) / (2.0 * sigma * sigma)).exp()
+        };
+        let sum: f64 = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .map(|(x, y)| weight(x, y))
+            .sum();
+        let vec2d: Vec<Vec<f32>> = (0..size)
+            .map(|y| (0..size).map(|x| (weight(x, y) / sum) as f32).collect())
+            .collect();
+        NDRaw::<f32>::new_from_vector2d(&vec2d)
+    }
+
+    // 巡回畳み込み
+    fn convolve(raw: &NDRaw<f32>, psf: &NDRaw<f32>) -> NDRaw<f32> {
+        let (width, height) = (raw.width(), raw.height());
+        let (pcx, pcy) = (psf.width() / 2, psf.height() / 2);
+        let vec2d: Vec<Vec<f32>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let mut sum = 0.0;
+                        for ((py, px), w) in psf.data().indexed_iter() {
+                            let sx = (x + width + pcx - px) % width;
+                            let sy = (y + height + pcy - py) % height;
+                            sum += w * raw.pix(sx, sy);
+                        }
+                        sum
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<f32>::new_from_vector2d(&vec2d)
+    }
+
+    fn rms_diff(a: &NDRaw<f32>, b: &NDRaw<f32>) -> f64 {
+        let sum: f64 = a
+            .data()
+            .iter()
+            .zip(b.data().iter())
+            .map(|(p, q)| (*p as f64 - *q as f64).powi(2))
+            .sum();
+        (sum / a.data().len() as f64).sqrt()
+    }
+
+    #[test]
+    fn test_deconvolve_wiener() {
+        println!("deconvolve::test::test_deconvolve_wiener()  {{");
+
+        // 格子状の点光源とエッジ
+        let vec2d: Vec<Vec<f32>> = (0..32)
+            .map(|y| {
+                (0..40)
+                    .map(|x| {
+                        let dot = if x % 8 == 4 && y % 8 == 4 {
+                            1000.0
+                        } else {
+                            0.0
+                        };
+                        let edge = if x >= 20 { 200.0 } else { 50.0 };
+                        dot + edge
+                    })
+                    .collect()
+            })
+            .collect();
+        let original = NDRaw::<f32>::new_from_vector2d(&vec2d);
+        let psf = gaussian_psf(3, 1.0);
+        let blurred = convolve(&original, &psf);
+
+        let restored = blurred.deconvolve_wiener(&psf, 60.0).unwrap();
+        let blurred_error = rms_diff(&blurred, &original);
+        let restored_error = rms_diff(&restored, &original);
+        println!(
+            "  [deconvolve][test_deconvolve_wiener()] rms(blurred) = {}, rms(restored) = {}",
+            blurred_error, restored_error
+        );
+        assert_eq!((40, 32), (restored.width(), restored.height()));
+        assert!(restored_error < blurred_error / 2.0);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_deconvolve_wiener_invalid() {
+        println!("deconvolve::test::test_deconvolve_wiener_invalid()  {{");
+
+        let raw = NDRaw::<u16>::new(8, 8);
+        let result = raw.deconvolve_wiener(&gaussian_psf(5, 1.0), 60.0);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = raw.deconvolve_wiener(&gaussian_psf(1, 1.0), -1.0);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
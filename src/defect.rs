@@ -0,0 +1,331 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::raw::RawImage;
+use crate::statistics::median;
+use num_traits::ToPrimitive;
+use std::collections::HashSet;
+
+// 欠陥種別
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum DefectKind {
+    Hot,
+    Dead,
+    Stuck,
+}
+
+// 欠陥画素
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DefectPixel {
+    pub x: usize,
+    pub y: usize,
+    pub kind: DefectKind,
+}
+
+impl DefectPixel {
+    pub fn new(x: usize, y: usize, kind: DefectKind) -> Self {
+        DefectPixel { x, y, kind }
+    }
+}
+
+// 欠陥補正方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DefectCorrection {
+    // 同色近傍8画素の中央値
+    Median,
+    // 水平/垂直のうち勾配の小さい方向で補間
+    Directional,
+}
+
+// 同色近傍オフセット (Bayerでは2画素おきに同色)
+pub(crate) const SAME_CHANNEL_OFFSETS: [(isize, isize); 8] = [
+    (-2, -2),
+    (0, -2),
+    (2, -2),
+    (-2, 0),
+    (2, 0),
+    (-2, 2),
+    (0, 2),
+    (2, 2),
+];
+
+impl<T: PixelType> NDRaw<T> {
+    // ホットピクセル検出 (同色近傍の中央値をthresholdより大きく超える画素の(x, y)一覧)
+    pub fn detect_hot_pixels(&self, threshold: T) -> Vec<(usize, usize)> {
+        let no_defects = HashSet::new();
+        let threshold = threshold.to_f64().unwrap();
+        let mut hot_pixels = Vec::new();
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if let Some(m) = median_of_neighbors(self, x, y, &no_defects) {
+                    if self.data[[y, x]].to_f64().unwrap() - m > threshold {
+                        hot_pixels.push((x, y));
+                    }
+                }
+            }
+        }
+        hot_pixels
+    }
+}
+
+// 固着画素検出 (明画像と暗画像の差(bright - dark)がmin_response未満の画素の(x, y)一覧, サイズ不一致はエラー)
+pub fn stuck_pixel_map<T: PixelType>(
+    bright: &NDRaw<T>,
+    dark: &NDRaw<T>,
+    min_response: T,
+) -> Result<Vec<(usize, usize)>, SensorIoError> {
+    check_shape(
+        (bright.width(), bright.height()),
+        (dark.width(), dark.height()),
+    )?;
+    let min_response = min_response.to_f64().unwrap();
+    let mut stuck_pixels = Vec::new();
+    for y in 0..bright.height() {
+        for x in 0..bright.width() {
+            let response =
+                bright.data[[y, x]].to_f64().unwrap() - dark.data[[y, x]].to_f64().unwrap();
+            if response < min_response {
+                stuck_pixels.push((x, y));
+            }
+        }
+    }
+    Ok(stuck_pixels)
+}
+
+// 欠陥画素補正 (欠陥画素同士は参照しない)
+pub(crate) fn correct_defects<R: RawImage + ?Sized>(
+    raw: &mut R,
+    defects: &[DefectPixel],
+    method: DefectCorrection,
+) {
+    let defect_set: HashSet<(usize, usize)> = defects.iter().map(|d| (d.x, d.y)).collect();
+
+    // 全補正値を元画像から算出してから書き込む
+    let corrections: Vec<(usize, usize, f64)> = defects
+        .iter()
+        .filter(|d| d.x < raw.width() && d.y < raw.height())
+        .filter_map(|d| {
+            let value = match method {
+                DefectCorrection::Median => median_of_neighbors(raw, d.x, d.y, &defect_set),
+                DefectCorrection::Directional => directional(raw, d.x, d.y, &defect_set)
+                    .or_else(|| median_of_neighbors(raw, d.x, d.y, &defect_set)),
+            };
+            value.map(|v| (d.x, d.y, v))
+        })
+        .collect();
+
+    for (x, y, v) in corrections {
+        *raw.pix_mut(x, y) = R::Pixel::from_f64_saturating(v);
+    }
+}
+
+// 正常な近傍画素値取得
+fn valid_neighbor<R: RawImage + ?Sized>(
+    raw: &R,
+    x: usize,
+    y: usize,
+    dx: isize,
+    dy: isize,
+    defect_set: &HashSet<(usize, usize)>,
+) -> Option<f64> {
+    let nx = x.checked_add_signed(dx)?;
+    let ny = y.checked_add_signed(dy)?;
+    if nx >= raw.width() || ny >= raw.height() || defect_set.contains(&(nx, ny)) {
+        return None;
+    }
+    raw.pix(nx, ny).to_f64()
+}
+
+// 同色近傍8画素(欠陥画素を除く)の中央値
+pub(crate) fn median_of_neighbors<R: RawImage + ?Sized>(
+    raw: &R,
+    x: usize,
+    y: usize,
+    defect_set: &HashSet<(usize, usize)>,
+) -> Option<f64> {
+    let mut values: Vec<f64> = SAME_CHANNEL_OFFSETS
+        .iter()
+        .filter_map(|&(dx, dy)| valid_neighbor(raw, x, y, dx, dy, defect_set))
+        .collect();
+    median(&mut values)
+}
+
+fn directional<R: RawImage + ?Sized>(
+    raw: &R,
+    x: usize,
+    y: usize,
+    defect_set: &HashSet<(usize, usize)>,
+) -> Option<f64> {
+    // (勾配, 補間値)
+    let axis = |dx: isize, dy: isize| -> Option<(f64, f64)> {
+        let a = valid_neighbor(raw, x, y, -dx, -dy, defect_set)?;
+        let b = valid_neighbor(raw, x, y, dx, dy, defect_set)?;
+        Some(((a - b).abs(), (a + b) / 2.0))
+    };
+    match (axis(2, 0), axis(0, 2)) {
+        (Some((gh, h)), Some((gv, v))) => {
+            if gh < gv {
+                Some(h)
+            } else if gv < gh {
+                Some(v)
+            } else {
+                Some((h + v) / 2.0)
+            }
+        }
+        (Some((_, h)), None) => Some(h),
+        (None, Some((_, v))) => Some(v),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{stuck_pixel_map, DefectCorrection, DefectKind, DefectPixel};
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    #[test]
+    fn test_correct_defects_median() {
+        println!("defect::test::test_correct_defects_median()  {{");
+
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 7]; 7]);
+        // 同色近傍: 10, 20, ..., 80 => 中央値45
+        let neighbors = [
+            (1, 1),
+            (3, 1),
+            (5, 1),
+            (1, 3),
+            (5, 3),
+            (1, 5),
+            (3, 5),
+            (5, 5),
+        ];
+        for (i, (x, y)) in neighbors.iter().enumerate() {
+            *raw_in.pix_mut(*x, *y) = (i as u16 + 1) * 10;
+        }
+        *raw_in.pix_mut(3, 3) = 4000;
+
+        let defects = [DefectPixel::new(3, 3, DefectKind::Hot)];
+        raw_in.correct_defects(&defects, DefectCorrection::Median);
+        println!(
+            "  [defect][test_correct_defects_median()] raw_in.data() = \n{}",
+            raw_in.data()
+        );
+        assert_eq!(45, *raw_in.pix(3, 3));
+        assert_eq!(10, *raw_in.pix(1, 1));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_correct_defects_cluster() {
+        println!("defect::test::test_correct_defects_cluster()  {{");
+
+        let mut raw_in = NARaw::<u16>::new_from_vector2d(&vec![vec![100; 8]; 8]);
+        // 隣接2x1クラスタ + 同色隣接(2画素おき)の欠陥
+        let defects = [
+            DefectPixel::new(3, 3, DefectKind::Hot),
+            DefectPixel::new(4, 3, DefectKind::Hot),
+            DefectPixel::new(5, 3, DefectKind::Hot),
+        ];
+        for d in defects.iter() {
+            *raw_in.pix_mut(d.x, d.y) = 4000;
+        }
+
+        for method in [DefectCorrection::Median, DefectCorrection::Directional] {
+            let mut raw_out = raw_in.clone();
+            raw_out.correct_defects(&defects, method);
+            println!(
+                "  [defect][test_correct_defects_cluster()] {:?} = {}",
+                method,
+                raw_out.data()
+            );
+            for d in defects.iter() {
+                assert_eq!(100, *raw_out.pix(d.x, d.y));
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_detect_hot_pixels() {
+        println!("defect::test::test_detect_hot_pixels()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| (0..8).map(|x| 100 + ((x * 3 + y * 7) % 5) as u16).collect())
+            .collect();
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        assert!(raw_in.detect_hot_pixels(50).is_empty());
+
+        *raw_in.pix_mut(5, 2) = 1000;
+        let hot_pixels = raw_in.detect_hot_pixels(50);
+        println!(
+            "  [defect][test_detect_hot_pixels()] hot_pixels = {:?}",
+            hot_pixels
+        );
+        assert_eq!(vec![(5, 2)], hot_pixels);
+
+        // 検出結果をそのまま補正へ
+        let defects: Vec<DefectPixel> = hot_pixels
+            .iter()
+            .map(|&(x, y)| DefectPixel::new(x, y, DefectKind::Hot))
+            .collect();
+        raw_in.correct_defects(&defects, DefectCorrection::Median);
+        assert!(*raw_in.pix(5, 2) < 110);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_stuck_pixel_map() {
+        println!("defect::test::test_stuck_pixel_map()  {{");
+
+        let dark = NDRaw::<u16>::new_from_vector2d(&vec![vec![64; 6]; 4]);
+        let mut bright = NDRaw::<u16>::new_from_vector2d(&vec![vec![3000; 6]; 4]);
+        // 暗画像と同値の固着画素, 応答の弱い画素
+        *bright.pix_mut(2, 1) = 64;
+        *bright.pix_mut(4, 3) = 500;
+        // 明画像より暗画像が明るい画素も応答なしとみなす
+        *bright.pix_mut(0, 0) = 10;
+
+        let stuck_pixels = stuck_pixel_map(&bright, &dark, 1000).unwrap();
+        println!(
+            "  [defect][test_stuck_pixel_map()] stuck_pixels = {:?}",
+            stuck_pixels
+        );
+        assert_eq!(vec![(0, 0), (2, 1), (4, 3)], stuck_pixels);
+        assert_eq!(
+            vec![(0, 0), (2, 1)],
+            stuck_pixel_map(&bright, &dark, 100).unwrap()
+        );
+        let result = stuck_pixel_map(&bright, &NDRaw::<u16>::new(4, 6), 100);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_correct_defects_directional() {
+        println!("defect::test::test_correct_defects_directional()  {{");
+
+        // 縦エッジ: x < 3 は10, x >= 3 は200
+        let vec2d: Vec<Vec<u16>> = (0..7)
+            .map(|_| (0..7).map(|x| if x < 3 { 10 } else { 200 }).collect())
+            .collect();
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        *raw_in.pix_mut(3, 3) = 0;
+
+        let defects = [DefectPixel::new(3, 3, DefectKind::Dead)];
+        raw_in.correct_defects(&defects, DefectCorrection::Directional);
+        println!(
+            "  [defect][test_correct_defects_directional()] raw_in.pix(3, 3) = {}",
+            raw_in.pix(3, 3)
+        );
+        assert_eq!(200, *raw_in.pix(3, 3));
+
+        println!("}}");
+    }
+}
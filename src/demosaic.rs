@@ -0,0 +1,187 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // バイリニアデモザイク => [R, G, B] (全解像度)
+    //   各画素の3x3近傍にある同色画素の平均 (自身がその色なら元の値, 画像外の近傍は除く)
+    pub fn demosaic_planes(&self, pattern: BayerPattern) -> [NDRaw<T>; 3] {
+        let (width, height) = (self.width(), self.height());
+        // Bayerチャネル => RGB面の番号
+        let plane_of = |x: usize, y: usize| match pattern.channel_at(x, y) {
+            BayerChannel::R => 0,
+            BayerChannel::Gr | BayerChannel::Gb => 1,
+            BayerChannel::B => 2,
+        };
+        [0, 1, 2].map(|plane| {
+            let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+                if plane_of(x, y) == plane {
+                    return self.data[[y, x]];
+                }
+                let (mut sum, mut count) = (0.0, 0);
+                for ny in y.saturating_sub(1)..(y + 2).min(height) {
+                    for nx in x.saturating_sub(1)..(x + 2).min(width) {
+                        if plane_of(nx, ny) == plane {
+                            sum += self.data[[ny, nx]].to_f64().unwrap();
+                            count += 1;
+                        }
+                    }
+                }
+                if count == 0 {
+                    T::zero()
+                } else {
+                    T::from_f64_saturating(sum / count as f64)
+                }
+            });
+            NDRaw::from_ndarray(data)
+        })
+    }
+
+    // G面のみの全解像度補間 (フォーカス評価・位置合わせ用, フルデモザイクより軽量)
+    //   G画素は元の値, R/B画素は勾配の小さい方向(水平/垂直)の上下or左右のG平均 (同じなら4近傍平均)
+    //   画像外の近傍は反対側の近傍で補完
+    pub fn interpolate_green(&self, pattern: BayerPattern) -> ndarray::Array2<T> {
+        let (height, width) = self.data.dim();
+        // 画像外の座標を反対側に折り返す
+        let reflect = |i: isize, len: usize| -> usize {
+            let len = len as isize;
+            let i = if i < 0 {
+                -i
+            } else if i >= len {
+                2 * (len - 1) - i
+            } else {
+                i
+            };
+            i.clamp(0, len - 1) as usize
+        };
+        ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            if matches!(
+                pattern.channel_at(x, y),
+                BayerChannel::Gr | BayerChannel::Gb
+            ) {
+                return self.data[[y, x]];
+            }
+            let g = |dx: isize, dy: isize| {
+                let sx = reflect(x as isize + dx, width);
+                let sy = reflect(y as isize + dy, height);
+                self.data[[sy, sx]].to_f64().unwrap()
+            };
+            let (left, right, up, down) = (g(-1, 0), g(1, 0), g(0, -1), g(0, 1));
+            let (grad_h, grad_v) = ((left - right).abs(), (up - down).abs());
+            let value = if grad_h < grad_v {
+                (left + right) / 2.0
+            } else if grad_v < grad_h {
+                (up + down) / 2.0
+            } else {
+                (left + right + up + down) / 4.0
+            };
+            T::from_f64_saturating(value)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::{BayerChannel, BayerPattern};
+    use crate::ndraw::NDRaw;
+
+    // 色毎の線形グラデーション (R: 100 + 10x + 20y, G: 1000 + 5x + 5y, B: 3000 - 10x + 10y)
+    fn scene(x: usize, y: usize) -> [f64; 3] {
+        let (x, y) = (x as f64, y as f64);
+        [
+            100.0 + 10.0 * x + 20.0 * y,
+            1000.0 + 5.0 * x + 5.0 * y,
+            3000.0 - 10.0 * x + 10.0 * y,
+        ]
+    }
+
+    #[test]
+    fn test_demosaic_planes() {
+        println!("demosaic::test::test_demosaic_planes()  {{");
+
+        for pattern in BayerPattern::ALL {
+            let mut raw = NDRaw::<f32>::new(8, 6);
+            for y in 0..6 {
+                for x in 0..8 {
+                    let [r, g, b] = scene(x, y);
+                    *raw.pix_mut(x, y) = match pattern.channel_at(x, y) {
+                        BayerChannel::R => r,
+                        BayerChannel::Gr | BayerChannel::Gb => g,
+                        BayerChannel::B => b,
+                    } as f32;
+                }
+            }
+
+            let planes = raw.demosaic_planes(pattern);
+            for plane in &planes {
+                assert_eq!((8, 6), (plane.width(), plane.height()));
+            }
+            if pattern == BayerPattern::Rggb {
+                println!(
+                    "  [demosaic][test_demosaic_planes()] R = \n{}",
+                    planes[0].data()
+                );
+            }
+            // 線形なシーンは内側で正確に復元される
+            for y in 1..5 {
+                for x in 1..7 {
+                    let expected = scene(x, y);
+                    for (c, plane) in planes.iter().enumerate() {
+                        assert!((*plane.pix(x, y) as f64 - expected[c]).abs() < 1e-3);
+                    }
+                }
+            }
+        }
+
+        // 整数型: RGGBのB位置(1, 1)のRは斜め4画素の平均
+        let raw =
+            NDRaw::<u16>::new_from_vector2d(&[vec![10, 0, 30], vec![0, 0, 0], vec![50, 0, 71]]);
+        let [r, _, _] = raw.demosaic_planes(BayerPattern::Rggb);
+        assert_eq!(40, *r.pix(1, 1));
+        assert_eq!(20, *r.pix(1, 0));
+        assert_eq!(10, *r.pix(0, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_interpolate_green() {
+        println!("demosaic::test::test_interpolate_green()  {{");
+
+        // 垂直エッジ (x < 3: 100, x >= 3: 900, G画素は+5)
+        let pattern = BayerPattern::Rggb;
+        let mut raw = NDRaw::<u16>::new(8, 6);
+        for y in 0..6 {
+            for x in 0..8 {
+                let base = if x < 3 { 100 } else { 900 };
+                *raw.pix_mut(x, y) = match pattern.channel_at(x, y) {
+                    BayerChannel::Gr | BayerChannel::Gb => base + 5,
+                    _ => base,
+                };
+            }
+        }
+
+        let green = raw.interpolate_green(pattern);
+        println!("  [demosaic][test_interpolate_green()] green = \n{}", green);
+        assert_eq!((6, 8), green.dim());
+        // G画素は元の値のまま
+        for y in 0..6 {
+            for x in 0..8 {
+                if matches!(
+                    pattern.channel_at(x, y),
+                    BayerChannel::Gr | BayerChannel::Gb
+                ) {
+                    assert_eq!(*raw.pix(x, y), green[[y, x]]);
+                }
+            }
+        }
+        // エッジ上のR画素(2, 0)・B画素(3, 1)は垂直方向で補間 (水平平均なら505)
+        assert_eq!(105, green[[0, 2]]);
+        assert_eq!(905, green[[1, 3]]);
+        // 平坦部は勾配が等しく4近傍平均 (左端は反対側で補完)
+        assert_eq!(105, green[[0, 0]]);
+        assert_eq!(905, green[[3, 7]]);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,154 @@
+use crate::error::SensorIoError;
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// ビット深度変換方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DepthScale {
+    // 拡張: 上位へシフトし下位ビットを上位ビットの繰り返しで埋める, 縮小: 右シフト
+    ShiftReplicate,
+    // v * max_to / max_from (最近接丸め)
+    Linear,
+}
+
+fn check_bits(from_bits: u32, to_bits: u32) -> Result<(), SensorIoError> {
+    for bits in [from_bits, to_bits] {
+        if !(1..=32).contains(&bits) {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "bit depth {} is out of range 1..=32",
+                bits
+            )));
+        }
+    }
+    Ok(())
+}
+
+// 1画素の深度変換 (from_bitsの最大値を超える値は最大値に, 負値は0に飽和)
+fn scale_depth<T: PixelType>(v: T, from_bits: u32, to_bits: u32, method: DepthScale) -> T {
+    let max_from = (1u64 << from_bits) - 1;
+    let max_to = (1u64 << to_bits) - 1;
+    let v = v.to_u64().unwrap_or(0).min(max_from);
+    let out = match method {
+        DepthScale::ShiftReplicate if to_bits <= from_bits => v >> (from_bits - to_bits),
+        DepthScale::ShiftReplicate => {
+            // 例: 10 => 16bit は v << 6 | v >> 4
+            let mut out = 0;
+            let mut shift = to_bits as i64 - from_bits as i64;
+            while shift > -(from_bits as i64) {
+                out |= if shift >= 0 { v << shift } else { v >> -shift };
+                shift -= from_bits as i64;
+            }
+            out
+        }
+        DepthScale::Linear => (v * max_to + max_from / 2) / max_from,
+    };
+    T::from_f64_saturating(out as f64)
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // ビット深度変換 (拡張・縮小とも)
+    pub fn expand_depth(
+        &self,
+        from_bits: u32,
+        to_bits: u32,
+        method: DepthScale,
+    ) -> Result<Self, SensorIoError> {
+        check_bits(from_bits, to_bits)?;
+        let data = self
+            .data
+            .mapv(|v| scale_depth(v, from_bits, to_bits, method));
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // ビット深度変換 (拡張・縮小とも)
+    pub fn expand_depth(
+        &self,
+        from_bits: u32,
+        to_bits: u32,
+        method: DepthScale,
+    ) -> Result<Self, SensorIoError> {
+        check_bits(from_bits, to_bits)?;
+        let data = self
+            .data
+            .map(|v| scale_depth(v, from_bits, to_bits, method));
+        Ok(NARaw { data })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::DepthScale;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_expand_depth() {
+        println!("depth::test::test_expand_depth()  {{");
+
+        // 10 => 16bit (0, 最大値, 中間値)
+        let raw_10 = NDRaw::<u16>::new_from_vector2d(&[vec![0, 1023, 512, 1]]);
+        let shifted = raw_10
+            .expand_depth(10, 16, DepthScale::ShiftReplicate)
+            .unwrap();
+        let linear = raw_10.expand_depth(10, 16, DepthScale::Linear).unwrap();
+        println!(
+            "  [depth][test_expand_depth()] 10 => 16: shift = {}, linear = {}",
+            shifted.data(),
+            linear.data()
+        );
+        assert_eq!(
+            &[0, 65535, (512 << 6) | (512 >> 4), 1 << 6],
+            shifted.data().as_slice().unwrap()
+        );
+        assert_eq!(&[0, 65535, 32800, 64], linear.data().as_slice().unwrap());
+
+        // 12 => 16bit
+        let raw_12 = NDRaw::<u16>::new_from_vector2d(&[vec![0, 4095, 2048]]);
+        let shifted = raw_12
+            .expand_depth(12, 16, DepthScale::ShiftReplicate)
+            .unwrap();
+        let linear = raw_12.expand_depth(12, 16, DepthScale::Linear).unwrap();
+        assert_eq!(&[0, 65535, 32776], shifted.data().as_slice().unwrap());
+        assert_eq!(&[0, 65535, 32776], linear.data().as_slice().unwrap());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_narrow_depth() {
+        println!("depth::test::test_narrow_depth()  {{");
+
+        let raw_16 = NARaw::<u16>::new_from_vector2d(&[vec![0, 65535, 32800, 63]]);
+        let shifted = raw_16
+            .expand_depth(16, 10, DepthScale::ShiftReplicate)
+            .unwrap();
+        let linear = raw_16.expand_depth(16, 10, DepthScale::Linear).unwrap();
+        println!(
+            "  [depth][test_narrow_depth()] 16 => 10: shift = {}, linear = {}",
+            shifted.data(),
+            linear.data()
+        );
+        assert_eq!(&[0, 1023, 512, 0], shifted.data().as_slice());
+        assert_eq!(&[0, 1023, 512, 1], linear.data().as_slice());
+
+        // 往復で元に戻る
+        let raw_10 = NDRaw::<u16>::new_from_vector2d(&[(0..1024).step_by(7).collect()]);
+        for method in [DepthScale::ShiftReplicate, DepthScale::Linear] {
+            let restored = raw_10
+                .expand_depth(10, 16, method)
+                .unwrap()
+                .expand_depth(16, 10, method)
+                .unwrap();
+            assert_eq!(raw_10.data(), restored.data());
+        }
+
+        let result = raw_16.expand_depth(0, 16, DepthScale::Linear);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
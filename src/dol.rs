@@ -0,0 +1,177 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// DOL(Digital Overlap) HDRの行インターリーブ形式
+//   出力の各露光画像はセンサの行順に並ぶため, Bayer配列はセンサと同じになる
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DolLayout {
+    // 1行毎に交互 (L S L S ...)
+    PerLine(DolPhase),
+    // 2行(Bayer 1周期)毎に交互 (L L S S ...)
+    PerTwoLines(DolPhase),
+}
+
+// 先頭行の露光
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DolPhase {
+    LongFirst,
+    ShortFirst,
+}
+
+impl DolLayout {
+    // インターリーブ1周期の行数
+    fn period(&self) -> usize {
+        match self {
+            DolLayout::PerLine(_) => 2,
+            DolLayout::PerTwoLines(_) => 4,
+        }
+    }
+
+    // 露光画像の行 => インターリーブ画像の行
+    fn frame_row(&self, line: usize, long: bool) -> usize {
+        let (DolLayout::PerLine(phase) | DolLayout::PerTwoLines(phase)) = *self;
+        let slot = usize::from(long != (phase == DolPhase::LongFirst));
+        match self {
+            DolLayout::PerLine(_) => line * 2 + slot,
+            DolLayout::PerTwoLines(_) => line / 2 * 4 + slot * 2 + line % 2,
+        }
+    }
+
+    fn check_height(&self, height: usize) -> Result<(), SensorIoError> {
+        if !height.is_multiple_of(self.period()) {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "height {} is not a multiple of the {:?} period ({} lines)",
+                height,
+                self,
+                self.period()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // DOLインターリーブ画像の分離 => (長秒露光, 短秒露光), 各々高さは半分
+    pub fn split_dol(&self, layout: DolLayout) -> Result<(Self, Self), SensorIoError> {
+        layout.check_height(self.height())?;
+        let dim = (self.height() / 2, self.width());
+        let extract = |long: bool| {
+            let data = ndarray::Array2::from_shape_fn(dim, |(y, x)| {
+                self.data[[layout.frame_row(y, long), x]]
+            });
+            NDRaw::from_ndarray(data)
+        };
+        Ok((extract(true), extract(false)))
+    }
+
+    // 長秒・短秒露光画像のDOLインターリーブ (split_dolの逆変換)
+    pub fn interleave_dol(
+        long: &Self,
+        short: &Self,
+        layout: DolLayout,
+    ) -> Result<Self, SensorIoError> {
+        check_shape(
+            (long.width(), long.height()),
+            (short.width(), short.height()),
+        )?;
+        let height = long.height() * 2;
+        layout.check_height(height)?;
+
+        let mut raw = NDRaw::<T>::new(long.width(), height);
+        for (frame, is_long) in [(long, true), (short, false)] {
+            for (line, row) in frame.data.rows().into_iter().enumerate() {
+                raw.data
+                    .row_mut(layout.frame_row(line, is_long))
+                    .assign(&row);
+            }
+        }
+        Ok(raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DolLayout, DolPhase};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // センサ行yの露光画像 (長秒: 1000 + y, 短秒: 100 + y)
+    fn exposure(base: u16) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..4).map(|y| vec![base + y; 6]).collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_split_dol() {
+        println!("dol::test::test_split_dol()  {{");
+
+        let long = exposure(1000);
+        let short = exposure(100);
+        let cases = [
+            (
+                DolLayout::PerLine(DolPhase::LongFirst),
+                [1000, 100, 1001, 101],
+            ),
+            (
+                DolLayout::PerLine(DolPhase::ShortFirst),
+                [100, 1000, 101, 1001],
+            ),
+            (
+                DolLayout::PerTwoLines(DolPhase::LongFirst),
+                [1000, 1001, 100, 101],
+            ),
+            (
+                DolLayout::PerTwoLines(DolPhase::ShortFirst),
+                [100, 101, 1000, 1001],
+            ),
+        ];
+        for (layout, head) in cases {
+            let frame = NDRaw::<u16>::interleave_dol(&long, &short, layout).unwrap();
+            let rows: Vec<u16> = (0..4).map(|y| *frame.pix(0, y)).collect();
+            println!(
+                "  [dol][test_split_dol()] {:?}: head rows = {:?}",
+                layout, rows
+            );
+            assert_eq!(head.to_vec(), rows);
+
+            // 分離結果は各露光の値のみ, かつ元の行順
+            let (long_out, short_out) = frame.split_dol(layout).unwrap();
+            assert_eq!(long.data(), long_out.data());
+            assert_eq!(short.data(), short_out.data());
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_split_dol_roundtrip() {
+        println!("dol::test::test_split_dol_roundtrip()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| (0..4).map(|x| ((x * 7 + y * 13) % 50) as u16).collect())
+            .collect();
+        let frame = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        for layout in [
+            DolLayout::PerLine(DolPhase::LongFirst),
+            DolLayout::PerTwoLines(DolPhase::ShortFirst),
+        ] {
+            let (long, short) = frame.split_dol(layout).unwrap();
+            assert_eq!((4, 4), (long.width(), long.height()));
+            let restored = NDRaw::<u16>::interleave_dol(&long, &short, layout).unwrap();
+            assert_eq!(frame.data(), restored.data());
+        }
+
+        // 周期に合わない高さ
+        let result = NDRaw::<u16>::new(4, 6).split_dol(DolLayout::PerTwoLines(DolPhase::LongFirst));
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = NDRaw::<u16>::interleave_dol(
+            &NDRaw::<u16>::new(4, 2),
+            &NDRaw::<u16>::new(4, 3),
+            DolLayout::PerLine(DolPhase::LongFirst),
+        );
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,171 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 累積分布によるトーンマップ (昇順の画素値一覧 => 値毎の出力値)
+//   最小値は0, 最大値はmax_codeに写像され, 同値の画素は同じ出力値 (単調非減少)
+//   画素値が1種類しかない場合はNone
+struct Equalization<T> {
+    values: Vec<T>,
+    mapped: Vec<T>,
+}
+
+impl<T: PixelType> Equalization<T> {
+    fn new(mut pixels: Vec<T>, max_code: T) -> Option<Self> {
+        // NaNを含んでもpanicしないようtotal_cmpで整列 (NaNは末尾)
+        pixels.sort_by(|a, b| Self::cmp(a, b));
+        let total = pixels.len();
+        let mut values = Vec::new();
+        let mut cdf = Vec::new();
+        for (i, p) in pixels.iter().enumerate() {
+            if values.last() == Some(p) {
+                *cdf.last_mut().unwrap() = i + 1;
+            } else {
+                values.push(*p);
+                cdf.push(i + 1);
+            }
+        }
+        if values.len() < 2 {
+            return None;
+        }
+
+        let cdf_min = cdf[0];
+        let max_code = max_code.to_f64().unwrap();
+        let mapped = cdf
+            .iter()
+            .map(|c| {
+                let v = (c - cdf_min) as f64 / (total - cdf_min) as f64 * max_code;
+                T::from_f64_saturating(v)
+            })
+            .collect();
+        Some(Equalization { values, mapped })
+    }
+
+    fn apply(&self, p: T) -> T {
+        let i = self
+            .values
+            .partition_point(|v| Self::cmp(v, &p) == std::cmp::Ordering::Less);
+        self.mapped[i.min(self.mapped.len() - 1)]
+    }
+
+    fn cmp(a: &T, b: &T) -> std::cmp::Ordering {
+        a.to_f64().unwrap().total_cmp(&b.to_f64().unwrap())
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // ヒストグラム平坦化 (画像全体の累積分布で0〜max_codeに写像, 一定値の画像はそのまま)
+    pub fn equalize_histogram(&self, max_code: T) -> Self {
+        match Equalization::new(self.data.iter().copied().collect(), max_code) {
+            Some(eq) => NDRaw::from_ndarray(self.data.mapv(|p| eq.apply(p))),
+            None => self.clone(),
+        }
+    }
+
+    // チャネル別ヒストグラム平坦化 (Bayerチャネル毎の累積分布で写像)
+    pub fn equalize_histogram_bayer(&self, max_code: T, pattern: BayerPattern) -> Self {
+        let mut raw = self.clone();
+        for channel in BayerChannel::ALL {
+            let plane = self.bayer_plane(pattern, channel);
+            let Some(eq) = Equalization::new(plane.data.iter().copied().collect(), max_code) else {
+                continue;
+            };
+            for ((y, x), p) in raw.data.indexed_iter_mut() {
+                if pattern.channel_at(x, y) == channel {
+                    *p = eq.apply(*p);
+                }
+            }
+        }
+        raw
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::BayerPattern;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_equalize_histogram() {
+        println!("equalize::test::test_equalize_histogram()  {{");
+
+        // 2階調の暗い画像
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| {
+                (0..4)
+                    .map(|x| if x < 2 || y == 0 { 64 } else { 70 })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let raw_out = raw_in.equalize_histogram(4095);
+        println!(
+            "  [equalize][test_equalize_histogram()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!(0, *raw_out.pix(0, 0));
+        assert_eq!(4095, *raw_out.pix(3, 3));
+
+        // 単調性 (同値は同じ値, 大小関係を保つ)
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&[vec![10, 10, 20, 30, 30, 30, 1000]]);
+        let raw_out = raw_in.equalize_histogram(255);
+        let out = raw_out.data().as_slice().unwrap();
+        println!("  [equalize][test_equalize_histogram()] out = {:?}", out);
+        assert!(out.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(out[0], out[1]);
+        assert_eq!((0, 255), (out[0], out[6]));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_equalize_histogram_constant() {
+        println!("equalize::test::test_equalize_histogram_constant()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 4]; 3]);
+        assert_eq!(raw_in.data(), raw_in.equalize_histogram(4095).data());
+        let raw_in = NDRaw::<f32>::new(0, 0);
+        assert!(raw_in.equalize_histogram(1.0).is_empty());
+
+        // NaNを含む画像でもpanicしない
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&[vec![0.5, f32::NAN, 0.25, 1.0]]);
+        let raw_out = raw_in.equalize_histogram(1.0);
+        assert_eq!(0.0, *raw_out.pix(2, 0));
+        assert_eq!(1.0, *raw_out.pix(1, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_equalize_histogram_bayer() {
+        println!("equalize::test::test_equalize_histogram_bayer()  {{");
+
+        // R画素は100/200, それ以外は1000/3000
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| {
+                (0..4)
+                    .map(|x| match (x % 2, y % 2, y < 2) {
+                        (0, 0, true) => 100,
+                        (0, 0, false) => 200,
+                        (_, _, true) => 1000,
+                        _ => 3000,
+                    })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let raw_out = raw_in.equalize_histogram_bayer(4095, BayerPattern::Rggb);
+        println!(
+            "  [equalize][test_equalize_histogram_bayer()] raw_out = \n{}",
+            raw_out.data()
+        );
+        // 各チャネル独立に0〜4095へ
+        assert_eq!((0, 4095), (*raw_out.pix(0, 0), *raw_out.pix(0, 2)));
+        assert_eq!((0, 4095), (*raw_out.pix(1, 0), *raw_out.pix(1, 2)));
+        // 全体平坦化ではR画素は暗部に寄る
+        let global = raw_in.equalize_histogram(4095);
+        assert!(*global.pix(0, 2) < 4095);
+
+        println!("}}");
+    }
+}
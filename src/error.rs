@@ -0,0 +1,60 @@
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum SensorIoError {
+    Io(io::Error),
+    ShapeMismatch(String),
+    InvalidArgument(String),
+    Parse(String),
+    ChecksumMismatch { expected: u32, actual: u32 },
+    OutOfBounds(String),
+    Unsupported(String),
+}
+
+impl fmt::Display for SensorIoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SensorIoError::Io(e) => write!(f, "I/O error: {}", e),
+            SensorIoError::ShapeMismatch(msg) => write!(f, "shape mismatch: {}", msg),
+            SensorIoError::InvalidArgument(msg) => write!(f, "invalid argument: {}", msg),
+            SensorIoError::Parse(msg) => write!(f, "parse error: {}", msg),
+            SensorIoError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {:08x}, got {:08x}",
+                expected, actual
+            ),
+            SensorIoError::OutOfBounds(msg) => write!(f, "out of bounds: {}", msg),
+            SensorIoError::Unsupported(msg) => write!(f, "unsupported: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SensorIoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SensorIoError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SensorIoError {
+    fn from(e: io::Error) -> Self {
+        SensorIoError::Io(e)
+    }
+}
+
+// 形状(width, height)一致確認
+pub(crate) fn check_shape(
+    expected: (usize, usize),
+    actual: (usize, usize),
+) -> Result<(), SensorIoError> {
+    if expected != actual {
+        return Err(SensorIoError::ShapeMismatch(format!(
+            "expected {}x{}, got {}x{}",
+            expected.0, expected.1, actual.0, actual.1
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // 露出補正係数推定 (target_percentile位置の画素値をtarget_valueに合わせる倍率, 0.1〜10.0)
+    pub fn compute_auto_exposure(&self, target_percentile: f32, target_value: T) -> f32 {
+        const MIN_FACTOR: f32 = 0.1;
+        const MAX_FACTOR: f32 = 10.0;
+
+        let percentile_value = self.compute_percentile(target_percentile).to_f32().unwrap();
+        if percentile_value <= 0.0 {
+            return MAX_FACTOR;
+        }
+        (target_value.to_f32().unwrap() / percentile_value).clamp(MIN_FACTOR, MAX_FACTOR)
+    }
+
+    // 白飛び画素率 (saturation_thresholdを超える画素の割合)
+    pub fn compute_overexposed_fraction(&self, saturation_threshold: T) -> f32 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        let overexposed = self
+            .data
+            .iter()
+            .filter(|pix| **pix > saturation_threshold)
+            .count();
+        overexposed as f32 / self.data.len() as f32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compute_auto_exposure() {
+        println!("exposure::test::test_compute_auto_exposure()  {{");
+
+        // 12bit, 平均がフルスケールの20%付近の露出不足画像
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| (0..8).map(|x| 780 + ((x + y) % 5) as u16 * 20).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        println!(
+            "  [exposure][test_compute_auto_exposure()] mean = {}",
+            raw_in.compute_statistics().mean
+        );
+
+        let factor = raw_in.compute_auto_exposure(0.98, 3686);
+        println!(
+            "  [exposure][test_compute_auto_exposure()] factor = {}",
+            factor
+        );
+        assert!(factor > 1.0);
+        assert!((factor - 3686.0 / 860.0).abs() < 1e-4);
+
+        // 全黒は上限, 過剰露出は下限でクランプ
+        assert_eq!(
+            10.0,
+            NDRaw::<u16>::new(4, 4).compute_auto_exposure(0.98, 3686)
+        );
+        let bright = NDRaw::<u16>::new_from_vector2d(&vec![vec![60000; 4]; 4]);
+        assert_eq!(0.1, bright.compute_auto_exposure(0.98, 10));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_overexposed_fraction() {
+        println!("exposure::test::test_compute_overexposed_fraction()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        assert_eq!(0.25, raw_in.compute_overexposed_fraction(8));
+        assert_eq!(0.0, raw_in.compute_overexposed_fraction(11));
+
+        println!("}}");
+    }
+}
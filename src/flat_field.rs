@@ -0,0 +1,217 @@
+use crate::bayer::BayerPattern;
+use crate::defect::median_of_neighbors;
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use std::collections::HashSet;
+
+// フラット画素がepsilon以下(ゼロ・ほぼゼロ)の場合の扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InvalidFlat {
+    // フラット値をepsilonとして計算
+    Clamp,
+    // フラットの同色近傍中央値で置き換え (正常な近傍が無ければ補正しない)
+    Substitute,
+    // 補正しない
+    Skip,
+}
+
+// フラットフィールド補正オプション
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FlatFieldOptions<T> {
+    // 有効なフラット値の下限
+    pub epsilon: f64,
+    pub invalid: InvalidFlat,
+    // 出力の上限
+    pub white_level: T,
+    // 指定時はチャネル毎のフラット平均で正規化
+    pub pattern: Option<BayerPattern>,
+}
+
+impl<T: PixelType> FlatFieldOptions<T> {
+    // 既定値 (epsilon 1.0, 近傍置換, 全画素平均で正規化)
+    pub fn new(white_level: T) -> Self {
+        FlatFieldOptions {
+            epsilon: 1.0,
+            invalid: InvalidFlat::Substitute,
+            white_level,
+            pattern: None,
+        }
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // フラットフィールド補正 (out = in * mean(flat) / flat, 計算はf64)
+    pub fn apply_flat_field(
+        &mut self,
+        flat: &Self,
+        options: &FlatFieldOptions<T>,
+    ) -> Result<(), SensorIoError> {
+        check_shape((self.width(), self.height()), (flat.width(), flat.height()))?;
+
+        let invalid_set: HashSet<(usize, usize)> = flat
+            .data
+            .indexed_iter()
+            .filter(|(_, p)| p.to_f64().unwrap() <= options.epsilon)
+            .map(|((y, x), _)| (x, y))
+            .collect();
+
+        // 正規化用の平均 (チャネル毎またはまとめて1つ, 無効画素は除く)
+        let channel_of = |x: usize, y: usize| match options.pattern {
+            Some(pattern) => pattern.channel_at(x, y) as usize,
+            None => 0,
+        };
+        let mut sum = [0.0; 4];
+        let mut count = [0usize; 4];
+        for ((y, x), p) in flat.data.indexed_iter() {
+            if !invalid_set.contains(&(x, y)) {
+                sum[channel_of(x, y)] += p.to_f64().unwrap();
+                count[channel_of(x, y)] += 1;
+            }
+        }
+        let mean: Vec<f64> = (0..4).map(|c| sum[c] / count[c].max(1) as f64).collect();
+
+        let white_level = options.white_level.to_f64().unwrap();
+        for ((y, x), pix) in self.data.indexed_iter_mut() {
+            let f = if invalid_set.contains(&(x, y)) {
+                match options.invalid {
+                    InvalidFlat::Clamp => Some(options.epsilon),
+                    InvalidFlat::Substitute => median_of_neighbors(flat, x, y, &invalid_set),
+                    InvalidFlat::Skip => None,
+                }
+            } else {
+                flat.data[[y, x]].to_f64()
+            };
+            if let Some(f) = f.filter(|f| *f > 0.0) {
+                let v = pix.to_f64().unwrap() * mean[channel_of(x, y)] / f;
+                *pix = T::from_f64_saturating(v.min(white_level));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FlatFieldOptions, InvalidFlat};
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    const PATTERN: BayerPattern = BayerPattern::Rggb;
+
+    // 周辺減光ゲイン
+    fn vignette(x: usize, y: usize) -> f64 {
+        let (dx, dy) = (x as f64 - 7.5, y as f64 - 5.5);
+        1.0 - 0.004 * (dx * dx + dy * dy)
+    }
+
+    // チャネル毎の基準値 * 周辺減光
+    fn mosaic(levels: [f64; 4]) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..12)
+            .map(|y| {
+                (0..16)
+                    .map(|x| {
+                        let level = levels[PATTERN.channel_at(x, y) as usize];
+                        (level * vignette(x, y)).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_apply_flat_field() {
+        println!("flat_field::test::test_apply_flat_field()  {{");
+
+        let flat = mosaic([2000.0; 4]);
+        let mut raw = mosaic([1000.0; 4]);
+        raw.apply_flat_field(&flat, &FlatFieldOptions::new(4095))
+            .unwrap();
+        println!(
+            "  [flat_field][test_apply_flat_field()] raw = \n{}",
+            raw.data()
+        );
+        let stats = raw.compute_statistics();
+        assert!(stats.max - stats.min <= 2.0);
+
+        let result = raw.apply_flat_field(&NDRaw::<u16>::new(4, 4), &FlatFieldOptions::new(4095));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_flat_field_per_channel() {
+        println!("flat_field::test::test_apply_flat_field_per_channel()  {{");
+
+        // 色付きのフラット光源 (R:G:B = 1:2:1.5)
+        let flat = mosaic([1000.0, 2000.0, 2000.0, 1500.0]);
+        let scene = [800.0, 800.0, 800.0, 800.0];
+
+        let mut global = mosaic(scene);
+        global
+            .apply_flat_field(&flat, &FlatFieldOptions::new(4095))
+            .unwrap();
+        let mut per_channel = mosaic(scene);
+        let options = FlatFieldOptions {
+            pattern: Some(PATTERN),
+            ..FlatFieldOptions::new(4095)
+        };
+        per_channel.apply_flat_field(&flat, &options).unwrap();
+
+        let stats = per_channel.compute_bayer_statistics(PATTERN);
+        let global_stats = global.compute_bayer_statistics(PATTERN);
+        println!(
+            "  [flat_field][test_apply_flat_field_per_channel()] per channel R/G = {}, global R/G = {}",
+            stats.r.mean / stats.gr.mean,
+            global_stats.r.mean / global_stats.gr.mean
+        );
+        // チャネル毎の正規化では被写体の色比を保つ
+        assert!((stats.r.mean / stats.gr.mean - 1.0).abs() < 0.01);
+        assert!((stats.b.mean / stats.gb.mean - 1.0).abs() < 0.01);
+        assert!((global_stats.r.mean / global_stats.gr.mean - 2.0).abs() < 0.02);
+        assert!(stats.r.max - stats.r.min <= 2.0);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_flat_field_zero_flat() {
+        println!("flat_field::test::test_apply_flat_field_zero_flat()  {{");
+
+        let mut flat = mosaic([2000.0; 4]);
+        *flat.pix_mut(6, 6) = 0;
+        let raw_in = mosaic([1000.0; 4]);
+        let expected = {
+            let mut raw = raw_in.clone();
+            raw.apply_flat_field(&mosaic([2000.0; 4]), &FlatFieldOptions::new(4095))
+                .unwrap();
+            *raw.pix(6, 6)
+        };
+
+        for (invalid, check) in [
+            (InvalidFlat::Substitute, expected as i32),
+            (InvalidFlat::Skip, *raw_in.pix(6, 6) as i32),
+            (InvalidFlat::Clamp, 4095),
+        ] {
+            let mut raw = raw_in.clone();
+            let options = FlatFieldOptions {
+                invalid,
+                ..FlatFieldOptions::new(4095)
+            };
+            raw.apply_flat_field(&flat, &options).unwrap();
+            println!(
+                "  [flat_field][test_apply_flat_field_zero_flat()] {:?}: {} (expected {})",
+                invalid,
+                raw.pix(6, 6),
+                check
+            );
+            // 置換値は同色近傍の中央値なので周辺減光の傾き分の誤差を許容
+            assert!((*raw.pix(6, 6) as i32 - check).abs() <= check / 20);
+        }
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,208 @@
+use crate::bayer::{pattern_after_crop, BayerPattern};
+use crate::error::SensorIoError;
+use crate::raw::RawImage;
+
+// 反転時のBayer配列の扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlipMode {
+    // 画素のみ反転し, Bayer配列を付け替える
+    Relabel,
+    // 反転後に1列/1行ずらし元のBayer配列を保つ (末尾は2画素手前の同色画素で補完)
+    //   補完元のない幅/高さ2の画像はエラー
+    Preserve,
+}
+
+// 左右反転 => 反転後のBayer配列
+pub(crate) fn flip_horizontal<R: RawImage + ?Sized>(
+    raw: &mut R,
+    pattern: BayerPattern,
+    mode: FlipMode,
+) -> Result<BayerPattern, SensorIoError> {
+    let (width, height) = (raw.width(), raw.height());
+    if width == 0 {
+        return Ok(pattern);
+    }
+    let flipped = pattern_after_crop(pattern, width - 1, 0);
+    let preserve = mode == FlipMode::Preserve && flipped != pattern;
+    if preserve && width < 3 {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "width {} is too small to preserve the bayer pattern",
+            width
+        )));
+    }
+
+    for y in 0..height {
+        for x in 0..width / 2 {
+            swap(raw, (x, y), (width - 1 - x, y));
+        }
+    }
+    if !preserve {
+        return Ok(flipped);
+    }
+    for y in 0..height {
+        for x in 0..width - 1 {
+            *raw.pix_mut(x, y) = *raw.pix(x + 1, y);
+        }
+        *raw.pix_mut(width - 1, y) = *raw.pix(width - 3, y);
+    }
+    Ok(pattern)
+}
+
+// 上下反転 => 反転後のBayer配列
+pub(crate) fn flip_vertical<R: RawImage + ?Sized>(
+    raw: &mut R,
+    pattern: BayerPattern,
+    mode: FlipMode,
+) -> Result<BayerPattern, SensorIoError> {
+    let (width, height) = (raw.width(), raw.height());
+    if height == 0 {
+        return Ok(pattern);
+    }
+    let flipped = pattern_after_crop(pattern, 0, height - 1);
+    let preserve = mode == FlipMode::Preserve && flipped != pattern;
+    if preserve && height < 3 {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "height {} is too small to preserve the bayer pattern",
+            height
+        )));
+    }
+
+    for y in 0..height / 2 {
+        for x in 0..width {
+            swap(raw, (x, y), (x, height - 1 - y));
+        }
+    }
+    if !preserve {
+        return Ok(flipped);
+    }
+    for y in 0..height - 1 {
+        for x in 0..width {
+            *raw.pix_mut(x, y) = *raw.pix(x, y + 1);
+        }
+    }
+    for x in 0..width {
+        *raw.pix_mut(x, height - 1) = *raw.pix(x, height - 3);
+    }
+    Ok(pattern)
+}
+
+fn swap<R: RawImage + ?Sized>(raw: &mut R, a: (usize, usize), b: (usize, usize)) {
+    let tmp = *raw.pix(a.0, a.1);
+    *raw.pix_mut(a.0, a.1) = *raw.pix(b.0, b.1);
+    *raw.pix_mut(b.0, b.1) = tmp;
+}
+
+#[cfg(test)]
+mod test {
+    use super::FlipMode;
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    // 画素値 = チャネル番号 * 1000 + 通し番号
+    fn labeled(pattern: BayerPattern, width: usize, height: usize) -> Vec<Vec<u16>> {
+        (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| pattern.channel_at(x, y) as u16 * 1000 + (y * width + x) as u16)
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn assert_pattern<R: RawImage<Pixel = u16>>(raw: &R, pattern: BayerPattern) {
+        for y in 0..raw.height() {
+            for x in 0..raw.width() {
+                assert_eq!(pattern.channel_at(x, y) as u16, *raw.pix(x, y) / 1000);
+            }
+        }
+    }
+
+    #[test]
+    fn test_flip_relabel() {
+        println!("flip::test::test_flip_relabel()  {{");
+
+        let vec2d = labeled(BayerPattern::Rggb, 6, 4);
+        let nd = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let (flipped, pattern) = nd
+            .flip_horizontal(BayerPattern::Rggb, FlipMode::Relabel)
+            .unwrap();
+        println!(
+            "  [flip][test_flip_relabel()] {:?}, flipped = \n{}",
+            pattern,
+            flipped.data()
+        );
+        assert_eq!(BayerPattern::Grbg, pattern);
+        assert_eq!(*nd.pix(5, 1), *flipped.pix(0, 1));
+        assert_pattern(&flipped, pattern);
+
+        let mut na = NARaw::<u16>::new_from_vector2d(&vec2d);
+        let pattern = na
+            .flip_vertical_mut(BayerPattern::Rggb, FlipMode::Relabel)
+            .unwrap();
+        assert_eq!(BayerPattern::Gbrg, pattern);
+        assert_eq!(*nd.pix(2, 3), *na.pix(2, 0));
+        assert_pattern(&na, pattern);
+
+        // 奇数サイズでは配列は変わらない
+        let nd = NDRaw::<u16>::new_from_vector2d(&labeled(BayerPattern::Bggr, 5, 3));
+        let (flipped, pattern) = nd
+            .flip_vertical(BayerPattern::Bggr, FlipMode::Relabel)
+            .unwrap();
+        assert_eq!(BayerPattern::Bggr, pattern);
+        assert_eq!(*nd.pix(4, 2), *flipped.pix(4, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_flip_preserve() {
+        println!("flip::test::test_flip_preserve()  {{");
+
+        let vec2d = labeled(BayerPattern::Gbrg, 6, 4);
+        let nd = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let (flipped, pattern) = nd
+            .flip_horizontal(BayerPattern::Gbrg, FlipMode::Preserve)
+            .unwrap();
+        println!(
+            "  [flip][test_flip_preserve()] {:?}, flipped = \n{}",
+            pattern,
+            flipped.data()
+        );
+        assert_eq!(BayerPattern::Gbrg, pattern);
+        // 反転後に1列ずれる
+        assert_eq!(*nd.pix(4, 0), *flipped.pix(0, 0));
+        assert_pattern(&flipped, pattern);
+
+        let mut na = NARaw::<u16>::new_from_vector2d(&vec2d);
+        let pattern = na
+            .flip_vertical_mut(BayerPattern::Gbrg, FlipMode::Preserve)
+            .unwrap();
+        assert_eq!(BayerPattern::Gbrg, pattern);
+        assert_eq!(*nd.pix(0, 2), *na.pix(0, 0));
+        assert_pattern(&na, pattern);
+
+        // 幅/高さ2では配列を保てないためエラー (画像は変更しない)
+        let mut small = NDRaw::<u16>::new_from_vector2d(&labeled(BayerPattern::Rggb, 2, 2));
+        let result = small.flip_horizontal_mut(BayerPattern::Rggb, FlipMode::Preserve);
+        println!("  [flip][test_flip_preserve()] result = {:?}", result);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = small.flip_vertical_mut(BayerPattern::Rggb, FlipMode::Preserve);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        assert_eq!(
+            NDRaw::<u16>::new_from_vector2d(&labeled(BayerPattern::Rggb, 2, 2)).data(),
+            small.data()
+        );
+
+        // 幅/高さ1は反転しても配列が変わらないためエラーにしない
+        let nd = NDRaw::<u16>::new_from_vector2d(&labeled(BayerPattern::Rggb, 1, 4));
+        let (_, pattern) = nd
+            .flip_horizontal(BayerPattern::Rggb, FlipMode::Preserve)
+            .unwrap();
+        assert_eq!(BayerPattern::Rggb, pattern);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,122 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::gradient::pix_clamped;
+use crate::integral::box_sum;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+
+// 局所フォーカス評価の窓サイズ
+const FOCUS_WINDOW: usize = 7;
+
+// 局所フォーカス評価値 (Laplacianの7x7窓内分散, 窓は画像内に切り詰め)
+fn local_focus_measure<T: PixelType>(raw: &NDRaw<T>) -> ndarray::Array2<f64> {
+    let (width, height) = (raw.width(), raw.height());
+    // Laplacian (範囲外は端の画素で補完) とその2乗の積分画像
+    let laplacian =
+        NDRaw::from_ndarray(ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            let p =
+                |dx: isize, dy: isize| pix_clamped(raw, x as isize + dx, y as isize + dy) as f64;
+            p(-1, 0) + p(1, 0) + p(0, -1) + p(0, 1) - 4.0 * p(0, 0)
+        }));
+    let sum = laplacian.integral_image();
+    let sum_sq = NDRaw::from_ndarray(laplacian.data.mapv(|l| l * l)).integral_image();
+
+    let half = FOCUS_WINDOW / 2;
+    ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+        let (x0, x1) = (x.saturating_sub(half), (x + half + 1).min(width));
+        let (y0, y1) = (y.saturating_sub(half), (y + half + 1).min(height));
+        let rect = Rect::new(x0, y0, x1 - x0, y1 - y0);
+        let n = (rect.width * rect.height) as f64;
+        let mean = box_sum(&sum, rect) / n;
+        box_sum(&sum_sq, rect) / n - mean * mean
+    })
+}
+
+// フォーカススタッキング (画素毎に局所フォーカス評価値が最大のフレームの画素を採用, 同値は先のフレーム)
+pub fn stack_focus_fusion<T: PixelType>(frames: &[NDRaw<T>]) -> Result<NDRaw<T>, SensorIoError> {
+    let Some(first) = frames.first() else {
+        return Err(SensorIoError::InvalidArgument(String::from(
+            "stack_focus_fusion needs at least one frame",
+        )));
+    };
+    for frame in frames {
+        check_shape(
+            (first.width(), first.height()),
+            (frame.width(), frame.height()),
+        )?;
+    }
+
+    let mut fused = first.clone();
+    let mut best = local_focus_measure(first);
+    for frame in &frames[1..] {
+        let measure = local_focus_measure(frame);
+        for ((y, x), m) in measure.indexed_iter() {
+            if *m > best[[y, x]] {
+                best[[y, x]] = *m;
+                fused.data[[y, x]] = frame.data[[y, x]];
+            }
+        }
+    }
+    Ok(fused)
+}
+
+#[cfg(test)]
+mod test {
+    use super::stack_focus_fusion;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    fn texture(x: usize, y: usize) -> u16 {
+        500 + ((x * 7 + y * 13) % 11) as u16 * 50
+    }
+
+    // rowsの行のみ合焦 (それ以外はぼけて平坦)
+    fn frame(rows: std::ops::Range<usize>) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = (0..24)
+            .map(|y| {
+                (0..20)
+                    .map(|x| {
+                        if rows.contains(&y) {
+                            texture(x, y)
+                        } else {
+                            750
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_stack_focus_fusion() {
+        println!("focus::test::test_stack_focus_fusion()  {{");
+
+        let frames = [frame(0..8), frame(8..16), frame(16..24)];
+        let fused = stack_focus_fusion(&frames).unwrap();
+        println!(
+            "  [focus][test_stack_focus_fusion()] fused = \n{}",
+            fused.data()
+        );
+        // 全行で合焦フレームの画素を採用
+        for y in 0..24 {
+            for x in 0..20 {
+                assert_eq!(texture(x, y), *fused.pix(x, y), "(x, y) = ({}, {})", x, y);
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_stack_focus_fusion_invalid() {
+        println!("focus::test::test_stack_focus_fusion_invalid()  {{");
+
+        let result = stack_focus_fusion::<u16>(&[]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = stack_focus_fusion(&[NDRaw::<u16>::new(4, 3), NDRaw::<u16>::new(3, 4)]);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 同一サイズのフレーム列
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct FrameBuffer<T: PixelType> {
+    frames: Vec<NDRaw<T>>,
+}
+
+impl<T: PixelType> FrameBuffer<T> {
+    // 空のフレーム列
+    pub fn new() -> Self {
+        FrameBuffer { frames: Vec::new() }
+    }
+
+    // Vector3D([frame][y][x])変換コンストラクタ (空・サイズ不一致はエラー)
+    pub fn new_from_vector3d(data: &[Vec<Vec<T>>]) -> Result<Self, SensorIoError> {
+        let Some(first) = data.first() else {
+            return Err(SensorIoError::InvalidArgument(String::from(
+                "no frames given",
+            )));
+        };
+        let shape = (first.first().map_or(0, |row| row.len()), first.len());
+        let mut buffer = FrameBuffer::new();
+        for (i, frame) in data.iter().enumerate() {
+            let actual = (frame.first().map_or(0, |row| row.len()), frame.len());
+            if frame.iter().any(|row| row.len() != actual.0) {
+                return Err(SensorIoError::ShapeMismatch(format!(
+                    "frame {}: rows have different lengths",
+                    i
+                )));
+            }
+            check_shape(shape, actual)
+                .map_err(|e| SensorIoError::ShapeMismatch(format!("frame {}: {}", i, e)))?;
+            buffer.frames.push(NDRaw::new_from_vector2d(frame));
+        }
+        Ok(buffer)
+    }
+
+    // フレーム追加 (既存フレームとサイズが異なればエラー)
+    pub fn push(&mut self, frame: NDRaw<T>) -> Result<(), SensorIoError> {
+        if let Some(first) = self.frames.first() {
+            check_shape(
+                (first.width(), first.height()),
+                (frame.width(), frame.height()),
+            )?;
+        }
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    // フレーム取得
+    pub fn get(&self, index: usize) -> Option<&NDRaw<T>> {
+        self.frames.get(index)
+    }
+
+    // 全フレーム取得
+    pub fn frames(&self) -> &[NDRaw<T>] {
+        &self.frames
+    }
+
+    // フレーム数取得
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    // フレームなし判定
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    // Vector3D([frame][y][x])変換
+    pub fn into_vector3d(&self) -> Vec<Vec<Vec<T>>> {
+        self.frames
+            .iter()
+            .map(|frame| {
+                frame
+                    .data
+                    .rows()
+                    .into_iter()
+                    .map(|row| row.to_vec())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<T: PixelType> Default for FrameBuffer<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FrameBuffer;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_vector3d_roundtrip() {
+        println!("frame_buffer::test::test_vector3d_roundtrip()  {{");
+
+        let data: Vec<Vec<Vec<u16>>> = (0..3)
+            .map(|i| vec![vec![i * 10, i * 10 + 1], vec![i * 10 + 2, i * 10 + 3]])
+            .collect();
+        let buffer = FrameBuffer::<u16>::new_from_vector3d(&data).unwrap();
+        assert_eq!(3, buffer.len());
+        assert_eq!(21, *buffer.get(2).unwrap().pix(1, 0));
+        assert_eq!(12, *buffer.frames()[1].pix(0, 1));
+
+        let restored = buffer.into_vector3d();
+        println!(
+            "  [frame_buffer][test_vector3d_roundtrip()] restored = {:?}",
+            restored
+        );
+        assert_eq!(data, restored);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_new_from_vector3d_invalid() {
+        println!("frame_buffer::test::test_new_from_vector3d_invalid()  {{");
+
+        let result = FrameBuffer::<u16>::new_from_vector3d(&[]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        // 2フレーム目の高さ違い, 3フレーム目の幅違い
+        for data in [
+            vec![vec![vec![0u16; 2]; 2], vec![vec![0; 2]; 3]],
+            vec![
+                vec![vec![0u16; 2]; 2],
+                vec![vec![0; 2]; 2],
+                vec![vec![0; 3]; 2],
+            ],
+        ] {
+            let result = FrameBuffer::<u16>::new_from_vector3d(&data);
+            println!(
+                "  [frame_buffer][test_new_from_vector3d_invalid()] {:?}",
+                result.as_ref().err()
+            );
+            let expected_frame = format!("frame {}", data.len() - 1);
+            assert!(matches!(
+                result,
+                Err(SensorIoError::ShapeMismatch(ref msg)) if msg.starts_with(&expected_frame)
+            ));
+        }
+
+        let mut buffer = FrameBuffer::<u16>::new();
+        buffer.push(NDRaw::new(4, 3)).unwrap();
+        let result = buffer.push(NDRaw::new(3, 4));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,146 @@
+use crate::ndraw::NDRaw;
+use crate::raw::RawImage;
+use num_traits::ToPrimitive;
+
+// 範囲外は端の画素で補完して取得
+pub(crate) fn pix_clamped<R: RawImage + ?Sized>(raw: &R, x: isize, y: isize) -> f32 {
+    let x = x.clamp(0, raw.width() as isize - 1) as usize;
+    let y = y.clamp(0, raw.height() as isize - 1) as usize;
+    raw.pix(x, y).to_f32().unwrap()
+}
+
+// Sobel勾配 => (強度, 方向)
+pub(crate) fn sobel<R: RawImage + ?Sized>(raw: &R) -> (NDRaw<f32>, NDRaw<f32>) {
+    let (width, height) = (raw.width(), raw.height());
+    let mut magnitude = NDRaw::<f32>::new(width, height);
+    let mut direction = NDRaw::<f32>::new(width, height);
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let p = |dx: isize, dy: isize| pix_clamped(raw, x + dx, y + dy);
+            let gx = (p(1, -1) + 2.0 * p(1, 0) + p(1, 1)) - (p(-1, -1) + 2.0 * p(-1, 0) + p(-1, 1));
+            let gy = (p(-1, 1) + 2.0 * p(0, 1) + p(1, 1)) - (p(-1, -1) + 2.0 * p(0, -1) + p(1, -1));
+            *magnitude.pix_mut(x as usize, y as usize) = gx.hypot(gy);
+            *direction.pix_mut(x as usize, y as usize) = gy.atan2(gx);
+        }
+    }
+    (magnitude, direction)
+}
+
+// Brennerフォーカス評価値
+pub(crate) fn focus_score_brenner<R: RawImage + ?Sized>(raw: &R) -> f64 {
+    let mut score = 0.0;
+    for y in 0..raw.height() {
+        for x in 2..raw.width() {
+            let d = raw.pix(x, y).to_f64().unwrap() - raw.pix(x - 2, y).to_f64().unwrap();
+            score += d * d;
+        }
+    }
+    score
+}
+
+// Laplacianフォーカス評価値
+pub(crate) fn focus_score_laplacian<R: RawImage + ?Sized>(raw: &R) -> f64 {
+    let (width, height) = (raw.width(), raw.height());
+    if width < 3 || height < 3 {
+        return 0.0;
+    }
+    let p = |x: usize, y: usize| raw.pix(x, y).to_f64().unwrap();
+    let mut values = Vec::with_capacity((width - 2) * (height - 2));
+    for y in 1..height - 1 {
+        for x in 1..width - 1 {
+            values.push(p(x - 1, y) + p(x + 1, y) + p(x, y - 1) + p(x, y + 1) - 4.0 * p(x, y));
+        }
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod test {
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    // 3x3平均ぼかし
+    fn box_blur(raw: &NDRaw<u16>) -> NDRaw<u16> {
+        let (width, height) = (raw.width(), raw.height());
+        let mut out = raw.clone();
+        for y in 1..height - 1 {
+            for x in 1..width - 1 {
+                let sum: u32 = (y - 1..=y + 1)
+                    .flat_map(|ny| (x - 1..=x + 1).map(move |nx| (nx, ny)))
+                    .map(|(nx, ny)| *raw.pix(nx, ny) as u32)
+                    .sum();
+                *out.pix_mut(x, y) = (sum / 9) as u16;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_gradient_direction() {
+        println!("gradient::test::test_gradient_direction()  {{");
+
+        // 右下方向に明るくなる斜めグラデーション
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| (0..8).map(|x| (x + y) as u16 * 10).collect())
+            .collect();
+        let nd = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let na = NARaw::<u16>::new_from_vector2d(&vec2d);
+        let direction = nd.gradient_direction();
+        let magnitude = nd.gradient_magnitude();
+        println!(
+            "  [gradient][test_gradient_direction()] direction(4, 4) = {}, magnitude(4, 4) = {}",
+            direction.pix(4, 4),
+            magnitude.pix(4, 4)
+        );
+        let quarter_pi = std::f32::consts::FRAC_PI_4;
+        assert!((direction.pix(4, 4) - quarter_pi).abs() < 1e-4);
+        assert!((magnitude.pix(4, 4) - 80.0 * 2f32.sqrt()).abs() < 1e-3);
+        assert_eq!(direction.data(), na.gradient_direction().data());
+
+        // 左下方向 (xに対して減少)
+        let vec2d: Vec<Vec<u16>> = (0..8)
+            .map(|y| (0..8).map(|x| (100 - x + y) as u16).collect())
+            .collect();
+        let direction = NDRaw::<u16>::new_from_vector2d(&vec2d).gradient_direction();
+        assert!((direction.pix(4, 4) - 3.0 * quarter_pi).abs() < 1e-4);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_focus_score() {
+        println!("gradient::test::test_focus_score()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..16)
+            .map(|y| {
+                (0..16)
+                    .map(|x| ((x * 37 + y * 91) % 23) as u16 * 40)
+                    .collect()
+            })
+            .collect();
+        let sharp = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let blurred = box_blur(&sharp);
+        let (laplacian_sharp, laplacian_blurred) = (
+            sharp.compute_focus_score_laplacian(),
+            blurred.compute_focus_score_laplacian(),
+        );
+        let (brenner_sharp, brenner_blurred) = (
+            sharp.compute_focus_score_brenner(),
+            blurred.compute_focus_score_brenner(),
+        );
+        println!(
+            "  [gradient][test_focus_score()] laplacian = {} -> {}, brenner = {} -> {}",
+            laplacian_sharp, laplacian_blurred, brenner_sharp, brenner_blurred
+        );
+        assert!(laplacian_blurred < laplacian_sharp);
+        assert!(brenner_blurred < brenner_sharp);
+
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec![vec![500; 8]; 8]);
+        assert_eq!(0.0, flat.compute_focus_score_laplacian());
+        assert_eq!(0.0, flat.compute_focus_score_brenner());
+
+        println!("}}");
+    }
+}
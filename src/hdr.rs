@@ -0,0 +1,190 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// HDR合成設定
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct HdrConfig<T> {
+    pub black_level: T,
+    // この値以上の画素は飽和として重み0
+    pub white_level: T,
+    // black_level + dark_margin以下の画素は重み0
+    pub dark_margin: T,
+}
+
+impl<T: PixelType> HdrConfig<T> {
+    // 既定値 (dark_marginはレンジの1%)
+    pub fn new(black_level: T, white_level: T) -> Self {
+        let range = white_level.to_f64().unwrap() - black_level.to_f64().unwrap();
+        HdrConfig {
+            black_level,
+            white_level,
+            dark_margin: T::from_f64_saturating(range * 0.01),
+        }
+    }
+}
+
+// 露光ブラケットのHDR合成 (線形raw, 露光比1.0のフレームを基準とした黒レベル減算後の値)
+//   frames: (フレーム, 露光比)
+//   各画素を黒レベル減算後に露光比で正規化し, 三角形(hat)重みで加重平均
+//   全フレームの重みが0の画素は, 飽和を含めば最短露光, そうでなければ最長露光の正規化値を用いる
+pub fn merge_hdr<T: PixelType>(
+    frames: &[(&NDRaw<T>, f64)],
+    config: &HdrConfig<T>,
+) -> Result<NDRaw<f32>, SensorIoError> {
+    let Some((first, _)) = frames.first() else {
+        return Err(SensorIoError::InvalidArgument(String::from(
+            "merge_hdr needs at least one frame",
+        )));
+    };
+    for (i, (frame, ratio)) in frames.iter().enumerate() {
+        check_shape(
+            (first.width(), first.height()),
+            (frame.width(), frame.height()),
+        )?;
+        if !(*ratio > 0.0 && ratio.is_finite()) {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "frame {} has invalid exposure ratio {}",
+                i, ratio
+            )));
+        }
+    }
+    let black = config.black_level.to_f64().unwrap();
+    let white = config.white_level.to_f64().unwrap();
+    let low = black + config.dark_margin.to_f64().unwrap();
+    if low >= white {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "black_level + dark_margin ({}) must be below white_level ({})",
+            low, white
+        )));
+    }
+
+    let shortest = (0..frames.len())
+        .min_by(|a, b| frames[*a].1.total_cmp(&frames[*b].1))
+        .unwrap();
+    let longest = (0..frames.len())
+        .max_by(|a, b| frames[*a].1.total_cmp(&frames[*b].1))
+        .unwrap();
+    let hat = |v: f64| {
+        if v <= low || v >= white {
+            0.0
+        } else {
+            1.0 - (2.0 * (v - low) / (white - low) - 1.0).abs()
+        }
+    };
+
+    let data = ndarray::Array2::from_shape_fn(first.data.dim(), |(y, x)| {
+        let normalized = |i: usize| {
+            let (frame, ratio) = frames[i];
+            (frame.data[[y, x]].to_f64().unwrap() - black).max(0.0) / ratio
+        };
+        let mut sum = 0.0;
+        let mut weight_sum = 0.0;
+        let mut saturated = false;
+        for (i, (frame, _)) in frames.iter().enumerate() {
+            let v = frame.data[[y, x]].to_f64().unwrap();
+            saturated |= v >= white;
+            let w = hat(v);
+            sum += w * normalized(i);
+            weight_sum += w;
+        }
+        let merged = if weight_sum > 0.0 {
+            sum / weight_sum
+        } else if saturated {
+            normalized(shortest)
+        } else {
+            normalized(longest)
+        };
+        merged as f32
+    });
+    Ok(NDRaw::from_ndarray(data))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge_hdr, HdrConfig};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    const BLACK: f64 = 64.0;
+    const WHITE: f64 = 4095.0;
+
+    // 露光比ratioで撮影したフレーム (scene: 露光比1.0での黒レベル減算後の値)
+    fn capture(scene: &[Vec<f64>], ratio: f64) -> NDRaw<u16> {
+        let vec2d: Vec<Vec<u16>> = scene
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|s| (BLACK + s * ratio).min(WHITE).round() as u16)
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_merge_hdr() {
+        println!("hdr::test::test_merge_hdr()  {{");
+
+        // 横方向に明るくなるシーン (長秒露光の右側は飽和)
+        let scene: Vec<Vec<f64>> = (0..4)
+            .map(|y| (0..8).map(|x| 20.0 + 500.0 * x as f64 + y as f64).collect())
+            .collect();
+        let short = capture(&scene, 1.0);
+        let middle = capture(&scene, 2.0);
+        let long = capture(&scene, 4.0);
+        let config = HdrConfig::new(BLACK as u16, WHITE as u16);
+        let merged = merge_hdr(&[(&short, 1.0), (&middle, 2.0), (&long, 4.0)], &config).unwrap();
+        println!("  [hdr][test_merge_hdr()] merged = \n{}", merged.data());
+
+        for (y, row) in scene.iter().enumerate() {
+            for (x, s) in row.iter().enumerate() {
+                assert!((*merged.pix(x, y) as f64 - s).abs() < 0.5);
+            }
+        }
+        // 長秒露光が飽和した画素は短秒露光の値に一致
+        assert_eq!(*long.pix(7, 0), WHITE as u16);
+        assert!((*merged.pix(7, 0) as f64 - (*short.pix(7, 0) as f64 - BLACK)).abs() < 1e-3);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_merge_hdr_fallback() {
+        println!("hdr::test::test_merge_hdr_fallback()  {{");
+
+        // 全フレーム飽和 => 最短露光, 全フレーム暗部 => 最長露光
+        let bright = NDRaw::<u16>::new_from_vector2d(&[vec![4095, 64]]);
+        let config = HdrConfig::new(64, 4095);
+        let merged = merge_hdr(&[(&bright, 4.0), (&bright, 0.5)], &config).unwrap();
+        println!(
+            "  [hdr][test_merge_hdr_fallback()] merged = {}",
+            merged.data()
+        );
+        assert_eq!((4095.0 - 64.0) / 0.5, *merged.pix(0, 0));
+        assert_eq!(0.0, *merged.pix(1, 0));
+
+        let dark = NDRaw::<u16>::new_from_vector2d(&[vec![70, 68]]);
+        let merged = merge_hdr(&[(&dark, 1.0), (&dark, 2.0)], &config).unwrap();
+        assert_eq!(3.0, *merged.pix(0, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_merge_hdr_invalid() {
+        println!("hdr::test::test_merge_hdr_invalid()  {{");
+
+        let config = HdrConfig::new(64u16, 4095);
+        let a = NDRaw::<u16>::new(4, 3);
+        let b = NDRaw::<u16>::new(3, 3);
+        let result = merge_hdr(&[(&a, 1.0), (&b, 2.0)], &config);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+        let result = merge_hdr(&[(&a, 0.0)], &config);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = merge_hdr::<u16>(&[], &config);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
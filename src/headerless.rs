@@ -0,0 +1,111 @@
+use crate::binfmt;
+use crate::error::SensorIoError;
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use byteorder::ReadBytesExt;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+// ヘッダなしraw画像のバイトオーダー
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+// 先頭からwidth * height画素(u16)を読み込む (行優先, ファイルが短い場合はIoエラー)
+pub(crate) fn read_pixels<T: PixelType, R: Read>(
+    reader: &mut R,
+    width: usize,
+    height: usize,
+    endianness: Endianness,
+) -> Result<Vec<T>, SensorIoError> {
+    let mut pixels = Vec::with_capacity(width * height);
+    for _ in 0..width * height {
+        let word = match endianness {
+            Endianness::Little => reader.read_u16::<byteorder::LittleEndian>()?,
+            Endianness::Big => reader.read_u16::<byteorder::BigEndian>()?,
+        };
+        pixels.push(binfmt::convert_pixel(word)?);
+    }
+    Ok(pixels)
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // ヘッダなしraw画像変換コンストラクタ (サイズ・バイトオーダーは呼び出し側で指定)
+    pub fn new_from_raw_bytes(
+        path_raw_in: String,
+        width: usize,
+        height: usize,
+        endianness: Endianness,
+    ) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        let pixels = read_pixels(&mut f_read, width, height, endianness)?;
+        let data = ndarray::Array2::from_shape_vec((height, width), pixels).unwrap();
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // ヘッダなしraw画像変換コンストラクタ (サイズ・バイトオーダーは呼び出し側で指定)
+    pub fn new_from_raw_bytes(
+        path_raw_in: String,
+        width: usize,
+        height: usize,
+        endianness: Endianness,
+    ) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        let pixels = read_pixels(&mut f_read, width, height, endianness)?;
+        let data = nalgebra::DMatrix::from_row_slice(height, width, &pixels);
+        Ok(NARaw::from_dmatrix(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Endianness;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use byteorder::WriteBytesExt;
+    use std::io::Write;
+
+    #[test]
+    fn test_new_from_raw_bytes() {
+        println!("headerless::test::test_new_from_raw_bytes()  {{");
+
+        // ヘッダなしの画素ブロック (3x2, Big Endian)
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_headerless_{}.raw", std::process::id()));
+        let pixels: Vec<u16> = vec![1, 2, 0x0300, 4, 5, 0xABCD];
+        let mut f_write = std::fs::File::create(&path).unwrap();
+        for &v in &pixels {
+            f_write.write_u16::<byteorder::BigEndian>(v).unwrap();
+        }
+        f_write.flush().unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+
+        let ndraw =
+            NDRaw::<u16>::new_from_raw_bytes(path_str.clone(), 3, 2, Endianness::Big).unwrap();
+        let naraw =
+            NARaw::<u16>::new_from_raw_bytes(path_str.clone(), 3, 2, Endianness::Big).unwrap();
+        let swapped =
+            NDRaw::<u16>::new_from_raw_bytes(path_str.clone(), 3, 2, Endianness::Little).unwrap();
+        let short = NDRaw::<u16>::new_from_raw_bytes(path_str, 4, 2, Endianness::Big);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [headerless][test_new_from_raw_bytes()] ndraw = \n{}",
+            ndraw.data()
+        );
+
+        assert_eq!((3, 2), (ndraw.width(), ndraw.height()));
+        assert_eq!(pixels, ndraw.data().iter().copied().collect::<Vec<u16>>());
+        assert_eq!(0xABCD, *naraw.pix(2, 1));
+        assert_eq!(0x0300, *naraw.pix(2, 0));
+        assert_eq!(0x0003, *swapped.pix(2, 0));
+        assert!(matches!(short, Err(SensorIoError::Io(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,189 @@
+use crate::error::SensorIoError;
+use crate::raw::RawImage;
+use num_traits::ToPrimitive;
+use std::path::Path;
+
+// 疑似カラーマップ (正規化値0〜1を先頭〜末尾の色に対応付け)
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PseudoColorMap {
+    entries: Vec<[u8; 3]>,
+}
+
+impl PseudoColorMap {
+    // 色の一覧から作成 (空はエラー)
+    pub fn from_entries(entries: Vec<[u8; 3]>) -> Result<Self, SensorIoError> {
+        if entries.is_empty() {
+            return Err(SensorIoError::InvalidArgument(
+                "colormap must have at least one entry".to_string(),
+            ));
+        }
+        Ok(PseudoColorMap { entries })
+    }
+
+    // グレースケール (256色)
+    pub fn gray() -> Self {
+        PseudoColorMap {
+            entries: (0..=255u8).map(|v| [v, v, v]).collect(),
+        }
+    }
+
+    // Jet (青 => シアン => 黄 => 赤, 256色)
+    pub fn jet() -> Self {
+        let channel = |t: f64, center: f64| {
+            ((1.5 - (4.0 * t - center).abs()).clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        PseudoColorMap {
+            entries: (0..256)
+                .map(|i| {
+                    let t = i as f64 / 255.0;
+                    [channel(t, 3.0), channel(t, 2.0), channel(t, 1.0)]
+                })
+                .collect(),
+        }
+    }
+
+    // 色の一覧取得
+    pub fn entries(&self) -> &[[u8; 3]] {
+        &self.entries
+    }
+
+    // 正規化値(0〜1, 範囲外はクランプ)の色
+    pub fn color(&self, t: f64) -> [u8; 3] {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        self.entries[(t * (self.entries.len() - 1) as f64).round() as usize]
+    }
+}
+
+// カラーバーの幅 (画素)
+const COLORBAR_WIDTH: u32 = 16;
+
+// 疑似カラー画像作成 (min/max未指定は画像の最小値/最大値, colorbarは右端に上が最大値のカラーバーを追加)
+fn render<R: RawImage + ?Sized>(
+    raw: &R,
+    colormap: &PseudoColorMap,
+    min: Option<R::Pixel>,
+    max: Option<R::Pixel>,
+    colorbar: bool,
+) -> image::RgbImage {
+    let (width, height) = (raw.width(), raw.height());
+    let value = |x: usize, y: usize| raw.pix(x, y).to_f64().unwrap();
+    let (mut lo, mut hi) = (f64::MAX, f64::MIN);
+    for y in 0..height {
+        for x in 0..width {
+            lo = lo.min(value(x, y));
+            hi = hi.max(value(x, y));
+        }
+    }
+    let lo = min.map_or(lo, |v| v.to_f64().unwrap());
+    let hi = max.map_or(hi, |v| v.to_f64().unwrap());
+    let range = hi - lo;
+
+    let bar = if colorbar { COLORBAR_WIDTH } else { 0 };
+    image::RgbImage::from_fn(width as u32 + bar, height as u32, |x, y| {
+        let t = if x as usize >= width {
+            // カラーバー (上端が1, 下端が0)
+            1.0 - y as f64 / (height.max(2) - 1) as f64
+        } else if range > 0.0 {
+            (value(x as usize, y as usize) - lo) / range
+        } else {
+            0.0
+        };
+        image::Rgb(colormap.color(t))
+    })
+}
+
+// 疑似カラーPNG保存
+pub(crate) fn export<R: RawImage + ?Sized>(
+    raw: &R,
+    path: &Path,
+    colormap: &PseudoColorMap,
+    min: Option<R::Pixel>,
+    max: Option<R::Pixel>,
+    colorbar: bool,
+) -> Result<(), SensorIoError> {
+    render(raw, colormap, min, max, colorbar)
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|e| match e {
+            image::ImageError::IoError(e) => SensorIoError::Io(e),
+            e => SensorIoError::InvalidArgument(e.to_string()),
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::PseudoColorMap;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    #[test]
+    fn test_pseudo_color_map() {
+        println!("heatmap::test::test_pseudo_color_map()  {{");
+
+        let jet = PseudoColorMap::jet();
+        println!(
+            "  [heatmap][test_pseudo_color_map()] jet = {:?} .. {:?}",
+            jet.color(0.0),
+            jet.color(1.0)
+        );
+        assert_eq!(256, jet.entries().len());
+        assert_eq!([0, 0, 128], jet.color(0.0));
+        assert_eq!([128, 0, 0], jet.color(1.0));
+        assert_eq!([128, 128, 128], PseudoColorMap::gray().color(0.5));
+        assert!(matches!(
+            PseudoColorMap::from_entries(vec![]),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_export_to_heatmap_png() {
+        println!("heatmap::test::test_export_to_heatmap_png()  {{");
+
+        let colormap =
+            PseudoColorMap::from_entries(vec![[0, 0, 255], [0, 255, 0], [255, 0, 0]]).unwrap();
+        let raw = NDRaw::<f32>::new_from_vector2d(&[
+            vec![-1.0, 0.0, 1.0, 2.0, 3.0],
+            vec![3.0, 3.0, 3.0, 3.0, 3.0],
+        ]);
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_heatmap_{}.png", std::process::id()));
+
+        // 最小値(-1.0)は先頭の色, 最大値(3.0)は末尾の色
+        raw.export_to_heatmap_png(&path, &colormap, None, None)
+            .unwrap();
+        let png = image::open(&path).unwrap().to_rgb8();
+        assert_eq!((5, 2), png.dimensions());
+        assert_eq!([0, 0, 255], png.get_pixel(0, 0).0);
+        assert_eq!([0, 255, 0], png.get_pixel(2, 0).0);
+        assert_eq!([255, 0, 0], png.get_pixel(4, 1).0);
+
+        // 範囲指定 (範囲外はクランプ)
+        raw.export_to_heatmap_png(&path, &colormap, Some(1.0), Some(2.0))
+            .unwrap();
+        let png = image::open(&path).unwrap().to_rgb8();
+        assert_eq!([0, 0, 255], png.get_pixel(1, 0).0);
+        assert_eq!([255, 0, 0], png.get_pixel(4, 0).0);
+
+        // カラーバー付き (NARaw)
+        let na = NARaw::<u16>::new_from_vector2d(&vec![vec![7u16; 20]; 10]);
+        na.export_to_heatmap_png_with_colorbar(&path, &colormap, None, None)
+            .unwrap();
+        let png = image::open(&path).unwrap().to_rgb8();
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [heatmap][test_export_to_heatmap_png()] with colorbar = {:?}",
+            png.dimensions()
+        );
+        assert_eq!((20 + 16, 10), png.dimensions());
+        assert_eq!([255, 0, 0], png.get_pixel(25, 0).0);
+        assert_eq!([0, 0, 255], png.get_pixel(25, 9).0);
+        // 一様な画像は先頭の色
+        assert_eq!([0, 0, 255], png.get_pixel(3, 3).0);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,221 @@
+use crate::bpm::BadPixelMap;
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 画素有効マスク (true: 有効)
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PixelValidityMask {
+    valid: ndarray::Array2<bool>,
+}
+
+impl PixelValidityMask {
+    // 全画素有効で作成
+    pub fn new(width: usize, height: usize) -> Self {
+        PixelValidityMask {
+            valid: ndarray::Array2::from_elem((height, width), true),
+        }
+    }
+
+    // 無効画素の座標(x, y)一覧から作成 (範囲外の座標は無視)
+    pub fn from_invalid_pixels(width: usize, height: usize, invalid: &[(usize, usize)]) -> Self {
+        let mut mask = Self::new(width, height);
+        for &(x, y) in invalid {
+            if x < width && y < height {
+                mask.set_valid(x, y, false);
+            }
+        }
+        mask
+    }
+
+    // width取得
+    pub fn width(&self) -> usize {
+        self.valid.ncols()
+    }
+
+    // height取得
+    pub fn height(&self) -> usize {
+        self.valid.nrows()
+    }
+
+    // 有効判定
+    pub fn is_valid(&self, x: usize, y: usize) -> bool {
+        self.valid[[y, x]]
+    }
+
+    // 有効/無効設定
+    pub fn set_valid(&mut self, x: usize, y: usize, valid: bool) {
+        self.valid[[y, x]] = valid;
+    }
+
+    // 無効画素数
+    pub fn invalid_count(&self) -> usize {
+        self.valid.iter().filter(|v| !**v).count()
+    }
+}
+
+impl From<&BadPixelMap> for PixelValidityMask {
+    fn from(bpm: &BadPixelMap) -> Self {
+        let invalid: Vec<(usize, usize)> = bpm.defects.iter().map(|d| (d.x, d.y)).collect();
+        Self::from_invalid_pixels(bpm.width, bpm.height, &invalid)
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 無効画素の補間 (有効画素はそのままf32化)
+    //   上下左右それぞれ最も近い有効画素を探し, 両側が揃った軸は距離による線形補間, 揃った軸の平均を採用
+    //   両側の揃う軸がなければ見つかった側の逆距離加重平均,
+    //   上下左右いずれにも有効画素がなければ外側へ1周ずつ探索し, 最初に有効画素が見つかった周の平均
+    //   全画素無効の場合は0, 画像とマスクのサイズ不一致はエラー
+    pub fn interpolate_missing_pixels(
+        &self,
+        mask: &PixelValidityMask,
+    ) -> Result<NDRaw<f32>, SensorIoError> {
+        check_shape((self.width(), self.height()), (mask.width(), mask.height()))?;
+        let (width, height) = (self.width(), self.height());
+        let value = |x: usize, y: usize| self.data[[y, x]].to_f64().unwrap();
+
+        // (dx, dy)方向で最も近い有効画素 (距離, 値)
+        let nearest = |x: usize, y: usize, dx: isize, dy: isize| {
+            let (mut sx, mut sy, mut distance) = (x as isize, y as isize, 0);
+            loop {
+                sx += dx;
+                sy += dy;
+                distance += 1;
+                if sx < 0 || sy < 0 || sx >= width as isize || sy >= height as isize {
+                    return None;
+                }
+                if mask.is_valid(sx as usize, sy as usize) {
+                    return Some((distance as f64, value(sx as usize, sy as usize)));
+                }
+            }
+        };
+
+        let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            if mask.is_valid(x, y) {
+                return value(x, y) as f32;
+            }
+            let sides = [
+                (nearest(x, y, -1, 0), nearest(x, y, 1, 0)),
+                (nearest(x, y, 0, -1), nearest(x, y, 0, 1)),
+            ];
+
+            let axes: Vec<f64> = sides
+                .iter()
+                .filter_map(|pair| match pair {
+                    (Some((d0, v0)), Some((d1, v1))) => Some((v0 * d1 + v1 * d0) / (d0 + d1)),
+                    _ => None,
+                })
+                .collect();
+            if !axes.is_empty() {
+                return (axes.iter().sum::<f64>() / axes.len() as f64) as f32;
+            }
+
+            let (mut sum, mut weight) = (0.0, 0.0);
+            for (d, v) in sides.iter().flat_map(|(a, b)| [a, b]).flatten() {
+                sum += v / d;
+                weight += 1.0 / d;
+            }
+            if weight > 0.0 {
+                return (sum / weight) as f32;
+            }
+
+            // 外周探索
+            for r in 1..width.max(height) as isize {
+                let (mut sum, mut count) = (0.0, 0);
+                for sy in y as isize - r..=y as isize + r {
+                    for sx in x as isize - r..=x as isize + r {
+                        let on_ring = (sx - x as isize).abs() == r || (sy - y as isize).abs() == r;
+                        if !on_ring
+                            || sx < 0
+                            || sy < 0
+                            || sx >= width as isize
+                            || sy >= height as isize
+                        {
+                            continue;
+                        }
+                        if mask.is_valid(sx as usize, sy as usize) {
+                            sum += value(sx as usize, sy as usize);
+                            count += 1;
+                        }
+                    }
+                }
+                if count > 0 {
+                    return (sum / count as f64) as f32;
+                }
+            }
+            0.0
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PixelValidityMask;
+    use crate::bpm::BadPixelMap;
+    use crate::defect::{DefectKind, DefectPixel};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_interpolate_missing_pixels() {
+        println!("inpaint::test::test_interpolate_missing_pixels()  {{");
+
+        // 5x5の線形グラデーション, 中央付近2x2ブロックを無効化 (値は壊しておく)
+        let gradient = |x: usize, y: usize| (10 * x + 20 * y) as u16;
+        let mut raw = NDRaw::<u16>::new(5, 5);
+        for y in 0..5 {
+            for x in 0..5 {
+                *raw.pix_mut(x, y) = gradient(x, y);
+            }
+        }
+        let block = [(1, 1), (2, 1), (1, 2), (2, 2)];
+        for &(x, y) in &block {
+            *raw.pix_mut(x, y) = 4095;
+        }
+        let mask = PixelValidityMask::from_invalid_pixels(5, 5, &block);
+        assert_eq!(4, mask.invalid_count());
+
+        let filled = raw.interpolate_missing_pixels(&mask).unwrap();
+        println!(
+            "  [inpaint][test_interpolate_missing_pixels()] filled = {}",
+            filled.data()
+        );
+        for y in 0..5 {
+            for x in 0..5 {
+                assert!((filled.pix(x, y) - gradient(x, y) as f32).abs() < 1e-4);
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_interpolate_missing_pixels_edges() {
+        println!("inpaint::test::test_interpolate_missing_pixels_edges()  {{");
+
+        let raw = NDRaw::<u16>::new_from_vector2d(&[vec![100, 0, 0], vec![0, 0, 0], vec![0, 0, 0]]);
+
+        // 左上以外が無効: 上下左右に有効画素がない画素は外周探索
+        let mut mask = PixelValidityMask::new(3, 3);
+        for y in 0..3 {
+            for x in 0..3 {
+                mask.set_valid(x, y, (x, y) == (0, 0));
+            }
+        }
+        let filled = raw.interpolate_missing_pixels(&mask).unwrap();
+        assert!(filled.data().iter().all(|v| *v == 100.0));
+
+        let result = raw.interpolate_missing_pixels(&PixelValidityMask::new(3, 2));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        // 欠陥画素マップからの変換
+        let bpm = BadPixelMap::new(3, 3, vec![DefectPixel::new(2, 1, DefectKind::Hot)]);
+        let mask = PixelValidityMask::from(&bpm);
+        assert!(!mask.is_valid(2, 1));
+        assert_eq!(1, mask.invalid_count());
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+
+impl<T: PixelType> NDRaw<T> {
+    // 積分画像 ((height + 1) x (width + 1), [[y, x]]は(0, 0)〜(x - 1, y - 1)の総和, 先頭行・列は0)
+    pub fn integral_image(&self) -> ndarray::Array2<f64> {
+        let (width, height) = (self.width(), self.height());
+        let mut sum = ndarray::Array2::<f64>::zeros((height + 1, width + 1));
+        for y in 0..height {
+            let mut row = 0.0;
+            for x in 0..width {
+                row += self.data[[y, x]].to_f64().unwrap();
+                sum[[y + 1, x + 1]] = sum[[y, x + 1]] + row;
+            }
+        }
+        sum
+    }
+}
+
+// 積分画像からの矩形内総和
+pub(crate) fn box_sum(integral: &ndarray::Array2<f64>, rect: Rect) -> f64 {
+    let (x0, y0, x1, y1) = (rect.x, rect.y, rect.right(), rect.bottom());
+    integral[[y1, x1]] - integral[[y0, x1]] - integral[[y1, x0]] + integral[[y0, x0]]
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 局所適応二値化
+    //   各画素を中心とする窓 (一辺 window / 2 * 2 + 1, 画像端は内側のみ) の平均 - c と比較し,
+    //   上回る画素を1, それ以外を0とする
+    pub fn adaptive_threshold(&self, window: usize, c: T) -> Self {
+        let (width, height) = (self.width(), self.height());
+        let integral = self.integral_image();
+        let radius = window / 2;
+        let c = c.to_f64().unwrap();
+        let data = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            let (x0, y0) = (x.saturating_sub(radius), y.saturating_sub(radius));
+            let (x1, y1) = ((x + radius + 1).min(width), (y + radius + 1).min(height));
+            let rect = Rect::new(x0, y0, x1 - x0, y1 - y0);
+            let mean = box_sum(&integral, rect) / (rect.width * rect.height) as f64;
+            if self.data[[y, x]].to_f64().unwrap() > mean - c {
+                T::one()
+            } else {
+                T::zero()
+            }
+        });
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::box_sum;
+    use crate::ndraw::NDRaw;
+    use crate::rect::Rect;
+
+    #[test]
+    fn test_integral_image() {
+        println!("integral::test::test_integral_image()  {{");
+
+        let raw = NDRaw::<u16>::new_from_vector2d(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        let integral = raw.integral_image();
+        println!(
+            "  [integral][test_integral_image()] integral = \n{}",
+            integral
+        );
+        assert_eq!((3, 4), integral.dim());
+        assert_eq!(21.0, integral[[2, 3]]);
+        assert_eq!(5.0 + 6.0, box_sum(&integral, Rect::new(1, 1, 2, 1)));
+        assert_eq!(2.0 + 5.0, box_sum(&integral, Rect::new(1, 0, 1, 2)));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_adaptive_threshold() {
+        println!("integral::test::test_adaptive_threshold()  {{");
+
+        // 左から右へ明るくなる背景 (100〜1360) に暗いマーク (背景 - 80)
+        let (width, height) = (64, 16);
+        let marks = [(5, 4), (20, 10), (40, 7), (60, 12)];
+        let mut raw = NDRaw::<u16>::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let background = 100 + 20 * x as u16;
+                *raw.pix_mut(x, y) = if marks.contains(&(x, y)) {
+                    background - 80
+                } else {
+                    background
+                };
+            }
+        }
+
+        // 大域閾値では分離できない (右側の背景より暗いマークがない)
+        assert!(*raw.pix(60, 12) > *raw.pix(5, 5));
+
+        let binary = raw.adaptive_threshold(7, 40);
+        let detected: Vec<(usize, usize)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .filter(|&(x, y)| *binary.pix(x, y) == 0)
+            .collect();
+        println!(
+            "  [integral][test_adaptive_threshold()] detected = {:?}",
+            detected
+        );
+        assert_eq!(marks.len(), detected.len());
+        for mark in marks {
+            assert!(detected.contains(&mark));
+        }
+
+        println!("}}");
+    }
+}
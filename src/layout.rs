@@ -0,0 +1,82 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use ndarray::ShapeBuilder;
+
+// メモリ配置
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Layout {
+    // 行優先 (同じ行の画素が連続, ndarrayの既定)
+    RowMajor,
+    // 列優先 (同じ列の画素が連続)
+    ColMajor,
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // メモリ配置取得 (1行/1列の画像は行優先とみなす)
+    pub fn layout(&self) -> Layout {
+        if !self.data.is_standard_layout() && self.data.t().is_standard_layout() {
+            Layout::ColMajor
+        } else {
+            Layout::RowMajor
+        }
+    }
+
+    // メモリ配置指定 (画素値・座標は変わらない, 新たに生成される画像は行優先)
+    pub fn with_layout(&self, layout: Layout) -> Self {
+        let shape = self.data.dim();
+        let mut data = match layout {
+            Layout::RowMajor => ndarray::Array2::zeros(shape),
+            Layout::ColMajor => ndarray::Array2::zeros(shape.f()),
+        };
+        data.assign(&self.data);
+        NDRaw::from_ndarray(data)
+    }
+
+    // 列優先に変換
+    pub fn to_column_major(&self) -> Self {
+        self.with_layout(Layout::ColMajor)
+    }
+
+    // 行優先に変換
+    pub fn to_row_major(&self) -> Self {
+        self.with_layout(Layout::RowMajor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Layout;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_to_column_major() {
+        println!("layout::test::test_to_column_major()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..3)
+            .map(|y| (0..4).map(|x| (y * 4 + x) as u16).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        assert_eq!(Layout::RowMajor, raw_in.layout());
+
+        let raw_col = raw_in.to_column_major();
+        println!(
+            "  [layout][test_to_column_major()] strides = {:?}",
+            raw_col.data().strides()
+        );
+        assert_eq!(Layout::ColMajor, raw_col.layout());
+        assert_eq!(&[1, 3], raw_col.data().strides());
+        assert_eq!(raw_in.data(), raw_col.data());
+        assert_eq!(*raw_in.pix(3, 2), *raw_col.pix(3, 2));
+
+        // 列が連続領域として取り出せる
+        let column = raw_col.data().column(2);
+        assert_eq!(Some(&[2u16, 6, 10][..]), column.as_slice());
+        assert!(raw_in.data().column(2).as_slice().is_none());
+
+        let raw_row = raw_col.to_row_major();
+        assert_eq!(Layout::RowMajor, raw_row.layout());
+        assert_eq!(&[4, 1], raw_row.data().strides());
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,138 @@
+use crate::bin_builder::BinReader;
+use crate::binfmt::read_bin_header;
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::sidecar;
+use std::path::{Path, PathBuf};
+
+// 遅延読み込みbin画像 (open時はヘッダのみ読み込み, 画素アクセス時に全体を読み込む)
+pub struct LazyNDRaw<T: PixelType> {
+    path: PathBuf,
+    width: usize,
+    height: usize,
+    cache: Option<NDRaw<T>>,
+}
+
+impl<T: PixelType> LazyNDRaw<T> {
+    // ヘッダのみ読み込み
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SensorIoError> {
+        let (width, height) = read_bin_header(path.as_ref())?;
+        Ok(LazyNDRaw {
+            path: path.as_ref().to_path_buf(),
+            width,
+            height,
+            cache: None,
+        })
+    }
+
+    // width取得
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // height取得
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // 画素値取得 (初回アクセス時に全体を読み込む)
+    pub fn pix(&mut self, x: usize, y: usize) -> Result<T, SensorIoError> {
+        if x >= self.width || y >= self.height {
+            return Err(SensorIoError::OutOfBounds(format!(
+                "pixel ({}, {}) is outside the {}x{} image",
+                x, y, self.width, self.height
+            )));
+        }
+        Ok(*self.load()?.pix(x, y))
+    }
+
+    // 全体読み込み (new_from_binimageと同じ形式判定・サイドカー読み込み, 読み込み済みならキャッシュを返す)
+    pub fn load(&mut self) -> Result<&NDRaw<T>, SensorIoError> {
+        if self.cache.is_none() {
+            let mut raw = BinReader::new().read(&self.path)?;
+            raw.metadata = sidecar::read_sidecar(&self.path)?;
+            self.cache = Some(raw);
+        }
+        Ok(self.cache.as_ref().unwrap())
+    }
+
+    // 読み込み済み判定
+    pub fn is_loaded(&self) -> bool {
+        self.cache.is_some()
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 遅延読み込みbin画像オープン (LazyNDRaw::openと同じ)
+    pub fn read_binimage_lazy(path: impl AsRef<Path>) -> Result<LazyNDRaw<T>, SensorIoError> {
+        LazyNDRaw::open(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LazyNDRaw;
+    use crate::bin_builder::BinWriter;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_lazy_ndraw() {
+        println!("lazy::test::test_lazy_ndraw()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let mut lazy = NDRaw::<u16>::read_binimage_lazy("testdata/test.bin").unwrap();
+        assert_eq!(
+            (raw_in.width(), raw_in.height()),
+            (lazy.width(), lazy.height())
+        );
+        assert!(!lazy.is_loaded());
+
+        let value = lazy.pix(2, 1).unwrap();
+        println!("  [lazy][test_lazy_ndraw()] pix(2, 1) = {}", value);
+        assert!(lazy.is_loaded());
+        assert_eq!(*raw_in.pix(2, 1), value);
+        assert_eq!(raw_in.data(), lazy.load().unwrap().data());
+
+        let result = lazy.pix(raw_in.width(), 0);
+        assert!(matches!(result, Err(SensorIoError::OutOfBounds(_))));
+
+        // CRC32付きのファイルも通常の読み込みと同じく扱う
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_lazy_crc_{}.bin", std::process::id()));
+        BinWriter::new()
+            .checksum(true)
+            .write(&raw_in, &path)
+            .unwrap();
+        let mut lazy = LazyNDRaw::<u16>::open(&path).unwrap();
+        let loaded = lazy.load().map(|raw| raw.data().clone());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(raw_in.data(), &loaded.unwrap());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_lazy_ndraw_header_only() {
+        println!("lazy::test::test_lazy_ndraw_header_only()  {{");
+
+        // 画素部を持たないファイルでもopenは成功し, 画素アクセスで初めて読み込みエラー
+        let path = std::env::temp_dir().join(format!("sensor_io_lazy_{}.bin", std::process::id()));
+        std::fs::write(&path, [4, 0, 3, 0]).unwrap();
+        let mut lazy = LazyNDRaw::<u16>::open(&path).unwrap();
+        assert_eq!((4, 3), (lazy.width(), lazy.height()));
+        assert!(!lazy.is_loaded());
+
+        let result = lazy.pix(0, 0);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [lazy][test_lazy_ndraw_header_only()] result = {:?}",
+            result
+        );
+        assert!(matches!(result, Err(SensorIoError::Io(_))));
+        assert!(!lazy.is_loaded());
+
+        println!("}}");
+    }
+}
@@ -1,5 +1,240 @@
+//! センサraw画像の入出力・処理ライブラリ
+//!
+//! まずpreludeをインポートする:
+//!
+//! ```
+//! use sensor_io::prelude::*;
+//!
+//! let raw = NDRaw::<u16>::new(4, 3);
+//! assert_eq!((4, 3), (raw.width(), raw.height()));
+//! ```
+
 // Raw Class with nalgebra
 pub mod naraw;
 
 // Raw Class with ndarray
 pub mod ndraw;
+
+// Bayer pattern
+pub mod bayer;
+
+// Statistics
+pub mod statistics;
+
+// Pixel type
+pub mod pixel;
+
+// Error
+pub mod error;
+
+// Temporal filter
+pub mod temporal;
+
+// Common raw image trait
+pub mod raw;
+
+// Defect pixel correction
+pub mod defect;
+
+// Auto exposure
+pub mod exposure;
+
+// Bad pixel map
+pub mod bpm;
+
+// Lens shading correction
+pub mod shading;
+
+// Image comparison
+pub mod compare;
+
+// Bayer-aware binning
+pub mod binning;
+
+// Rectangle region
+pub mod rect;
+
+// Centroid and peak detection
+pub mod centroid;
+
+// Gradient and focus metrics
+pub mod gradient;
+
+// Crop
+pub mod crop;
+
+// Flip
+pub mod flip;
+
+// Sensor configuration
+pub mod config;
+
+// Image blending
+pub mod blend;
+
+// Right-angle rotation
+pub mod rotate;
+
+// Radial profile
+pub mod profile;
+
+// Memory layout
+pub mod layout;
+
+// Row noise correction
+pub mod row_noise;
+
+// DSNU/PRNU
+pub mod uniformity;
+
+// PSF estimation
+pub mod psf;
+
+// Dark frame subtraction
+pub mod dark;
+
+// Resize
+pub mod resize;
+
+// Flat field correction
+pub mod flat_field;
+
+// HDR merge
+pub mod hdr;
+
+// Synthetic test charts
+pub mod testcharts;
+
+// DOL HDR interleave
+pub mod dol;
+
+// Lazy bin image loading
+pub mod lazy;
+
+// PWL companding
+pub mod pwl;
+
+// Bit depth conversion
+pub mod depth;
+
+// Focus stacking
+pub mod focus;
+
+// Pedestal
+pub mod pedestal;
+
+// Highlight clipping
+pub mod clipping;
+
+// Dark current model
+pub mod dark_model;
+
+// Linearity
+pub mod linearity;
+
+// Histogram equalization
+pub mod equalize;
+
+// Frame buffer
+pub mod frame_buffer;
+
+// Contrast stretch
+pub mod contrast;
+
+// 1D convolution
+pub mod convolve;
+
+// RGB image
+pub mod rgb;
+
+// Color correction
+pub mod color;
+
+// Radiometric calibration
+pub mod radiometry;
+
+// YCbCr / YUV420 conversion
+pub mod yuv;
+
+// Missing pixel interpolation
+pub mod inpaint;
+
+// Monochrome import
+pub mod mono;
+
+// Metadata sidecar
+mod sidecar;
+
+// Integral image / adaptive threshold
+pub mod integral;
+
+// Run-length encoding
+pub mod rle;
+
+// CFA layouts (Quad Bayer)
+pub mod cfa;
+
+// Tile mosaic composition
+pub mod mosaic;
+
+// RGB-IR / RGBW
+pub mod rgbir;
+
+// Processing pipeline
+pub mod pipeline;
+
+// Demosaic
+pub mod demosaic;
+
+// PDAF pixels
+pub mod pdaf;
+
+// False-color heatmap export
+pub mod heatmap;
+
+// Headerless raw import
+pub mod headerless;
+
+// Channel cross-talk
+pub mod crosstalk;
+
+// CFA visualization
+pub mod visualize;
+
+// Bin image writer/reader builder
+pub mod bin_builder;
+
+// Prelude
+pub mod prelude;
+
+// Bin image format
+mod binfmt;
+pub use binfmt::read_bin_header;
+
+// NetCDF I/O
+#[cfg(feature = "netcdf")]
+pub mod netcdf_io;
+
+// Compressed bin image
+#[cfg(feature = "flate2")]
+pub mod compress;
+
+// FFT spectrum
+#[cfg(feature = "rustfft")]
+pub mod spectrum;
+
+// Wiener deconvolution
+#[cfg(feature = "rustfft")]
+pub mod deconvolve;
+
+// Phase correlation registration
+#[cfg(feature = "rustfft")]
+pub mod registration;
+
+// TIFF I/O
+#[cfg(feature = "tiff")]
+pub mod tiff_io;
+
+// TOML sensor configuration
+#[cfg(feature = "toml")]
+pub mod sensor_config;
@@ -0,0 +1,139 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// リニアリティ評価結果
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LinearityResult {
+    pub r_squared: f64,
+    // 回帰直線からの最大乖離 (回帰値に対する%, 回帰値が0の点は除く)
+    pub max_deviation_percent: f64,
+    pub slope: f64,
+    pub intercept: f64,
+}
+
+// 露光時間と平均信号値の最小二乗直線回帰によるリニアリティ評価
+//   露光時間が全て同じ場合は傾き0, データ点が回帰直線上にあればR²は1, 要素数不一致はエラー
+pub fn compute_linearity(
+    exposure_times: &[f32],
+    mean_signals: &[f64],
+) -> Result<LinearityResult, SensorIoError> {
+    if exposure_times.len() != mean_signals.len() {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "exposure_times has {} entries, mean_signals has {}",
+            exposure_times.len(),
+            mean_signals.len()
+        )));
+    }
+    let n = exposure_times.len().max(1) as f64;
+    let xs: Vec<f64> = exposure_times.iter().map(|t| *t as f64).collect();
+    let x_mean = xs.iter().sum::<f64>() / n;
+    let y_mean = mean_signals.iter().sum::<f64>() / n;
+    let sxx: f64 = xs.iter().map(|x| (x - x_mean).powi(2)).sum();
+    let sxy: f64 = xs
+        .iter()
+        .zip(mean_signals)
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum();
+    let slope = if sxx > 0.0 { sxy / sxx } else { 0.0 };
+    let intercept = y_mean - slope * x_mean;
+
+    let mut ss_res = 0.0;
+    let mut ss_tot = 0.0;
+    let mut max_deviation_percent = 0.0f64;
+    for (x, y) in xs.iter().zip(mean_signals) {
+        let fit = intercept + slope * x;
+        ss_res += (y - fit).powi(2);
+        ss_tot += (y - y_mean).powi(2);
+        if fit.abs() > f64::EPSILON {
+            max_deviation_percent = max_deviation_percent.max((y - fit).abs() / fit.abs() * 100.0);
+        }
+    }
+    let r_squared = if ss_tot > 0.0 {
+        1.0 - ss_res / ss_tot
+    } else {
+        1.0
+    };
+
+    Ok(LinearityResult {
+        r_squared,
+        max_deviation_percent,
+        slope,
+        intercept,
+    })
+}
+
+// フレーム群の平均値によるリニアリティ評価
+pub fn compute_linearity_from_frames<T: PixelType>(
+    exposure_times: &[f32],
+    frames: &[NDRaw<T>],
+) -> Result<LinearityResult, SensorIoError> {
+    let mean_signals: Vec<f64> = frames
+        .iter()
+        .map(|frame| frame.compute_statistics().mean)
+        .collect();
+    compute_linearity(exposure_times, &mean_signals)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compute_linearity, compute_linearity_from_frames};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compute_linearity() {
+        println!("linearity::test::test_compute_linearity()  {{");
+
+        let exposure_times = [1.0f32, 2.0, 4.0, 8.0, 16.0];
+        let signals: Vec<f64> = exposure_times
+            .iter()
+            .map(|t| 64.0 + 200.0 * *t as f64)
+            .collect();
+        let result = compute_linearity(&exposure_times, &signals).unwrap();
+        println!(
+            "  [linearity][test_compute_linearity()] result = {:?}",
+            result
+        );
+        assert!((result.r_squared - 1.0).abs() < 1e-12);
+        assert!(result.max_deviation_percent < 1e-9);
+        assert!((result.slope - 200.0).abs() < 1e-9);
+        assert!((result.intercept - 64.0).abs() < 1e-9);
+
+        // 高輝度側の飽和による非線形性
+        let mut saturated = signals.clone();
+        saturated[4] = 2500.0;
+        let result = compute_linearity(&exposure_times, &saturated).unwrap();
+        println!(
+            "  [linearity][test_compute_linearity()] saturated = {:?}",
+            result
+        );
+        assert!(result.r_squared < 0.99);
+        assert!(result.max_deviation_percent > 5.0);
+
+        let result = compute_linearity(&exposure_times, &signals[..3]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_linearity_from_frames() {
+        println!("linearity::test::test_compute_linearity_from_frames()  {{");
+
+        let exposure_times = [1.0f32, 2.0, 3.0];
+        let frames: Vec<NDRaw<u16>> = exposure_times
+            .iter()
+            .map(|t| {
+                let level = 100 + 500 * *t as u16;
+                NDRaw::<u16>::new_from_vector2d(&[vec![level - 1, level + 1], vec![level, level]])
+            })
+            .collect();
+        let result = compute_linearity_from_frames(&exposure_times, &frames).unwrap();
+        assert_eq!(1.0, result.r_squared);
+        assert_eq!(500.0, result.slope);
+        assert_eq!(100.0, result.intercept);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,135 @@
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rgb::RgbSource;
+
+// モノクロセンサ向けRGB画像の変換方式
+//   変換した画像のCFA配列はCfaLayout::Mono (CFA面分離等は非対応エラー)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum MonoConversion {
+    // Rec.709 輝度 (0.2126 R + 0.7152 G + 0.0722 B)
+    Luma709,
+    // Gチャネルのみ
+    Green,
+}
+
+// RGB画像 => 行優先の画素列 (ビット深度はRgbSource, 四捨五入してTの範囲に飽和)
+fn convert_rgb_to_mono<T: PixelType>(
+    img_in: &image::DynamicImage,
+    conversion: MonoConversion,
+) -> Vec<T> {
+    let source = RgbSource::new::<T>(img_in);
+    let (width, height) = (img_in.width() as usize, img_in.height() as usize);
+    (0..height)
+        .flat_map(|y| (0..width).map(move |x| (x, y)))
+        .map(|(x, y)| {
+            let [r, g, b] = source.rgb_at(x, y);
+            let v = match conversion {
+                MonoConversion::Luma709 => 0.2126 * r + 0.7152 * g + 0.0722 * b,
+                MonoConversion::Green => g,
+            };
+            T::from_f64_saturating(v.round())
+        })
+        .collect()
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // image(RGB)変換コンストラクタ (モノクロセンサ用, CFAサンプリングしない)
+    pub fn new_from_rgbimage_mono(path_image_in: String, conversion: MonoConversion) -> Self {
+        let img_in = image::open(path_image_in).unwrap();
+        Self::new_from_dynamic_image_mono(&img_in, conversion)
+    }
+
+    // DynamicImage変換コンストラクタ (モノクロセンサ用)
+    pub fn new_from_dynamic_image_mono(
+        img_in: &image::DynamicImage,
+        conversion: MonoConversion,
+    ) -> Self {
+        let (width, height) = (img_in.width() as usize, img_in.height() as usize);
+        let pixels = convert_rgb_to_mono(img_in, conversion);
+        let data = ndarray::Array2::from_shape_vec((height, width), pixels).unwrap();
+        NDRaw::from_ndarray(data)
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // image(RGB)変換コンストラクタ (モノクロセンサ用, CFAサンプリングしない)
+    pub fn new_from_rgbimage_mono(path_image_in: String, conversion: MonoConversion) -> Self {
+        let img_in = image::open(path_image_in).unwrap();
+        Self::new_from_dynamic_image_mono(&img_in, conversion)
+    }
+
+    // DynamicImage変換コンストラクタ (モノクロセンサ用)
+    pub fn new_from_dynamic_image_mono(
+        img_in: &image::DynamicImage,
+        conversion: MonoConversion,
+    ) -> Self {
+        let (width, height) = (img_in.width() as usize, img_in.height() as usize);
+        let pixels = convert_rgb_to_mono(img_in, conversion);
+        let data = nalgebra::DMatrix::from_row_slice(height, width, &pixels);
+        NARaw { data }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MonoConversion;
+    use crate::cfa::{CfaChannel, CfaLayout};
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_new_from_rgbimage_mono() {
+        println!("mono::test::test_new_from_rgbimage_mono()  {{");
+
+        // 純粋な赤 (8bit)
+        let img = image::DynamicImage::ImageRgb8(image::ImageBuffer::from_pixel(
+            4,
+            2,
+            image::Rgb([255u8, 0, 0]),
+        ));
+        let luma = NDRaw::<u16>::new_from_dynamic_image_mono(&img, MonoConversion::Luma709);
+        println!(
+            "  [mono][test_new_from_rgbimage_mono()] luma = {}",
+            luma.data()
+        );
+        // 0.2126 * 255 = 54.2 (CFAサンプリングと違い全画素同じ値)
+        assert!(luma.data().iter().all(|v| *v == 54));
+        let green = NDRaw::<u16>::new_from_dynamic_image_mono(&img, MonoConversion::Green);
+        assert!(green.data().iter().all(|v| *v == 0));
+
+        // ファイル経由 (16bit)
+        let img = image::ImageBuffer::from_pixel(3, 2, image::Rgb([1000u16, 2000, 3000]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_mono_rgb16_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+        let nd = NDRaw::<u16>::new_from_rgbimage_mono(path_str.clone(), MonoConversion::Luma709);
+        let na = NARaw::<u16>::new_from_rgbimage_mono(path_str, MonoConversion::Green);
+        std::fs::remove_file(&path).unwrap();
+        // 212.6 + 1430.4 + 216.6 = 1859.6
+        assert_eq!((3, 2), (nd.width(), nd.height()));
+        assert_eq!(1860, *nd.pix(2, 1));
+        assert_eq!((3, 2), (na.width(), na.height()));
+        assert_eq!(2000, *na.pix(2, 1));
+
+        // モノクロ画像はCFA処理を拒否する
+        let layout = CfaLayout::Mono;
+        assert!(matches!(
+            nd.extract_cfa_planes(&layout),
+            Err(SensorIoError::Unsupported(_))
+        ));
+        assert!(matches!(
+            nd.extract_cfa_plane(&layout, CfaChannel::White),
+            Err(SensorIoError::Unsupported(_))
+        ));
+        assert!(matches!(
+            layout.bayer_pattern(),
+            Err(SensorIoError::Unsupported(_))
+        ));
+        assert_eq!(None, layout.channel_at(0, 0));
+
+        println!("}}");
+    }
+}
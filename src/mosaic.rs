@@ -0,0 +1,104 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+use std::marker::PhantomData;
+
+// 既知のオフセットで取得したタイルの合成
+//   画素値の和をf64で積算するため, 重なりが多くてもTの範囲で飽和しない
+pub struct MosaicComposer<T: PixelType> {
+    canvas: NDRaw<f64>,
+    weight: NDRaw<u32>,
+    _pixel: PhantomData<T>,
+}
+
+impl<T: PixelType> MosaicComposer<T> {
+    // 合成後の画サイズ指定コンストラクタ
+    pub fn new(total_width: usize, total_height: usize) -> Self {
+        MosaicComposer {
+            canvas: NDRaw::new(total_width, total_height),
+            weight: NDRaw::new(total_width, total_height),
+            _pixel: PhantomData,
+        }
+    }
+
+    // タイル追加 (左上が(x_offset, y_offset), キャンバスからはみ出す場合はエラー)
+    pub fn add_tile(
+        &mut self,
+        tile: &NDRaw<T>,
+        x_offset: usize,
+        y_offset: usize,
+    ) -> Result<(), SensorIoError> {
+        Rect::new(x_offset, y_offset, tile.width(), tile.height())
+            .check_within(self.canvas.width(), self.canvas.height())?;
+        for y in 0..tile.height() {
+            for x in 0..tile.width() {
+                *self.canvas.pix_mut(x_offset + x, y_offset + y) +=
+                    tile.pix(x, y).to_f64().unwrap();
+                *self.weight.pix_mut(x_offset + x, y_offset + y) += 1;
+            }
+        }
+        Ok(())
+    }
+
+    // 重み(重なり枚数)マップ取得
+    pub fn weight(&self) -> &NDRaw<u32> {
+        &self.weight
+    }
+
+    // 合成画像 (重なりは平均, タイルのない画素は0)
+    pub fn into_blended(&self) -> NDRaw<f32> {
+        let mut data = ndarray::Array2::<f32>::zeros(self.canvas.data.dim());
+        ndarray::Zip::from(&mut data)
+            .and(&self.canvas.data)
+            .and(&self.weight.data)
+            .for_each(|out, sum, weight| {
+                if *weight > 0 {
+                    *out = (sum / *weight as f64) as f32;
+                }
+            });
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MosaicComposer;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_mosaic_composer() {
+        println!("mosaic::test::test_mosaic_composer()  {{");
+
+        // 40x20のタイル2枚を10画素重ねて70x20に合成
+        let left = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 40]; 20]);
+        let right = NDRaw::<u16>::new_from_vector2d(&vec![vec![300; 40]; 20]);
+        let mut composer = MosaicComposer::<u16>::new(70, 24);
+        composer.add_tile(&left, 0, 2).unwrap();
+        composer.add_tile(&right, 30, 2).unwrap();
+
+        let blended = composer.into_blended();
+        println!(
+            "  [mosaic][test_mosaic_composer()] row = {}",
+            blended.data().row(10)
+        );
+        assert_eq!(100.0, *blended.pix(29, 10));
+        for x in 30..40 {
+            assert_eq!(200.0, *blended.pix(x, 10));
+            assert_eq!(2, *composer.weight().pix(x, 10));
+        }
+        assert_eq!(300.0, *blended.pix(40, 10));
+        // タイルのない行
+        assert_eq!(0.0, *blended.pix(35, 0));
+        assert_eq!(0, *composer.weight().pix(35, 0));
+
+        // はみ出しはエラー
+        assert!(matches!(
+            composer.add_tile(&right, 31, 0),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        println!("}}");
+    }
+}
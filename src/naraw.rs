@@ -1,15 +1,17 @@
-use byteorder::{ReadBytesExt, WriteBytesExt};
-use image::GenericImageView;
+use crate::bin_builder::{BinReader, BinWriter};
+use crate::binfmt;
+use crate::error::SensorIoError;
+use crate::pixel::PixelType;
+use crate::rgb::RgbSource;
 use nalgebra;
-use num_traits;
 use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, Read, Write};
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct NARaw<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> {
-    data: nalgebra::DMatrix<T>,
+pub struct NARaw<T: PixelType> {
+    pub(crate) data: nalgebra::DMatrix<T>,
 }
-impl<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> NARaw<T> {
+impl<T: PixelType> NARaw<T> {
     // 画サイズ指定コンストラクタ
     pub fn new(width: usize, height: usize) -> Self {
         let data = nalgebra::DMatrix::<T>::zeros(height, width);
@@ -22,21 +24,58 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> NARa
         NARaw { data }
     }
 
-    // image(bin)変換コンストラクタ
-    pub fn new_from_binimage(path_raw_in: String) -> Self {
-        let mut f_read = BufReader::new(File::open(path_raw_in).unwrap());
+    // nalgebra行列変換コンストラクタ (所有権を受け取りコピーしない, (y, x)でアクセスされる行列)
+    pub fn from_dmatrix(matrix: nalgebra::DMatrix<T>) -> Self {
+        NARaw { data: matrix }
+    }
 
-        let width = f_read.read_u16::<byteorder::LittleEndian>().unwrap() as usize; // Little Endian(u16)
-        let height = f_read.read_u16::<byteorder::LittleEndian>().unwrap() as usize; // Little Endian(u16)
-        let mut data = nalgebra::DMatrix::<T>::zeros(height, width);
-        for y in 0..height {
-            for x in 0..width {
-                data[(y, x)] =
-                    T::from_u16(f_read.read_u16::<byteorder::LittleEndian>().unwrap()).unwrap();
+    // (x, y, 値)の列からのコンストラクタ (指定外の画素はdefault, 範囲外の座標はエラー)
+    pub fn from_sparse(
+        width: usize,
+        height: usize,
+        iter: impl Iterator<Item = (usize, usize, T)>,
+        default: T,
+    ) -> Result<Self, SensorIoError> {
+        let mut raw = NARaw {
+            data: nalgebra::DMatrix::from_element(height, width, default),
+        };
+        for (x, y, value) in iter {
+            if !raw.contains(x, y) {
+                return Err(SensorIoError::OutOfBounds(format!(
+                    "pixel ({}, {}) is outside the {}x{} image",
+                    x, y, width, height
+                )));
             }
+            *raw.pix_mut(x, y) = value;
         }
+        Ok(raw)
+    }
 
-        NARaw { data }
+    // image(bin)変換コンストラクタ (CRC32があれば検証)
+    pub fn new_from_binimage(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        Self::read_image(&mut f_read, BinReader::new())
+    }
+
+    // image(bin)変換コンストラクタ (CRC32必須)
+    pub fn new_from_binimage_verified(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        Self::read_image(&mut f_read, BinReader::new().checksum(true))
+    }
+
+    // ストリームからのbin画像読み込み (CRC32があれば検証)
+    pub fn read_from_stream<R: Read + ?Sized>(reader: &mut R) -> Result<Self, SensorIoError> {
+        Self::read_image(reader, BinReader::new())
+    }
+
+    fn read_image<R: Read + ?Sized>(
+        reader: &mut R,
+        bin_reader: BinReader,
+    ) -> Result<Self, SensorIoError> {
+        let (width, height, pixels) = bin_reader.read_pixels::<T, R>(reader)?;
+        let data = nalgebra::DMatrix::from_row_slice(height, width, &pixels);
+
+        Ok(NARaw { data })
     }
 
     // image(RGB)変換コンストラクタ
@@ -51,7 +90,7 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> NARa
         &self.data
     }
 
-    // pix取得
+    // pix取得 (引数は(x, y)順, data()の添字は[(y, x)]順)
     pub fn pix(&self, x: usize, y: usize) -> &T {
         &self.data[(y, x)]
     }
@@ -59,6 +98,22 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> NARa
         &mut self.data[(y, x)]
     }
 
+    // (x, y) => 行優先の通し番号
+    pub fn xy_to_index(&self, x: usize, y: usize) -> usize {
+        y * self.width() + x
+    }
+
+    // 行優先の通し番号 => (x, y)
+    pub fn index_to_xy(&self, index: usize) -> (usize, usize) {
+        (index % self.width(), index / self.width())
+    }
+
+    // 行優先の通し番号でpix取得
+    pub fn pixel_at_index(&self, index: usize) -> &T {
+        let (x, y) = self.index_to_xy(index);
+        self.pix(x, y)
+    }
+
     // 形状取得
     pub fn shape(&self) -> (usize, usize) {
         self.data.shape()
@@ -74,61 +129,136 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + nalgebra::Scalar> NARa
         self.data.nrows()
     }
 
-    // bin画像書き込み
-    pub fn write_binimage(&self, path_raw_out: String) -> &Self {
-        let mut f_write = BufWriter::new(File::create(path_raw_out).unwrap());
+    // 画素数取得 (width * height)
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
 
-        let width = Self::width(self);
-        let height = Self::height(self);
-        let _ = f_write.write_u16::<byteorder::LittleEndian>(width as u16);
-        let _ = f_write.write_u16::<byteorder::LittleEndian>(height as u16);
-        for y in 0..height {
-            for x in 0..width {
-                let _ = f_write
-                    .write_u16::<byteorder::LittleEndian>(self.data[(y, x)].to_u16().unwrap());
-            }
+    // 画素なし判定
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 座標の範囲内判定
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width() && y < self.height()
+    }
+
+    // pix取得 (範囲外はOutOfBoundsエラー)
+    pub fn try_pix(&mut self, x: usize, y: usize) -> Result<&mut T, SensorIoError> {
+        if !self.contains(x, y) {
+            return Err(SensorIoError::OutOfBounds(format!(
+                "({}, {}) outside {}x{} image",
+                x,
+                y,
+                self.width(),
+                self.height()
+            )));
         }
+        Ok(self.pix_mut(x, y))
+    }
+
+    // bin画像での1画素あたりのバイト数取得 (ファイルサイズ = 4 + width * height * pixel_byte_size() [+ CRC32(4)])
+    pub fn pixel_byte_size() -> usize {
+        binfmt::PIXEL_BYTE_SIZE
+    }
+
+    // bin画像書き込み (CRC32付き)
+    pub fn write_binimage(&self, path_raw_out: String) -> Result<&Self, SensorIoError> {
+        BinWriter::new().write(self, path_raw_out)?;
+
+        Ok(self)
+    }
 
-        self
+    // ストリームへのbin画像書き込み (CRC32付き)
+    pub fn write_to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> Result<(), SensorIoError> {
+        BinWriter::new().write_to_stream(self, writer)
     }
 
     // bin画像読み込み
-    pub fn read_binimage(&mut self, path_raw_in: String) -> &Self {
-        *self = Self::new_from_binimage(path_raw_in);
+    pub fn read_binimage(&mut self, path_raw_in: String) -> Result<&Self, SensorIoError> {
+        *self = Self::new_from_binimage(path_raw_in)?;
 
-        self
+        Ok(self)
     }
+
+    // アフィン変換 (出力画素を逆写像しバイリニア補間、範囲外は0)
+    pub fn warp_affine(
+        &self,
+        matrix: nalgebra::Matrix2x3<f64>,
+        out_w: usize,
+        out_h: usize,
+    ) -> Self {
+        let linear = matrix.fixed_view::<2, 2>(0, 0).into_owned();
+        let translation = matrix.column(2).into_owned();
+        let data = match linear.try_inverse() {
+            Some(inverse) => nalgebra::DMatrix::<T>::from_fn(out_h, out_w, |y, x| -> T {
+                let src = inverse * (nalgebra::Vector2::new(x as f64, y as f64) - translation);
+                self.sample_bilinear(src.x, src.y)
+                    .map_or(T::zero(), T::from_f64_saturating)
+            }),
+            None => nalgebra::DMatrix::<T>::zeros(out_h, out_w),
+        };
+        NARaw { data }
+    }
+
+    // バイリニア補間 (範囲外はNone)
+    fn sample_bilinear(&self, x: f64, y: f64) -> Option<f64> {
+        const EPS: f64 = 1e-9;
+        let max_x = Self::width(self) as f64 - 1.0;
+        let max_y = Self::height(self) as f64 - 1.0;
+        if !(x >= -EPS && y >= -EPS && x <= max_x + EPS && y <= max_y + EPS) {
+            return None;
+        }
+        let x = x.clamp(0.0, max_x);
+        let y = y.clamp(0.0, max_y);
+        let x0 = x.floor() as usize;
+        let y0 = y.floor() as usize;
+        let x1 = (x0 + 1).min(Self::width(self) - 1);
+        let y1 = (y0 + 1).min(Self::height(self) - 1);
+        let fx = x - x0 as f64;
+        let fy = y - y0 as f64;
+        let p = |xx: usize, yy: usize| self.data[(yy, xx)].to_f64().unwrap();
+        let top = p(x0, y0) * (1.0 - fx) + p(x1, y0) * fx;
+        let bottom = p(x0, y1) * (1.0 - fx) + p(x1, y1) * fx;
+        Some(top * (1.0 - fy) + bottom * fy)
+    }
+
     fn convert_vector2d_to_dmatrix(vec2d: &[Vec<T>]) -> nalgebra::DMatrix<T> {
         nalgebra::DMatrix::<T>::from_fn(vec2d.len(), vec2d[0].len(), |y, x| -> T { vec2d[y][x] })
     }
 
     fn convert_rgb_to_dmatrix(img_in: &image::DynamicImage) -> nalgebra::DMatrix<T> {
+        let source = RgbSource::new::<T>(img_in);
         nalgebra::DMatrix::<T>::from_fn(
             img_in.height() as usize,
             img_in.width() as usize,
-            |y, x| -> T { Self::convert_rgb_to_bayer(img_in, x, y) },
+            |y, x| -> T { Self::convert_rgb_to_bayer(&source, x, y) },
         )
     }
 
-    fn convert_rgb_to_bayer(img_in: &image::DynamicImage, x: usize, y: usize) -> T {
-        let pix;
-        if x % 2 != y % 2 {
+    fn convert_rgb_to_bayer(source: &RgbSource, x: usize, y: usize) -> T {
+        let rgb = source.rgb_at(x, y);
+        let channel = if x % 2 != y % 2 {
             // G
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[1]).unwrap();
-        } else if x % 2 == 0 {
+            rgb[1]
+        } else if x.is_multiple_of(2) {
             // R
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[0]).unwrap();
+            rgb[0]
         } else {
             // B
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[2]).unwrap();
-        }
-        pix
+            rgb[2]
+        };
+        // Tに収まらない値は飽和
+        T::from_f64_saturating(channel)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::NARaw;
+    use crate::bin_builder::BinWriter;
+    use crate::error::SensorIoError;
 
     #[test]
     fn test_new() {
@@ -147,6 +277,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_new_from_vector() {
         println!("naraw::test::test_new_from_vector()  {{");
 
@@ -189,7 +320,9 @@ mod test {
             raw_in.data()
         );
 
-        raw_in.write_binimage(String::from("write_naraw.bin"));
+        raw_in
+            .write_binimage(String::from("write_naraw.bin"))
+            .unwrap();
 
         println!("}}");
     }
@@ -198,7 +331,7 @@ mod test {
     fn test_new_from_binimage() {
         println!("ndraw::test::test_new_from_vector()  {{");
 
-        let raw_in = NARaw::<u16>::new_from_binimage(String::from("testdata/test.bin"));
+        let raw_in = NARaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
         println!(
             "  [ndraw][test_new_from_binimage()] raw_in.width()  = {}",
             raw_in.width()
@@ -224,4 +357,262 @@ mod test {
 
         println!("}}");
     }
+
+    #[test]
+    fn test_warp_affine() {
+        println!("naraw::test::test_warp_affine()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+        let raw_in = NARaw::<u16>::new_from_vector2d(&vec2d);
+
+        // 恒等変換
+        let identity = nalgebra::Matrix2x3::new(1.0, 0.0, 0.0, 0.0, 1.0, 0.0);
+        let raw_out = raw_in.warp_affine(identity, 4, 3);
+        println!(
+            "  [naraw][test_warp_affine()] identity = {}",
+            raw_out.data()
+        );
+        assert_eq!(raw_in.data(), raw_out.data());
+
+        // 平行移動 (+1, +1)
+        let translation = nalgebra::Matrix2x3::new(1.0, 0.0, 1.0, 0.0, 1.0, 1.0);
+        let raw_out = raw_in.warp_affine(translation, 4, 3);
+        println!(
+            "  [naraw][test_warp_affine()] translation = {}",
+            raw_out.data()
+        );
+        for y in 0..3 {
+            for x in 0..4 {
+                let expected = if x >= 1 && y >= 1 {
+                    *raw_in.pix(x - 1, y - 1)
+                } else {
+                    0
+                };
+                assert_eq!(expected, *raw_out.pix(x, y));
+            }
+        }
+
+        // サブピクセル平行移動はバイリニア補間
+        let raw_f32 = NARaw::<f32>::new_from_vector2d(&[vec![0.0, 2.0, 4.0], vec![6.0, 8.0, 10.0]]);
+        let half = nalgebra::Matrix2x3::new(1.0, 0.0, 0.5, 0.0, 1.0, 0.0);
+        let raw_out = raw_f32.warp_affine(half, 3, 2);
+        assert_eq!(0.0, *raw_out.pix(0, 0));
+        assert_eq!(1.0, *raw_out.pix(1, 0));
+        assert_eq!(9.0, *raw_out.pix(2, 1));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_pixel_at_index() {
+        println!("naraw::test::test_pixel_at_index()  {{");
+
+        let raw_in = NARaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        println!(
+            "  [naraw][test_pixel_at_index()] raw_in.pixel_at_index(5) = {}",
+            raw_in.pixel_at_index(5)
+        );
+        assert_eq!(raw_in.pix(1, 1), raw_in.pixel_at_index(5));
+        assert_eq!(5, raw_in.xy_to_index(1, 1));
+        assert_eq!((3, 2), raw_in.index_to_xy(11));
+        for i in 0..raw_in.width() * raw_in.height() {
+            let (x, y) = raw_in.index_to_xy(i);
+            assert_eq!(i, raw_in.xy_to_index(x, y));
+            assert_eq!(i as u16, *raw_in.pixel_at_index(i));
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_new_from_binimage_verified() {
+        println!("naraw::test::test_new_from_binimage_verified()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+        let raw_in = NARaw::<u16>::new_from_vector2d(&vec2d);
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_naraw_crc_{}.bin", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+        // CRC32なしのファイルは検証付き読み込みでエラー
+        BinWriter::new()
+            .checksum(false)
+            .write(&raw_in, &path)
+            .unwrap();
+        let result = NARaw::<u16>::new_from_binimage_verified(path_str.clone());
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        // write_binimageはCRC32付きで書き込む
+        raw_in.write_binimage(path_str.clone()).unwrap();
+        let raw_read = NARaw::<u16>::new_from_binimage_verified(path_str.clone()).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+        let raw_plain =
+            NARaw::<u16>::read_from_stream(&mut std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(raw_in.data(), raw_plain.data());
+
+        // 画素部の1バイトを反転
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[8 + 2 * 5] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+        let result = NARaw::<u16>::new_from_binimage_verified(path_str);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [naraw][test_new_from_binimage_verified()] result = {:?}",
+            result.as_ref().err()
+        );
+        assert!(matches!(
+            result,
+            Err(SensorIoError::ChecksumMismatch { .. })
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_stream_roundtrip() {
+        println!("naraw::test::test_stream_roundtrip()  {{");
+
+        let raw_in = NARaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        raw_in.write_to_stream(&mut cursor).unwrap();
+
+        // パス指定の書き込みとバイト単位で一致
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_naraw_stream_{}.bin", std::process::id()));
+        raw_in
+            .write_binimage(path.to_str().unwrap().to_string())
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes, cursor.get_ref());
+
+        cursor.set_position(0);
+        let raw_read = NARaw::<u16>::read_from_stream(&mut cursor).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        // 途中で切れたストリーム
+        let mut truncated = std::io::Cursor::new(bytes[..bytes.len() / 2].to_vec());
+        let result = NARaw::<u16>::read_from_stream(&mut truncated);
+        println!(
+            "  [naraw][test_stream_roundtrip()] truncated = {:?}",
+            result.as_ref().err()
+        );
+        assert!(matches!(
+            result,
+            Err(SensorIoError::Io(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_contains() {
+        println!("naraw::test::test_contains()  {{");
+
+        let raw = NARaw::<u16>::new(4, 3);
+        assert!(raw.contains(0, 0));
+        assert!(raw.contains(3, 2));
+        assert!(!raw.contains(4, 2));
+        assert!(!raw.contains(3, 3));
+        assert!(!raw.contains(4, 3));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_try_pix() {
+        println!("naraw::test::test_try_pix()  {{");
+
+        let mut raw = NARaw::<u16>::new(4, 3);
+        *raw.try_pix(3, 2).unwrap() = 7;
+        assert_eq!(7, *raw.pix(3, 2));
+        assert!(matches!(
+            raw.try_pix(4, 0),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+        assert!(matches!(
+            raw.try_pix(0, 3),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_from_dmatrix() {
+        println!("naraw::test::test_from_dmatrix()  {{");
+
+        // 3行4列 (height 3, width 4)
+        let matrix = nalgebra::DMatrix::<u16>::from_fn(3, 4, |y, x| (y * 10 + x) as u16);
+        let ptr = matrix.as_ptr();
+        let raw = NARaw::<u16>::from_dmatrix(matrix);
+        assert_eq!((4, 3), (raw.width(), raw.height()));
+        assert_eq!(21, *raw.pix(1, 2));
+        // バッファはコピーされない
+        assert_eq!(ptr, raw.data().as_ptr());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_new_from_rgbimage_16bit() {
+        println!("naraw::test::test_new_from_rgbimage_16bit()  {{");
+
+        // 16bit RGB画像 (R=300, G=40, B=65535)
+        let img = image::ImageBuffer::from_pixel(4, 2, image::Rgb([300u16, 40, 65535]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_naraw_rgb16_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+
+        // 16bit値を表せない型へは8bitに縮小して読み込む (65535 => 255, 300 => 1)
+        let raw_u8 = NARaw::<u8>::new_from_rgbimage(path_str.clone());
+        let raw_u16 = NARaw::<u16>::new_from_rgbimage(path_str);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [naraw][test_new_from_rgbimage_16bit()] raw_u8 = {}",
+            raw_u8.data()
+        );
+        assert_eq!(
+            (1, 0, 255),
+            (*raw_u8.pix(0, 0), *raw_u8.pix(1, 0), *raw_u8.pix(1, 1))
+        );
+        assert_eq!(
+            (300, 40, 65535),
+            (*raw_u16.pix(0, 0), *raw_u16.pix(1, 0), *raw_u16.pix(1, 1))
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_from_sparse() {
+        println!("naraw::test::test_from_sparse()  {{");
+
+        let placements = vec![(0, 0, 5), (2, 1, 7), (1, 2, 9)];
+        let raw = NARaw::<u16>::from_sparse(3, 3, placements.into_iter(), 1).unwrap();
+        println!("  [naraw][test_from_sparse()] raw = \n{}", raw.data());
+        assert_eq!((3, 3), (raw.width(), raw.height()));
+        assert_eq!((5, 7, 9), (*raw.pix(0, 0), *raw.pix(2, 1), *raw.pix(1, 2)));
+        assert_eq!((1, 1), (*raw.pix(1, 1), *raw.pix(2, 2)));
+
+        let result = NARaw::<u16>::from_sparse(3, 3, [(3, 0, 1)].into_iter(), 0);
+        assert!(matches!(result, Err(SensorIoError::OutOfBounds(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_len() {
+        println!("naraw::test::test_len()  {{");
+
+        let empty = NARaw::<u16>::new(0, 0);
+        assert!(empty.is_empty());
+        assert_eq!(0, empty.len());
+
+        let raw = NARaw::<u16>::new(4, 3);
+        assert!(!raw.is_empty());
+        assert_eq!(12, raw.len());
+
+        println!("}}");
+    }
 }
@@ -1,50 +1,87 @@
-use byteorder::{ReadBytesExt, WriteBytesExt};
-use image::GenericImageView;
+use crate::bin_builder::{BinReader, BinWriter};
+use crate::binfmt;
+use crate::error::SensorIoError;
+use crate::pixel::PixelType;
+use crate::rgb::RgbSource;
+use crate::sidecar;
 use ndarray;
-use num_traits;
-use std::fs::File;
-use std::io::{BufReader, BufWriter};
+use std::collections::HashMap;
+use std::io::{Read, Write};
 
 #[derive(Clone, serde::Serialize, serde::Deserialize)]
-pub struct NDRaw<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitive> {
-    data: ndarray::Array2<T>,
+pub struct NDRaw<T: PixelType> {
+    pub(crate) data: ndarray::Array2<T>,
+    // 撮影メタデータ (露光時間・ゲイン・タイムスタンプ等, bin画像とは別のJSONサイドカーに保存)
+    //   画素を変更する演算の結果には引き継がない (cloneは引き継ぐ)
+    #[serde(default)]
+    pub(crate) metadata: HashMap<String, String>,
 }
-impl<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitive> NDRaw<T> {
+impl<T: PixelType> NDRaw<T> {
     // 画サイズ指定コンストラクタ
     pub fn new(width: usize, height: usize) -> Self {
         let data = ndarray::Array2::<T>::zeros((height, width));
-        NDRaw { data }
+        NDRaw::from_ndarray(data)
     }
 
     // Vector2D変換コンストラクタ
     pub fn new_from_vector2d(vec2d: &[Vec<T>]) -> Self {
         let vec1d = Self::convert_vector2d_to_vector1d(vec2d);
         let data = Self::convert_vector1d_to_ndarray(vec1d, vec2d[0].len(), vec2d.len());
-        NDRaw { data }
+        NDRaw::from_ndarray(data)
     }
 
-    // image(bin)変換コンストラクタ
-    pub fn new_from_binimage(path_raw_in: String) -> Self {
-        let mut f_read = BufReader::new(File::open(path_raw_in).unwrap());
+    // ndarray変換コンストラクタ (所有権を受け取りコピーしない, [[y, x]]でアクセスされる配列)
+    pub fn from_ndarray(arr: ndarray::Array2<T>) -> Self {
+        NDRaw {
+            data: arr,
+            metadata: HashMap::new(),
+        }
+    }
 
-        let width = f_read.read_u16::<byteorder::LittleEndian>().unwrap() as usize; // Little Endian(u16)
-        let height = f_read.read_u16::<byteorder::LittleEndian>().unwrap() as usize; // Little Endian(u16)
-        let mut data = ndarray::Array2::<T>::zeros((height, width));
-        for y in 0..height {
-            for x in 0..width {
-                data[[y, x]] =
-                    T::from_u16(f_read.read_u16::<byteorder::LittleEndian>().unwrap()).unwrap();
+    // (x, y, 値)の列からのコンストラクタ (指定外の画素はdefault, 範囲外の座標はエラー)
+    pub fn from_sparse(
+        width: usize,
+        height: usize,
+        iter: impl Iterator<Item = (usize, usize, T)>,
+        default: T,
+    ) -> Result<Self, SensorIoError> {
+        let mut raw = NDRaw::from_ndarray(ndarray::Array2::from_elem((height, width), default));
+        for (x, y, value) in iter {
+            if !raw.contains(x, y) {
+                return Err(SensorIoError::OutOfBounds(format!(
+                    "pixel ({}, {}) is outside the {}x{} image",
+                    x, y, width, height
+                )));
             }
+            *raw.pix_mut(x, y) = value;
         }
+        Ok(raw)
+    }
+
+    // image(bin)変換コンストラクタ (CRC32があれば検証, サイドカー(<path>.json)があればメタデータとして読み込む)
+    pub fn new_from_binimage(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut raw = BinReader::new().read(&path_raw_in)?;
+        raw.metadata = sidecar::read_sidecar(&path_raw_in)?;
+        Ok(raw)
+    }
+
+    // image(bin)変換コンストラクタ (CRC32必須, サイドカーがあればメタデータとして読み込む)
+    pub fn new_from_binimage_verified(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut raw = BinReader::new().checksum(true).read(&path_raw_in)?;
+        raw.metadata = sidecar::read_sidecar(&path_raw_in)?;
+        Ok(raw)
+    }
 
-        NDRaw { data }
+    // ストリームからのbin画像読み込み (CRC32があれば検証)
+    pub fn read_from_stream<R: Read + ?Sized>(reader: &mut R) -> Result<Self, SensorIoError> {
+        BinReader::new().read_from_stream(reader)
     }
 
     // image(RGB)変換コンストラクタ
     pub fn new_from_rgbimage(path_image_in: String) -> Self {
         let img_in = image::open(path_image_in).unwrap();
         let data = Self::convert_rgb_to_ndarray(&img_in);
-        NDRaw { data }
+        NDRaw::from_ndarray(data)
     }
 
     // data取得
@@ -52,7 +89,22 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitiv
         &self.data
     }
 
-    // pix取得
+    // メタデータ設定
+    pub fn set_metadata(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.metadata.insert(key.into(), value.into());
+    }
+
+    // メタデータ取得
+    pub fn get_metadata(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(|v| v.as_str())
+    }
+
+    // メタデータ一覧取得
+    pub fn metadata(&self) -> &HashMap<String, String> {
+        &self.metadata
+    }
+
+    // pix取得 (引数は(x, y)順, data()の添字は[[y, x]]順)
     pub fn pix(&self, x: usize, y: usize) -> &T {
         &self.data[[y, x]]
     }
@@ -60,6 +112,22 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitiv
         &mut self.data[[y, x]]
     }
 
+    // (x, y) => 行優先の通し番号
+    pub fn xy_to_index(&self, x: usize, y: usize) -> usize {
+        y * self.width() + x
+    }
+
+    // 行優先の通し番号 => (x, y)
+    pub fn index_to_xy(&self, index: usize) -> (usize, usize) {
+        (index % self.width(), index / self.width())
+    }
+
+    // 行優先の通し番号でpix取得
+    pub fn pixel_at_index(&self, index: usize) -> &T {
+        let (x, y) = self.index_to_xy(index);
+        self.pix(x, y)
+    }
+
     // 形状取得
     pub fn shape(&self) -> &[usize] {
         self.data.shape()
@@ -75,29 +143,61 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitiv
         self.data.nrows()
     }
 
-    // bin画像書き込み
-    pub fn write_binimage(&self, path_raw_out: String) -> &Self {
-        let mut f_write = BufWriter::new(File::create(path_raw_out).unwrap());
+    // 画素数取得 (width * height)
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
 
-        let width = Self::width(self);
-        let height = Self::height(self);
-        let _ = f_write.write_u16::<byteorder::LittleEndian>(width as u16);
-        let _ = f_write.write_u16::<byteorder::LittleEndian>(height as u16);
-        for y in 0..height {
-            for x in 0..width {
-                let _ = f_write
-                    .write_u16::<byteorder::LittleEndian>(self.data[[y, x]].to_u16().unwrap());
-            }
+    // 画素なし判定
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    // 座標の範囲内判定
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width() && y < self.height()
+    }
+
+    // pix取得 (範囲外はOutOfBoundsエラー)
+    pub fn try_pix(&mut self, x: usize, y: usize) -> Result<&mut T, SensorIoError> {
+        if !self.contains(x, y) {
+            return Err(SensorIoError::OutOfBounds(format!(
+                "({}, {}) outside {}x{} image",
+                x,
+                y,
+                self.width(),
+                self.height()
+            )));
+        }
+        Ok(self.pix_mut(x, y))
+    }
+
+    // bin画像での1画素あたりのバイト数取得 (ファイルサイズ = 4 + width * height * pixel_byte_size() [+ CRC32(4)])
+    pub fn pixel_byte_size() -> usize {
+        binfmt::PIXEL_BYTE_SIZE
+    }
+
+    // bin画像書き込み (CRC32付き)
+    //   メタデータがあればサイドカー(<path>.json)に保存 (空の場合は既存のサイドカーに触れない)
+    pub fn write_binimage(&self, path_raw_out: String) -> Result<&Self, SensorIoError> {
+        BinWriter::new().write(self, &path_raw_out)?;
+        if !self.metadata.is_empty() {
+            sidecar::write_sidecar(&path_raw_out, &self.metadata)?;
         }
 
-        self
+        Ok(self)
+    }
+
+    // ストリームへのbin画像書き込み (CRC32付き)
+    pub fn write_to_stream<W: Write + ?Sized>(&self, writer: &mut W) -> Result<(), SensorIoError> {
+        BinWriter::new().write_to_stream(self, writer)
     }
 
     // bin画像読み込み
-    pub fn read_binimage(&mut self, path_raw_in: String) -> &Self {
-        *self = Self::new_from_binimage(path_raw_in);
+    pub fn read_binimage(&mut self, path_raw_in: String) -> Result<&Self, SensorIoError> {
+        *self = Self::new_from_binimage(path_raw_in)?;
 
-        self
+        Ok(self)
     }
 
     fn convert_vector1d_to_ndarray(
@@ -117,31 +217,35 @@ impl<T: num_traits::PrimInt + num_traits::FromPrimitive + num_traits::ToPrimitiv
     }
 
     fn convert_rgb_to_ndarray(img_in: &image::DynamicImage) -> ndarray::Array2<T> {
+        let source = RgbSource::new::<T>(img_in);
         ndarray::Array2::<T>::from_shape_fn(
             (img_in.height() as usize, img_in.width() as usize),
-            |(y, x)| -> T { Self::convert_rgb_to_bayer(img_in, x, y) },
+            |(y, x)| -> T { Self::convert_rgb_to_bayer(&source, x, y) },
         )
     }
 
-    fn convert_rgb_to_bayer(img_in: &image::DynamicImage, x: usize, y: usize) -> T {
-        let pix;
-        if x % 2 != y % 2 {
+    fn convert_rgb_to_bayer(source: &RgbSource, x: usize, y: usize) -> T {
+        let rgb = source.rgb_at(x, y);
+        let channel = if x % 2 != y % 2 {
             // G
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[1]).unwrap();
-        } else if x % 2 == 0 {
+            rgb[1]
+        } else if x.is_multiple_of(2) {
             // R
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[0]).unwrap();
+            rgb[0]
         } else {
             // B
-            pix = T::from(img_in.get_pixel(x as u32, y as u32)[2]).unwrap();
-        }
-        pix
+            rgb[2]
+        };
+        // Tに収まらない値は飽和
+        T::from_f64_saturating(channel)
     }
 }
 
 #[cfg(test)]
 mod test {
     use super::NDRaw;
+    use crate::bin_builder::BinWriter;
+    use crate::error::SensorIoError;
 
     #[test]
     fn test_new() {
@@ -160,6 +264,7 @@ mod test {
     }
 
     #[test]
+    #[allow(clippy::needless_range_loop)]
     fn test_new_from_vector() {
         println!("ndraw::test::test_new_from_vector()  {{");
 
@@ -201,7 +306,9 @@ mod test {
             raw_in.data()
         );
 
-        raw_in.write_binimage(String::from("write_ndraw.bin"));
+        raw_in
+            .write_binimage(String::from("write_ndraw.bin"))
+            .unwrap();
         println!("}}");
     }
 
@@ -209,7 +316,7 @@ mod test {
     fn test_new_from_binimage() {
         println!("ndraw::test::test_new_from_vector()  {{");
 
-        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin"));
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
         println!(
             "  [ndraw][test_new_from_binimage()] raw_in.width()  = {}",
             raw_in.width()
@@ -235,4 +342,288 @@ mod test {
 
         println!("}}");
     }
+
+    #[test]
+    fn test_pixel_at_index() {
+        println!("ndraw::test::test_pixel_at_index()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        println!(
+            "  [ndraw][test_pixel_at_index()] raw_in.pixel_at_index(5) = {}",
+            raw_in.pixel_at_index(5)
+        );
+        assert_eq!(raw_in.pix(1, 1), raw_in.pixel_at_index(5));
+        assert_eq!(5, raw_in.xy_to_index(1, 1));
+        assert_eq!((3, 2), raw_in.index_to_xy(11));
+        for i in 0..raw_in.width() * raw_in.height() {
+            let (x, y) = raw_in.index_to_xy(i);
+            assert_eq!(i, raw_in.xy_to_index(x, y));
+            assert_eq!(i as u16, *raw_in.pixel_at_index(i));
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_new_from_binimage_verified() {
+        println!("ndraw::test::test_new_from_binimage_verified()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9, 10, 11]];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_ndraw_crc_{}.bin", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+        // CRC32なしのファイルは検証付き読み込みでエラー
+        BinWriter::new()
+            .checksum(false)
+            .write(&raw_in, &path)
+            .unwrap();
+        let result = NDRaw::<u16>::new_from_binimage_verified(path_str.clone());
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        // write_binimageはCRC32付きで書き込む
+        raw_in.write_binimage(path_str.clone()).unwrap();
+        let raw_read = NDRaw::<u16>::new_from_binimage_verified(path_str.clone()).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+        let raw_plain =
+            NDRaw::<u16>::read_from_stream(&mut std::fs::File::open(&path).unwrap()).unwrap();
+        assert_eq!(raw_in.data(), raw_plain.data());
+
+        // 画素部の1バイトを反転
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[8 + 2 * 5] ^= 0xff;
+        std::fs::write(&path, &bytes).unwrap();
+        let result = NDRaw::<u16>::new_from_binimage_verified(path_str);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [ndraw][test_new_from_binimage_verified()] result = {:?}",
+            result.as_ref().err()
+        );
+        assert!(matches!(
+            result,
+            Err(SensorIoError::ChecksumMismatch { .. })
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_stream_roundtrip() {
+        println!("ndraw::test::test_stream_roundtrip()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        raw_in.write_to_stream(&mut cursor).unwrap();
+
+        // パス指定の書き込みとバイト単位で一致
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_ndraw_stream_{}.bin", std::process::id()));
+        raw_in
+            .write_binimage(path.to_str().unwrap().to_string())
+            .unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&bytes, cursor.get_ref());
+
+        cursor.set_position(0);
+        let raw_read = NDRaw::<u16>::read_from_stream(&mut cursor).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        // 途中で切れたストリーム
+        let mut truncated = std::io::Cursor::new(bytes[..bytes.len() / 2].to_vec());
+        let result = NDRaw::<u16>::read_from_stream(&mut truncated);
+        println!(
+            "  [ndraw][test_stream_roundtrip()] truncated = {:?}",
+            result.as_ref().err()
+        );
+        assert!(matches!(
+            result,
+            Err(SensorIoError::Io(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_signed_roundtrip() {
+        println!("ndraw::test::test_signed_roundtrip()  {{");
+
+        let vec2d: Vec<Vec<i16>> = vec![vec![-32768, -1, 0], vec![1, -300, 32767]];
+        let raw_in = NDRaw::<i16>::new_from_vector2d(&vec2d);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        raw_in.write_to_stream(&mut cursor).unwrap();
+        // wordはi16, 画素部はi16(LE)
+        assert_eq!(1, cursor.get_ref()[3]);
+        assert_eq!([0xff, 0xff], cursor.get_ref()[10..12]);
+
+        cursor.set_position(0);
+        let raw_read = NDRaw::<i16>::read_from_stream(&mut cursor).unwrap();
+        println!(
+            "  [ndraw][test_signed_roundtrip()] raw_read = \n{}",
+            raw_read.data()
+        );
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        // i32は32bit符号付きワードとして書き込み, i32として読み戻せる
+        let raw_i32 = NDRaw::<i32>::new_from_vector2d(&[
+            vec![i32::MIN, -40000, -1],
+            vec![0, 40000, i32::MAX],
+        ]);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        raw_i32.write_to_stream(&mut cursor).unwrap();
+        // wordはi32, 画素部は4バイト/画素
+        assert_eq!(2, cursor.get_ref()[3]);
+        assert_eq!(8 + 6 * 4 + 4, cursor.get_ref().len());
+        cursor.set_position(0);
+        let raw_read = NDRaw::<i32>::read_from_stream(&mut cursor).unwrap();
+        println!(
+            "  [ndraw][test_signed_roundtrip()] raw_i32 = \n{}",
+            raw_read.data()
+        );
+        assert_eq!(raw_i32.data(), raw_read.data());
+
+        // 読み込み側の型に収まらない値はエラー
+        cursor.set_position(0);
+        let result = NDRaw::<i16>::read_from_stream(&mut cursor);
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        // i32に収まらないi64の値はエラー
+        let raw_i64 = NDRaw::<i64>::new_from_vector2d(&[vec![0, 1 << 40]]);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        let result = raw_i64.write_to_stream(&mut cursor);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_pixel_byte_size() {
+        println!("ndraw::test::test_pixel_byte_size()  {{");
+
+        assert_eq!(2, NDRaw::<u16>::pixel_byte_size());
+        assert_eq!(2, NDRaw::<u8>::pixel_byte_size());
+
+        // ファイルサイズ = ヘッダ(8) + 画素部 + CRC32(4)
+        let raw_in = NDRaw::<u8>::new(5, 3);
+        let mut cursor = std::io::Cursor::new(Vec::<u8>::new());
+        raw_in.write_to_stream(&mut cursor).unwrap();
+        assert_eq!(
+            8 + 5 * 3 * NDRaw::<u8>::pixel_byte_size() + 4,
+            cursor.get_ref().len()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_contains() {
+        println!("ndraw::test::test_contains()  {{");
+
+        let raw = NDRaw::<u16>::new(4, 3);
+        assert!(raw.contains(0, 0));
+        assert!(raw.contains(3, 2));
+        assert!(!raw.contains(4, 2));
+        assert!(!raw.contains(3, 3));
+        assert!(!raw.contains(4, 3));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_try_pix() {
+        println!("ndraw::test::test_try_pix()  {{");
+
+        let mut raw = NDRaw::<u16>::new(4, 3);
+        *raw.try_pix(3, 2).unwrap() = 7;
+        assert_eq!(7, *raw.pix(3, 2));
+        assert!(matches!(
+            raw.try_pix(4, 0),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+        assert!(matches!(
+            raw.try_pix(0, 3),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_from_ndarray() {
+        println!("ndraw::test::test_from_ndarray()  {{");
+
+        // 3行4列 (height 3, width 4)
+        let arr = ndarray::Array2::<u16>::from_shape_fn((3, 4), |(y, x)| (y * 10 + x) as u16);
+        let ptr = arr.as_ptr();
+        let raw = NDRaw::<u16>::from_ndarray(arr);
+        assert_eq!((4, 3), (raw.width(), raw.height()));
+        assert_eq!(21, *raw.pix(1, 2));
+        // バッファはコピーされない
+        assert_eq!(ptr, raw.data().as_ptr());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_new_from_rgbimage_16bit() {
+        println!("ndraw::test::test_new_from_rgbimage_16bit()  {{");
+
+        // 16bit RGB画像 (R=300, G=40, B=65535)
+        let img = image::ImageBuffer::from_pixel(4, 2, image::Rgb([300u16, 40, 65535]));
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_ndraw_rgb16_{}.png", std::process::id()));
+        img.save(&path).unwrap();
+        let path_str = path.to_str().unwrap().to_string();
+
+        // 16bit値を表せない型へは8bitに縮小して読み込む (65535 => 255, 300 => 1)
+        let raw_u8 = NDRaw::<u8>::new_from_rgbimage(path_str.clone());
+        let raw_u16 = NDRaw::<u16>::new_from_rgbimage(path_str);
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [ndraw][test_new_from_rgbimage_16bit()] raw_u8 = {}",
+            raw_u8.data()
+        );
+        assert_eq!(
+            (1, 0, 255),
+            (*raw_u8.pix(0, 0), *raw_u8.pix(1, 0), *raw_u8.pix(1, 1))
+        );
+        assert_eq!(
+            (300, 40, 65535),
+            (*raw_u16.pix(0, 0), *raw_u16.pix(1, 0), *raw_u16.pix(1, 1))
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_from_sparse() {
+        println!("ndraw::test::test_from_sparse()  {{");
+
+        let placements = vec![(0, 0, 5), (2, 1, 7), (1, 2, 9)];
+        let raw = NDRaw::<u16>::from_sparse(3, 3, placements.into_iter(), 1).unwrap();
+        println!("  [ndraw][test_from_sparse()] raw = \n{}", raw.data());
+        assert_eq!((3, 3), (raw.width(), raw.height()));
+        assert_eq!((5, 7, 9), (*raw.pix(0, 0), *raw.pix(2, 1), *raw.pix(1, 2)));
+        assert_eq!((1, 1), (*raw.pix(1, 1), *raw.pix(2, 2)));
+
+        let result = NDRaw::<u16>::from_sparse(3, 3, [(3, 0, 1)].into_iter(), 0);
+        assert!(matches!(result, Err(SensorIoError::OutOfBounds(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_len() {
+        println!("ndraw::test::test_len()  {{");
+
+        let empty = NDRaw::<u16>::new(0, 0);
+        assert!(empty.is_empty());
+        assert_eq!(0, empty.len());
+
+        let raw = NDRaw::<u16>::new(4, 3);
+        assert!(!raw.is_empty());
+        assert_eq!(12, raw.len());
+
+        println!("}}");
+    }
 }
@@ -0,0 +1,210 @@
+use crate::config::Metadata;
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use std::io;
+use std::path::Path;
+
+// Metadataを格納するグローバル属性名
+const ATTR_SERIAL_NUMBER: &str = "serial_number";
+const ATTR_CALIBRATION_DATE: &str = "calibration_date";
+
+// NetCDF入出力 (2次元変数, 次元名は("y", "x"))
+impl<T: PixelType + netcdf::NcTypeDescriptor> NDRaw<T> {
+    // NetCDF書き込み (width/heightはグローバル属性として記録)
+    pub fn write_netcdf(
+        &self,
+        path: impl AsRef<Path>,
+        variable_name: &str,
+    ) -> Result<(), SensorIoError> {
+        self.write_netcdf_impl(path.as_ref(), variable_name, None)
+    }
+
+    // NetCDF書き込み (Metadataの各フィールドもグローバル属性として記録, Noneのフィールドは省略)
+    pub fn write_netcdf_with_metadata(
+        &self,
+        path: impl AsRef<Path>,
+        variable_name: &str,
+        metadata: &Metadata,
+    ) -> Result<(), SensorIoError> {
+        self.write_netcdf_impl(path.as_ref(), variable_name, Some(metadata))
+    }
+
+    fn write_netcdf_impl(
+        &self,
+        path: &Path,
+        variable_name: &str,
+        metadata: Option<&Metadata>,
+    ) -> Result<(), SensorIoError> {
+        let mut file = netcdf::create(path).map_err(to_io_error)?;
+        file.add_dimension("y", self.height())
+            .map_err(to_io_error)?;
+        file.add_dimension("x", self.width()).map_err(to_io_error)?;
+        file.add_attribute("width", self.width() as u64)
+            .map_err(to_io_error)?;
+        file.add_attribute("height", self.height() as u64)
+            .map_err(to_io_error)?;
+        if let Some(metadata) = metadata {
+            if let Some(serial_number) = &metadata.serial_number {
+                file.add_attribute(ATTR_SERIAL_NUMBER, serial_number.as_str())
+                    .map_err(to_io_error)?;
+            }
+            if let Some(calibration_date) = &metadata.calibration_date {
+                file.add_attribute(ATTR_CALIBRATION_DATE, calibration_date.as_str())
+                    .map_err(to_io_error)?;
+            }
+        }
+
+        let values: Vec<T> = self.data.iter().copied().collect();
+        let mut var = file
+            .add_variable::<T>(variable_name, &["y", "x"])
+            .map_err(to_io_error)?;
+        var.put_values(&values, ..).map_err(to_io_error)?;
+
+        Ok(())
+    }
+
+    // NetCDF変換コンストラクタ
+    pub fn new_from_netcdf(
+        path: impl AsRef<Path>,
+        variable_name: &str,
+    ) -> Result<Self, SensorIoError> {
+        let file = netcdf::open(path.as_ref()).map_err(to_io_error)?;
+        let var = file.variable(variable_name).ok_or_else(|| {
+            SensorIoError::Io(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("variable `{}` not found", variable_name),
+            ))
+        })?;
+        let dims = var.dimensions();
+        if dims.len() != 2 {
+            return Err(SensorIoError::Parse(format!(
+                "variable `{}` has {} dimensions, expected 2",
+                variable_name,
+                dims.len()
+            )));
+        }
+        let (height, width) = (dims[0].len(), dims[1].len());
+        let values: Vec<T> = var.get_values(..).map_err(to_io_error)?;
+        let data = ndarray::Array2::from_shape_vec((height, width), values)
+            .map_err(|e| SensorIoError::Parse(e.to_string()))?;
+
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+// NetCDFのMetadata読み込み (属性がないフィールドはNone)
+pub fn read_netcdf_metadata(path: impl AsRef<Path>) -> Result<Metadata, SensorIoError> {
+    let file = netcdf::open(path.as_ref()).map_err(to_io_error)?;
+    let read_string = |name: &str| -> Result<Option<String>, SensorIoError> {
+        match file.attribute(name) {
+            Some(attr) => {
+                let value = attr.value().map_err(to_io_error)?;
+                let value = String::try_from(value)
+                    .map_err(|e| SensorIoError::Parse(format!("attribute `{}`: {}", name, e)))?;
+                Ok(Some(value))
+            }
+            None => Ok(None),
+        }
+    };
+    Ok(Metadata {
+        serial_number: read_string(ATTR_SERIAL_NUMBER)?,
+        calibration_date: read_string(ATTR_CALIBRATION_DATE)?,
+    })
+}
+
+fn to_io_error(e: netcdf::Error) -> SensorIoError {
+    SensorIoError::Io(io::Error::other(e.to_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_netcdf_metadata;
+    use crate::config::Metadata;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sensor_io_nc_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn test_netcdf_roundtrip_u16() {
+        println!("netcdf_io::test::test_netcdf_roundtrip_u16()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let path = temp_path("u16.nc");
+        raw_in.write_netcdf(&path, "raw").unwrap();
+        let raw_read = NDRaw::<u16>::new_from_netcdf(&path, "raw").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [netcdf_io][test_netcdf_roundtrip_u16()] raw_read.data() = \n{}",
+            raw_read.data()
+        );
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_netcdf_roundtrip_f32() {
+        println!("netcdf_io::test::test_netcdf_roundtrip_f32()  {{");
+
+        let raw_in =
+            NDRaw::<f32>::new_from_vector2d(&[vec![0.5, -1.25, 3.0], vec![1e-3, 2.0, 7.75]]);
+        let path = temp_path("f32.nc");
+        raw_in.write_netcdf(&path, "signal").unwrap();
+        let raw_read = NDRaw::<f32>::new_from_netcdf(&path, "signal").unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(raw_in.data(), raw_read.data());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_netcdf_missing_variable() {
+        println!("netcdf_io::test::test_netcdf_missing_variable()  {{");
+
+        let raw_in = NDRaw::<u16>::new(4, 3);
+        let path = temp_path("missing.nc");
+        raw_in.write_netcdf(&path, "raw").unwrap();
+        let result = NDRaw::<u16>::new_from_netcdf(&path, "nothing");
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(SensorIoError::Io(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_netcdf_metadata_roundtrip() {
+        println!("netcdf_io::test::test_netcdf_metadata_roundtrip()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&[vec![1, 2, 3], vec![4, 5, 6]]);
+        let metadata = Metadata {
+            serial_number: Some(String::from("SN-0042")),
+            calibration_date: Some(String::from("2024-05-01")),
+        };
+        let path = temp_path("metadata.nc");
+        raw_in
+            .write_netcdf_with_metadata(&path, "raw", &metadata)
+            .unwrap();
+        let raw_read = NDRaw::<u16>::new_from_netcdf(&path, "raw").unwrap();
+        let metadata_read = read_netcdf_metadata(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        println!(
+            "  [netcdf_io][test_netcdf_metadata_roundtrip()] metadata_read = {:?}",
+            metadata_read
+        );
+        assert_eq!(raw_in.data(), raw_read.data());
+        assert_eq!(metadata, metadata_read);
+
+        // Metadataなしで書き込んだ場合は全フィールドNone
+        let path = temp_path("no_metadata.nc");
+        raw_in.write_netcdf(&path, "raw").unwrap();
+        let metadata_read = read_netcdf_metadata(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(Metadata::default(), metadata_read);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,212 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 位相差AF(PDAF)画素の配置
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PdafMask {
+    // 座標(x, y)の一覧
+    Explicit(Vec<(usize, usize)>),
+    // 繰り返し配置 (period_x * period_y のブロック毎にoffsetsの位置)
+    Periodic {
+        period_x: usize,
+        period_y: usize,
+        offsets: Vec<(usize, usize)>,
+    },
+}
+
+impl PdafMask {
+    // PDAF画素判定
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        match self {
+            PdafMask::Explicit(sites) => sites.contains(&(x, y)),
+            PdafMask::Periodic {
+                period_x,
+                period_y,
+                offsets,
+            } => *period_x > 0 && *period_y > 0 && offsets.contains(&(x % period_x, y % period_y)),
+        }
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // PDAF画素マスク ([[y, x]], PDAF画素がtrue, 画像外の座標は無視)
+    pub fn mask_pdaf(&self, mask: &PdafMask) -> ndarray::Array2<bool> {
+        let (width, height) = (self.width(), self.height());
+        match mask {
+            PdafMask::Explicit(sites) => {
+                let mut out = ndarray::Array2::from_elem((height, width), false);
+                for &(x, y) in sites {
+                    if x < width && y < height {
+                        out[[y, x]] = true;
+                    }
+                }
+                out
+            }
+            PdafMask::Periodic { .. } => {
+                ndarray::Array2::from_shape_fn((height, width), |(y, x)| mask.contains(x, y))
+            }
+        }
+    }
+
+    // PDAF画素の補間
+    //   同色の上下左右斜め2画素先 (G画素は斜め隣接のGも含む) のうち, PDAF画素でない画素の平均
+    //   有効な近傍がない場合はそのまま
+    pub fn interpolate_pdaf(&mut self, mask: &PdafMask, pattern: BayerPattern) {
+        const SAME_CHANNEL: [(isize, isize); 8] = [
+            (-2, -2),
+            (0, -2),
+            (2, -2),
+            (-2, 0),
+            (2, 0),
+            (-2, 2),
+            (0, 2),
+            (2, 2),
+        ];
+        const GREEN_DIAGONAL: [(isize, isize); 4] = [(-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+        let pdaf = self.mask_pdaf(mask);
+        let (width, height) = (self.width() as isize, self.height() as isize);
+        let source = self.data.clone();
+        for ((y, x), is_pdaf) in pdaf.indexed_iter() {
+            if !*is_pdaf {
+                continue;
+            }
+            let green = matches!(
+                pattern.channel_at(x, y),
+                BayerChannel::Gr | BayerChannel::Gb
+            );
+            let offsets = SAME_CHANNEL
+                .iter()
+                .chain(GREEN_DIAGONAL.iter().filter(|_| green));
+            let (mut sum, mut count) = (0.0, 0);
+            for &(dx, dy) in offsets {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if nx < 0 || ny < 0 || nx >= width || ny >= height {
+                    continue;
+                }
+                let (nx, ny) = (nx as usize, ny as usize);
+                if !pdaf[[ny, nx]] {
+                    sum += source[[ny, nx]].to_f64().unwrap();
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                self.data[[y, x]] = T::from_f64_saturating(sum / count as f64);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PdafMask;
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_interpolate_pdaf() {
+        println!("pdaf::test::test_interpolate_pdaf()  {{");
+
+        // 8x8ブロック毎に(3, 2)(Gr)と(4, 5)(Gb)がPDAF画素 (周囲より暗い)
+        let mask = PdafMask::Periodic {
+            period_x: 8,
+            period_y: 8,
+            offsets: vec![(3, 2), (4, 5)],
+        };
+        let (width, height) = (24, 16);
+        let mut raw = NDRaw::<u16>::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                *raw.pix_mut(x, y) = if mask.contains(x, y) { 100 } else { 1000 };
+            }
+        }
+        let pdaf = raw.mask_pdaf(&mask);
+        assert_eq!(12, pdaf.iter().filter(|v| **v).count());
+        assert!(pdaf[[2, 11]] && pdaf[[13, 20]]);
+
+        let original = raw.clone();
+        raw.interpolate_pdaf(&mask, BayerPattern::Rggb);
+        println!(
+            "  [pdaf][test_interpolate_pdaf()] (3, 2): {} => {}",
+            original.pix(3, 2),
+            raw.pix(3, 2)
+        );
+        for y in 0..height {
+            for x in 0..width {
+                if mask.contains(x, y) {
+                    // PDAF画素は周囲の通常画素の値に
+                    assert_eq!(1000, *raw.pix(x, y));
+                } else {
+                    // 通常画素は変わらない
+                    assert_eq!(*original.pix(x, y), *raw.pix(x, y));
+                }
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_interpolate_pdaf_skips_pdaf_neighbors() {
+        println!("pdaf::test::test_interpolate_pdaf_skips_pdaf_neighbors()  {{");
+
+        // 隣り合う同色PDAF画素 (2, 2)と(4, 2): 互いの値は補間に使わない
+        let mask = PdafMask::Explicit(vec![(2, 2), (4, 2), (100, 100)]);
+        let mut raw = NDRaw::<u16>::new_from_vector2d(&vec![vec![500; 7]; 5]);
+        *raw.pix_mut(2, 2) = 0;
+        *raw.pix_mut(4, 2) = 0;
+        raw.interpolate_pdaf(&mask, BayerPattern::Rggb);
+        assert_eq!(500, *raw.pix(2, 2));
+        assert_eq!(500, *raw.pix(4, 2));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_statistics_excluding_pdaf() {
+        println!("pdaf::test::test_statistics_excluding_pdaf()  {{");
+
+        let mask = PdafMask::Periodic {
+            period_x: 4,
+            period_y: 4,
+            offsets: vec![(1, 0)],
+        };
+        let mut raw = NDRaw::<u16>::new_from_vector2d(&vec![vec![200; 8]; 8]);
+        for y in 0..8 {
+            for x in 0..8 {
+                if mask.contains(x, y) {
+                    *raw.pix_mut(x, y) = 0;
+                }
+            }
+        }
+        let biased = raw.compute_statistics();
+        let stats = raw
+            .compute_statistics_masked(Some(&raw.mask_pdaf(&mask)))
+            .unwrap();
+        println!(
+            "  [pdaf][test_statistics_excluding_pdaf()] mean = {} (all pixels {})",
+            stats.mean, biased.mean
+        );
+        assert!(biased.mean < 200.0);
+        assert_eq!((60, 200.0, 0.0), (stats.count, stats.mean, stats.std_dev));
+        assert_eq!(biased, raw.compute_statistics_masked(None).unwrap());
+
+        // NARawでも同じマスクで除外できる
+        let na =
+            NARaw::<u16>::from_dmatrix(nalgebra::DMatrix::from_fn(8, 8, |y, x| *raw.pix(x, y)));
+        let na_stats = na
+            .compute_statistics_masked(Some(&raw.mask_pdaf(&mask)))
+            .unwrap();
+        assert_eq!(stats, na_stats);
+        assert_eq!(biased, na.compute_statistics());
+
+        let result =
+            raw.compute_statistics_masked(Some(&ndarray::Array2::from_elem((4, 8), false)));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,84 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // ペデスタル加算 (white_levelで飽和) => 飽和した画素数
+    pub fn add_pedestal(&mut self, value: T, white_level: T) -> usize {
+        let (value, white_level) = (value.to_f64().unwrap(), white_level.to_f64().unwrap());
+        let mut clipped = 0;
+        for pix in self.data.iter_mut() {
+            let v = pix.to_f64().unwrap() + value;
+            if v > white_level {
+                clipped += 1;
+            }
+            *pix = T::from_f64_saturating(v.min(white_level));
+        }
+        clipped
+    }
+
+    // ペデスタル減算 (0で下限クリップ) => クリップした画素数
+    pub fn remove_pedestal(&mut self, value: T) -> usize {
+        let value = value.to_f64().unwrap();
+        let mut clipped = 0;
+        for pix in self.data.iter_mut() {
+            let v = pix.to_f64().unwrap() - value;
+            if v < 0.0 {
+                clipped += 1;
+            }
+            *pix = T::from_f64_saturating(v.max(0.0));
+        }
+        clipped
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_add_pedestal() {
+        println!("pedestal::test::test_add_pedestal()  {{");
+
+        let mut raw = NDRaw::<u16>::new_from_vector2d(&[vec![0, 100, 4031, 4032, 4095]]);
+        let clipped = raw.add_pedestal(64, 4095);
+        println!(
+            "  [pedestal][test_add_pedestal()] raw = {}, clipped = {}",
+            raw.data(),
+            clipped
+        );
+        // 4031 + 64 = 4095はちょうど白レベルで飽和なし
+        assert_eq!(2, clipped);
+        assert_eq!(&[64, 164, 4095, 4095, 4095], raw.data().as_slice().unwrap());
+
+        // 型の最大値を超える場合も桁あふれしない
+        let mut raw = NDRaw::<u8>::new_from_vector2d(&[vec![200, 250]]);
+        assert_eq!(1, raw.add_pedestal(16, 255));
+        assert_eq!(&[216, 255], raw.data().as_slice().unwrap());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_remove_pedestal() {
+        println!("pedestal::test::test_remove_pedestal()  {{");
+
+        let mut raw = NDRaw::<u16>::new_from_vector2d(&[vec![0, 63, 64, 65, 4095]]);
+        let clipped = raw.remove_pedestal(64);
+        println!(
+            "  [pedestal][test_remove_pedestal()] raw = {}, clipped = {}",
+            raw.data(),
+            clipped
+        );
+        assert_eq!(2, clipped);
+        assert_eq!(&[0, 0, 0, 1, 4031], raw.data().as_slice().unwrap());
+
+        // 加算・減算の往復 (クリップがなければ元に戻る)
+        let raw_in = NDRaw::<u16>::new_from_binimage(String::from("testdata/test.bin")).unwrap();
+        let mut raw = raw_in.clone();
+        assert_eq!(0, raw.add_pedestal(64, u16::MAX));
+        assert_eq!(0, raw.remove_pedestal(64));
+        assert_eq!(raw_in.data(), raw.data());
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,261 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// パイプラインの処理ステップ
+pub trait ProcessingStep<In: PixelType, Out: PixelType> {
+    fn apply(&self, img: NDRaw<In>) -> Result<NDRaw<Out>, SensorIoError>;
+    fn name(&self) -> &str;
+}
+
+// 複数ステップの処理パイプライン (同じ型のステップを順に適用し, 最後に型を変換するステップを適用)
+pub struct PixelPipeline<T: PixelType, U: PixelType> {
+    steps: Vec<Box<dyn ProcessingStep<T, T>>>,
+    final_step: Box<dyn ProcessingStep<T, U>>,
+}
+
+impl<T: PixelType + 'static> PixelPipeline<T, T> {
+    // 空のパイプライン (最後のステップは恒等変換)
+    pub fn new() -> Self {
+        PixelPipeline {
+            steps: Vec::new(),
+            final_step: Box::new(IdentityStep),
+        }
+    }
+
+    // 最後のステップ設定 (出力型を変える)
+    pub fn with_final_step<U: PixelType>(
+        self,
+        step: impl ProcessingStep<T, U> + 'static,
+    ) -> PixelPipeline<T, U> {
+        PixelPipeline {
+            steps: self.steps,
+            final_step: Box::new(step),
+        }
+    }
+}
+
+impl<T: PixelType + 'static> Default for PixelPipeline<T, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: PixelType, U: PixelType> PixelPipeline<T, U> {
+    // ステップ追加 (最後のステップの前に追加)
+    pub fn add_step(mut self, step: impl ProcessingStep<T, T> + 'static) -> Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    // ステップ名一覧 (適用順, 最後のステップを含む)
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps
+            .iter()
+            .map(|step| step.name())
+            .chain(std::iter::once(self.final_step.name()))
+            .collect()
+    }
+
+    // 実行 (エラーはステップ名を付けて返す)
+    pub fn run(&self, img: NDRaw<T>) -> Result<NDRaw<U>, SensorIoError> {
+        let mut img = img;
+        for step in &self.steps {
+            img = step
+                .apply(img)
+                .map_err(|e| with_step_name(step.name(), e))?;
+        }
+        self.final_step
+            .apply(img)
+            .map_err(|e| with_step_name(self.final_step.name(), e))
+    }
+}
+
+fn with_step_name(name: &str, e: SensorIoError) -> SensorIoError {
+    match e {
+        SensorIoError::ShapeMismatch(msg) => {
+            SensorIoError::ShapeMismatch(format!("{}: {}", name, msg))
+        }
+        SensorIoError::InvalidArgument(msg) => {
+            SensorIoError::InvalidArgument(format!("{}: {}", name, msg))
+        }
+        e => e,
+    }
+}
+
+// 恒等変換
+struct IdentityStep;
+
+impl<T: PixelType> ProcessingStep<T, T> for IdentityStep {
+    fn apply(&self, img: NDRaw<T>) -> Result<NDRaw<T>, SensorIoError> {
+        Ok(img)
+    }
+
+    fn name(&self) -> &str {
+        "identity"
+    }
+}
+
+// 二値化 (threshold超を1, それ以外を0)
+pub struct ThresholdStep<T: PixelType> {
+    pub threshold: T,
+}
+
+impl<T: PixelType> ProcessingStep<T, T> for ThresholdStep<T> {
+    fn apply(&self, img: NDRaw<T>) -> Result<NDRaw<T>, SensorIoError> {
+        let data = img.data.mapv(|v| {
+            if v > self.threshold {
+                T::one()
+            } else {
+                T::zero()
+            }
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    fn name(&self) -> &str {
+        "threshold"
+    }
+}
+
+// ガウシアンぼかし (半径ceil(3σ)の分離型カーネル, 範囲外は端の画素で補完)
+pub struct GaussianBlurStep {
+    pub sigma: f64,
+}
+
+impl<T: PixelType> ProcessingStep<T, T> for GaussianBlurStep {
+    fn apply(&self, img: NDRaw<T>) -> Result<NDRaw<T>, SensorIoError> {
+        if self.sigma.is_nan() || self.sigma <= 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "sigma must be positive: {}",
+                self.sigma
+            )));
+        }
+        let radius = (3.0 * self.sigma).ceil() as isize;
+        let mut kernel: Vec<f64> = (-radius..=radius)
+            .map(|i| (-(i * i) as f64 / (2.0 * self.sigma * self.sigma)).exp())
+            .collect();
+        let sum: f64 = kernel.iter().sum();
+        kernel.iter_mut().for_each(|w| *w /= sum);
+        Ok(img.convolve_rows(&kernel).convolve_cols(&kernel))
+    }
+
+    fn name(&self) -> &str {
+        "gaussian_blur"
+    }
+}
+
+// 最小値0・最大値1への正規化 (一様な画像は全て0)
+pub struct NormalizeStep;
+
+impl<T: PixelType> ProcessingStep<T, f32> for NormalizeStep {
+    fn apply(&self, img: NDRaw<T>) -> Result<NDRaw<f32>, SensorIoError> {
+        let stats = img.compute_statistics();
+        let range = stats.max - stats.min;
+        let data = img.data.mapv(|v| {
+            if range > 0.0 {
+                ((v.to_f64().unwrap() - stats.min) / range) as f32
+            } else {
+                0.0
+            }
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    fn name(&self) -> &str {
+        "normalize"
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GaussianBlurStep, NormalizeStep, PixelPipeline, ProcessingStep, ThresholdStep};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 全画素に加算するステップ
+    struct AddStep(u16);
+
+    impl ProcessingStep<u16, u16> for AddStep {
+        fn apply(&self, img: NDRaw<u16>) -> Result<NDRaw<u16>, SensorIoError> {
+            Ok(NDRaw::from_ndarray(img.data().mapv(|v| v + self.0)))
+        }
+
+        fn name(&self) -> &str {
+            "add"
+        }
+    }
+
+    #[test]
+    fn test_pixel_pipeline() {
+        println!("pipeline::test::test_pixel_pipeline()  {{");
+
+        let img = NDRaw::<u16>::new_from_vector2d(&[vec![10, 20, 30, 40]]);
+
+        // 加算 => 二値化 の順 (逆順なら全て1)
+        let pipeline = PixelPipeline::new()
+            .add_step(AddStep(5))
+            .add_step(ThresholdStep { threshold: 30 });
+        println!(
+            "  [pipeline][test_pixel_pipeline()] steps = {:?}",
+            pipeline.step_names()
+        );
+        assert_eq!(vec!["add", "threshold", "identity"], pipeline.step_names());
+        let out = pipeline.run(img.clone()).unwrap();
+        assert_eq!(&[0, 0, 1, 1], out.data().as_slice().unwrap());
+
+        let reversed = PixelPipeline::new()
+            .add_step(ThresholdStep { threshold: 30 })
+            .add_step(AddStep(5));
+        assert_eq!(
+            &[5, 5, 5, 6],
+            reversed
+                .run(img.clone())
+                .unwrap()
+                .data()
+                .as_slice()
+                .unwrap()
+        );
+
+        // 最後に型を変えるステップ
+        let pipeline = PixelPipeline::new()
+            .add_step(AddStep(5))
+            .with_final_step(NormalizeStep);
+        let out = pipeline.run(img.clone()).unwrap();
+        assert_eq!(
+            &[0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0],
+            out.data().as_slice().unwrap()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_gaussian_blur_step() {
+        println!("pipeline::test::test_gaussian_blur_step()  {{");
+
+        // インパルスのぼかし: 総和はほぼ保存, 中心が最大
+        let mut img = NDRaw::<f32>::new(15, 15);
+        *img.pix_mut(7, 7) = 1000.0;
+        let pipeline = PixelPipeline::new().add_step(GaussianBlurStep { sigma: 1.5 });
+        let out = pipeline.run(img).unwrap();
+        let sum: f32 = out.data().iter().sum();
+        println!(
+            "  [pipeline][test_gaussian_blur_step()] sum = {}, center = {}",
+            sum,
+            out.pix(7, 7)
+        );
+        assert!((sum - 1000.0).abs() < 1.0);
+        assert!(out.data().iter().all(|v| v <= out.pix(7, 7)));
+        assert_eq!(out.pix(6, 7), out.pix(8, 7));
+
+        // 不正なσはステップ名付きのエラー
+        let pipeline = PixelPipeline::<f32, f32>::new().add_step(GaussianBlurStep { sigma: 0.0 });
+        match pipeline.run(NDRaw::new(4, 4)) {
+            Err(SensorIoError::InvalidArgument(msg)) => assert!(msg.starts_with("gaussian_blur")),
+            _ => panic!("expected InvalidArgument"),
+        }
+
+        println!("}}");
+    }
+}
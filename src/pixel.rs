@@ -0,0 +1,174 @@
+use nalgebra;
+use num_traits;
+
+// bin画像の画素ワードの種類 (ヘッダに記録し, 読み込み時はヘッダの種類で解釈する)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinWordKind {
+    // 0..=65535 (ビット深度8以下は1バイト)
+    Unsigned16,
+    // i16の2の補数表現 (ビット深度8以下は1バイト)
+    Signed16,
+    // i32 (4バイト, ビット深度は適用しない)
+    Signed32,
+}
+
+// 画素型 (整数型・浮動小数点型共通)
+pub trait PixelType:
+    num_traits::Num
+    + num_traits::NumCast
+    + num_traits::FromPrimitive
+    + num_traits::ToPrimitive
+    + num_traits::Bounded
+    + PartialOrd
+    + Copy
+    + Send
+    + Sync
+    + std::fmt::Display
+    + nalgebra::Scalar
+{
+    // f64からの飽和変換 (整数型は四捨五入・範囲外はクランプ)
+    fn from_f64_saturating(v: f64) -> Self;
+
+    // bin画像の16bit画素値への変換 (i8/i16はi16の2の補数表現, それ以外は0..=65535, 範囲外はNone)
+    fn to_bin_word(self) -> Option<u16>;
+
+    // bin画像の16bit画素値からの変換 (i8/i16はi16として解釈, それ以外はu16として解釈, 範囲外はNone)
+    fn from_bin_word(v: u16) -> Option<Self>;
+
+    // bin画像に書き込む画素ワードの種類 (i8/i16はSigned16, i32/i64はSigned32, それ以外はUnsigned16)
+    const BIN_WORD_KIND: BinWordKind;
+
+    // bin画像の32bit画素値への変換 (範囲外はNone)
+    fn to_bin_word32(self) -> Option<i32> {
+        num_traits::ToPrimitive::to_i32(&self)
+    }
+
+    // bin画像の32bit画素値からの変換 (範囲外はNone)
+    fn from_bin_word32(v: i32) -> Option<Self> {
+        num_traits::FromPrimitive::from_i32(v)
+    }
+}
+
+macro_rules! impl_pixel_type_int {
+    ($kind:ident; $($t:ty),*) => {
+        $(
+            impl PixelType for $t {
+                const BIN_WORD_KIND: BinWordKind = BinWordKind::$kind;
+
+                fn from_f64_saturating(v: f64) -> Self {
+                    // `as` はNaNを0、範囲外を最小値/最大値に丸める
+                    v.round() as $t
+                }
+
+                fn to_bin_word(self) -> Option<u16> {
+                    num_traits::ToPrimitive::to_u16(&self)
+                }
+
+                fn from_bin_word(v: u16) -> Option<Self> {
+                    num_traits::FromPrimitive::from_u16(v)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_pixel_type_signed {
+    ($($t:ty),*) => {
+        $(
+            impl PixelType for $t {
+                const BIN_WORD_KIND: BinWordKind = BinWordKind::Signed16;
+
+                fn from_f64_saturating(v: f64) -> Self {
+                    // `as` はNaNを0、範囲外を最小値/最大値に丸める
+                    v.round() as $t
+                }
+
+                fn to_bin_word(self) -> Option<u16> {
+                    num_traits::ToPrimitive::to_i16(&self).map(|v| v as u16)
+                }
+
+                fn from_bin_word(v: u16) -> Option<Self> {
+                    num_traits::FromPrimitive::from_i16(v as i16)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_pixel_type_float {
+    ($($t:ty),*) => {
+        $(
+            impl PixelType for $t {
+                const BIN_WORD_KIND: BinWordKind = BinWordKind::Unsigned16;
+
+                fn from_f64_saturating(v: f64) -> Self {
+                    v as $t
+                }
+
+                fn to_bin_word(self) -> Option<u16> {
+                    num_traits::ToPrimitive::to_u16(&self)
+                }
+
+                fn from_bin_word(v: u16) -> Option<Self> {
+                    num_traits::FromPrimitive::from_u16(v)
+                }
+            }
+        )*
+    };
+}
+
+// i32/i64の16bit画素値はu16として扱う (i16として解釈すると32768以上が負値に化ける)
+//   bin画像には32bit符号付きで書き込む
+impl_pixel_type_int!(Unsigned16; u8, u16, u32, u64);
+impl_pixel_type_int!(Signed32; i32, i64);
+impl_pixel_type_signed!(i8, i16);
+impl_pixel_type_float!(f32, f64);
+
+#[cfg(test)]
+mod test {
+    use super::{BinWordKind, PixelType};
+
+    #[test]
+    fn test_from_f64_saturating() {
+        println!("pixel::test::test_from_f64_saturating()  {{");
+
+        assert_eq!(3u16, u16::from_f64_saturating(2.5));
+        assert_eq!(0u16, u16::from_f64_saturating(-10.0));
+        assert_eq!(u16::MAX, u16::from_f64_saturating(1.0e9));
+        assert_eq!(0u8, u8::from_f64_saturating(f64::NAN));
+        assert_eq!(-3i16, i16::from_f64_saturating(-2.6));
+        assert_eq!(0.25f32, f32::from_f64_saturating(0.25));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_bin_word() {
+        println!("pixel::test::test_bin_word()  {{");
+
+        assert_eq!(Some(0xfffe), (-2i16).to_bin_word());
+        assert_eq!(Some(-2i16), i16::from_bin_word(0xfffe));
+        assert_eq!(Some(-2i8), i8::from_bin_word(0xfffe));
+        assert_eq!(None, i8::from_bin_word(0x0100));
+
+        // i32/i64はu16の範囲をそのまま保持する
+        assert_eq!(Some(40000), 40000i32.to_bin_word());
+        assert_eq!(Some(40000i32), i32::from_bin_word(40000));
+        assert_eq!(Some(65535i64), i64::from_bin_word(0xffff));
+        assert_eq!(None, (-40000i32).to_bin_word());
+        assert_eq!(None, (-2i32).to_bin_word());
+        assert_eq!(None, 70000i64.to_bin_word());
+        assert_eq!(Some(0xfffe), 0xfffeu32.to_bin_word());
+        assert_eq!(Some(0xfffeu32), u32::from_bin_word(0xfffe));
+        assert_eq!(None, u8::from_bin_word(256));
+
+        // 32bit画素値
+        assert_eq!(BinWordKind::Signed32, i32::BIN_WORD_KIND);
+        assert_eq!(Some(-40000), (-40000i32).to_bin_word32());
+        assert_eq!(None, (1i64 << 40).to_bin_word32());
+        assert_eq!(Some(-40000i64), i64::from_bin_word32(-40000));
+        assert_eq!(None, u16::from_bin_word32(-1));
+
+        println!("}}");
+    }
+}
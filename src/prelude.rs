@@ -0,0 +1,49 @@
+// よく使う型・トレイトの一括インポート用 (use sensor_io::prelude::*;)
+pub use crate::bayer::{BayerChannel, BayerPattern};
+pub use crate::bin_builder::{BinCompression, BinReader, BinWriter};
+pub use crate::compare::{assert_images_equal, compare_images};
+pub use crate::config::{Metadata, SensorGeometry};
+pub use crate::error::SensorIoError;
+pub use crate::frame_buffer::FrameBuffer;
+pub use crate::headerless::Endianness;
+pub use crate::naraw::NARaw;
+pub use crate::ndraw::NDRaw;
+pub use crate::pixel::PixelType;
+pub use crate::raw::RawImage;
+pub use crate::rect::Rect;
+pub use crate::rgb::RgbRaw;
+pub use crate::statistics::Statistics;
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_prelude() {
+        println!("prelude::test::test_prelude()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| (0..6).map(|x| (y * 6 + x) as u16).collect())
+            .collect();
+        let raw = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let cropped = raw.crop(Rect::new(2, 2, 4, 2)).unwrap();
+        let (rotated, pattern) = cropped.rotate180(BayerPattern::Rggb);
+        let (restored, pattern) = rotated.rotate180(pattern);
+        assert_images_equal(&cropped, &restored);
+        assert_eq!(BayerPattern::Rggb, pattern);
+
+        let stats: Statistics<f64> = raw.compute_statistics();
+        let report = compare_images(&raw, &NDRaw::<u16>::new(6, 4)).unwrap();
+        println!(
+            "  [prelude][test_prelude()] mean = {}, differing = {}",
+            stats.mean, report.num_differing_pixels
+        );
+        assert_eq!(23, report.num_differing_pixels);
+        assert!(matches!(
+            compare_images(&raw, &cropped),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,129 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // 動径プロファイル => [(半径, 平均値)]
+    //   中心(cx, cy)からの距離で0〜max_radiusをnum_bins等分し, 半径はbin内画素の平均距離
+    //   (画素の無いbinはbin中心の半径と平均値NaN)
+    pub fn compute_radial_profile(
+        &self,
+        cx: f64,
+        cy: f64,
+        max_radius: f64,
+        num_bins: usize,
+    ) -> Result<Vec<(f64, f64)>, SensorIoError> {
+        let profile = self.compute_radial_profile_std(cx, cy, max_radius, num_bins)?;
+        Ok(profile.into_iter().map(|(r, mean, _)| (r, mean)).collect())
+    }
+
+    // 動径プロファイル => [(半径, 平均値, 標準偏差)]
+    pub fn compute_radial_profile_std(
+        &self,
+        cx: f64,
+        cy: f64,
+        max_radius: f64,
+        num_bins: usize,
+    ) -> Result<Vec<(f64, f64, f64)>, SensorIoError> {
+        if num_bins == 0 || max_radius.is_nan() || max_radius <= 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "radial profile needs num_bins > 0 and max_radius > 0 (got {}, {})",
+                num_bins, max_radius
+            )));
+        }
+
+        // bin毎の(画素数, 距離の和, 値の和, 値の二乗和)
+        let mut acc = vec![(0usize, 0.0, 0.0, 0.0); num_bins];
+        let bin_width = max_radius / num_bins as f64;
+        for ((y, x), pix) in self.data.indexed_iter() {
+            let r = (x as f64 - cx).hypot(y as f64 - cy);
+            if r >= max_radius {
+                continue;
+            }
+            let v = pix.to_f64().unwrap();
+            let bin = &mut acc[((r / bin_width) as usize).min(num_bins - 1)];
+            bin.0 += 1;
+            bin.1 += r;
+            bin.2 += v;
+            bin.3 += v * v;
+        }
+
+        Ok(acc
+            .iter()
+            .enumerate()
+            .map(|(i, &(count, sum_r, sum, sum_sq))| {
+                if count == 0 {
+                    return ((i as f64 + 0.5) * bin_width, f64::NAN, f64::NAN);
+                }
+                let n = count as f64;
+                let mean = sum / n;
+                let var = (sum_sq / n - mean * mean).max(0.0);
+                (sum_r / n, mean, var.sqrt())
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compute_radial_profile() {
+        println!("profile::test::test_compute_radial_profile()  {{");
+
+        // 中心からの距離の1次関数
+        let (cx, cy) = (15.5, 11.0);
+        let f = |r: f64| 1000.0 - 25.0 * r;
+        let vec2d: Vec<Vec<f64>> = (0..24)
+            .map(|y| {
+                (0..32)
+                    .map(|x| f((x as f64 - cx).hypot(y as f64 - cy)))
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<f64>::new_from_vector2d(&vec2d);
+
+        let profile = raw_in.compute_radial_profile(cx, cy, 10.0, 10).unwrap();
+        println!(
+            "  [profile][test_compute_radial_profile()] profile = {:?}",
+            profile
+        );
+        assert_eq!(10, profile.len());
+        for (i, (r, mean)) in profile.iter().enumerate() {
+            assert!(*r >= i as f64 && *r < (i + 1) as f64);
+            assert!((mean - f(*r)).abs() < 1e-9, "r = {}", r);
+        }
+
+        let profile = raw_in.compute_radial_profile_std(cx, cy, 10.0, 2).unwrap();
+        assert!(profile.iter().all(|(_, _, std)| *std > 0.0));
+
+        assert!(matches!(
+            raw_in.compute_radial_profile(cx, cy, 10.0, 0),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_radial_profile_std() {
+        println!("profile::test::test_compute_radial_profile_std()  {{");
+
+        // 一様画像は標準偏差0, 画素の無いbinはNaN
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![200; 5]; 5]);
+        let profile = raw_in
+            .compute_radial_profile_std(2.0, 2.0, 20.0, 10)
+            .unwrap();
+        println!(
+            "  [profile][test_compute_radial_profile_std()] profile = {:?}",
+            profile
+        );
+        assert_eq!((200.0, 0.0), (profile[0].1, profile[0].2));
+        assert!(profile[9].1.is_nan());
+        assert_eq!(19.0, profile[9].0);
+
+        println!("}}");
+    }
+}
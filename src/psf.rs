@@ -0,0 +1,102 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+use crate::statistics::median;
+
+impl<T: PixelType> NDRaw<T> {
+    // ピンホール画像からのPSF推定
+    //   (cx, cy)中心の(2*half_size+1)四方を切り出し, 外周画素の中央値を背景として減算(負値は0),
+    //   総和が1になるよう正規化
+    pub fn estimate_psf_from_pinhole(
+        &self,
+        cx: usize,
+        cy: usize,
+        half_size: usize,
+    ) -> Result<NDRaw<f32>, SensorIoError> {
+        let size = 2 * half_size + 1;
+        if cx < half_size || cy < half_size {
+            return Err(SensorIoError::OutOfBounds(format!(
+                "PSF window {}x{} around ({}, {}) exceeds the image",
+                size, size, cx, cy
+            )));
+        }
+        let region = self.crop(Rect::new(cx - half_size, cy - half_size, size, size))?;
+
+        let mut border: Vec<f64> = region
+            .data
+            .indexed_iter()
+            .filter(|((y, x), _)| *x == 0 || *y == 0 || *x == size - 1 || *y == size - 1)
+            .map(|(_, p)| p.to_f64().unwrap())
+            .collect();
+        let background = median(&mut border).unwrap();
+
+        let psf = region
+            .data
+            .mapv(|p| (p.to_f64().unwrap() - background).max(0.0));
+        let sum = psf.sum();
+        if sum <= 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "PSF region around ({}, {}) has no signal above background",
+                cx, cy
+            )));
+        }
+        let data = psf.mapv(|v| (v / sum) as f32);
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    #[test]
+    fn test_estimate_psf_from_pinhole() {
+        println!("psf::test::test_estimate_psf_from_pinhole()  {{");
+
+        let center = (20.3, 15.8);
+        let vec2d: Vec<Vec<u16>> = (0..32)
+            .map(|y| {
+                (0..40)
+                    .map(|x| {
+                        let r2 = (x as f64 - center.0).powi(2) + (y as f64 - center.1).powi(2);
+                        (100.0 + 3000.0 * (-r2 / (2.0 * 1.5 * 1.5)).exp()).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+
+        let psf = raw_in.estimate_psf_from_pinhole(20, 16, 7).unwrap();
+        let sum: f64 = psf.data().iter().map(|v| *v as f64).sum();
+        let (px, py) = psf.find_centroid_subpixel();
+        println!(
+            "  [psf][test_estimate_psf_from_pinhole()] sum = {}, centroid = ({}, {})",
+            sum, px, py
+        );
+        assert_eq!((15, 15), (psf.width(), psf.height()));
+        assert!((sum - 1.0).abs() < 1e-6);
+        assert!((px - 7.0).abs() < 0.5 && (py - 7.0).abs() < 0.5);
+        // 背景を除いた重心は真の中心に近い
+        assert!((px + 13.0 - center.0).abs() < 0.05);
+        assert!((py + 9.0 - center.1).abs() < 0.05);
+
+        assert!(matches!(
+            raw_in.estimate_psf_from_pinhole(3, 16, 7),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+        assert!(matches!(
+            raw_in.estimate_psf_from_pinhole(35, 16, 7),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec![vec![100; 16]; 16]);
+        assert!(matches!(
+            flat.estimate_psf_from_pinhole(8, 8, 3),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        println!("}}");
+    }
+}
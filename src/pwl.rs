@@ -0,0 +1,154 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+
+// PWL(折れ線)コンパンディングカーブ
+//   points: 折れ点(入力, 出力), 入力は狭義単調増加, 出力は単調非減少
+//   区間内は整数演算の線形補間 (out = y0 + (in - x0) * (y1 - y0) / (x1 - x0), 切り捨て)
+//   最初の折れ点より小さい入力は最初の出力, 最後の折れ点より大きい入力は最後の出力に飽和
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PwlCurve {
+    points: Vec<(u32, u32)>,
+}
+
+impl PwlCurve {
+    // 折れ点指定コンストラクタ (2点以上, 単調性を検証)
+    pub fn new(points: Vec<(u32, u32)>) -> Result<Self, SensorIoError> {
+        if points.len() < 2 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "PWL curve needs at least 2 knee points, got {}",
+                points.len()
+            )));
+        }
+        for w in points.windows(2) {
+            if w[0].0 >= w[1].0 || w[0].1 > w[1].1 {
+                return Err(SensorIoError::InvalidArgument(format!(
+                    "PWL knee points {:?} -> {:?} are not monotone",
+                    w[0], w[1]
+                )));
+            }
+        }
+        Ok(PwlCurve { points })
+    }
+
+    // 折れ点取得
+    pub fn points(&self) -> &[(u32, u32)] {
+        &self.points
+    }
+
+    // 逆カーブ (入出力を入れ替え, 出力が狭義単調増加でなければエラー)
+    pub fn inverse(&self) -> Result<Self, SensorIoError> {
+        Self::new(self.points.iter().map(|&(x, y)| (y, x)).collect())
+    }
+
+    // 1画素の変換
+    pub fn apply(&self, v: u32) -> u32 {
+        let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+        if v <= first.0 {
+            return first.1;
+        }
+        if v >= last.0 {
+            return last.1;
+        }
+        let i = self.points.partition_point(|p| p.0 <= v);
+        let ((x0, y0), (x1, y1)) = (self.points[i - 1], self.points[i]);
+        let dy = (v - x0) as u64 * (y1 - y0) as u64 / (x1 - x0) as u64;
+        y0 + dy as u32
+    }
+
+    // 圧縮 (出力はu16に飽和)
+    pub fn compress(&self, raw: &NDRaw<u32>) -> NDRaw<u16> {
+        let data = raw.data.mapv(|v| self.apply(v).min(u16::MAX as u32) as u16);
+        NDRaw::from_ndarray(data)
+    }
+
+    // 伸張 (逆カーブで変換, 圧縮との往復誤差は区間の入力幅/出力幅の切り上げ以下)
+    pub fn decompress(&self, raw: &NDRaw<u16>) -> Result<NDRaw<u32>, SensorIoError> {
+        let inverse = self.inverse()?;
+        let data = raw.data.mapv(|v| inverse.apply(v as u32));
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::PwlCurve;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 20bit => 12bit (傾き 1, 1/14, 約1/1009)
+    fn curve_20_to_12() -> PwlCurve {
+        PwlCurve::new(vec![(0, 0), (2048, 2048), (16384, 3072), (1048575, 4095)]).unwrap()
+    }
+
+    #[test]
+    fn test_pwl_knee_table() {
+        println!("pwl::test::test_pwl_knee_table()  {{");
+
+        let curve = curve_20_to_12();
+        let raw_in = NDRaw::<u32>::new_from_vector2d(&[vec![
+            1000, 2048, 4096, 16384, 100000, 1048575, 2000000,
+        ]]);
+        let compressed = curve.compress(&raw_in);
+        println!(
+            "  [pwl][test_pwl_knee_table()] compressed = {}",
+            compressed.data()
+        );
+        // 手計算値 (例: 4096 => 2048 + 2048 * 1024 / 14336 = 2194)
+        assert_eq!(
+            &[1000, 2048, 2194, 3072, 3154, 4095, 4095],
+            compressed.data().as_slice().unwrap()
+        );
+
+        let decompressed = curve.decompress(&compressed).unwrap();
+        assert_eq!(
+            &[1000, 2048, 4092, 16384, 99120, 1048575, 1048575],
+            decompressed.data().as_slice().unwrap()
+        );
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_pwl_roundtrip() {
+        println!("pwl::test::test_pwl_roundtrip()  {{");
+
+        let curve = curve_20_to_12();
+        let values: Vec<u32> = (0..1048576).step_by(37).collect();
+        let raw_in = NDRaw::<u32>::new_from_vector2d(&[values]);
+        let restored = curve.decompress(&curve.compress(&raw_in)).unwrap();
+
+        // 区間毎の量子化誤差上限
+        let max_error = |v: u32| {
+            let w = curve.points().windows(2).find(|w| v <= w[1].0).unwrap();
+            (w[1].0 - w[0].0).div_ceil(w[1].1 - w[0].1)
+        };
+        let mut worst = 0;
+        for (v, r) in raw_in.data().iter().zip(restored.data().iter()) {
+            assert!(r <= v);
+            assert!(v - r <= max_error(*v));
+            worst = worst.max(v - r);
+        }
+        println!("  [pwl][test_pwl_roundtrip()] worst error = {}", worst);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_pwl_invalid() {
+        println!("pwl::test::test_pwl_invalid()  {{");
+
+        let result = PwlCurve::new(vec![(0, 0)]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = PwlCurve::new(vec![(0, 0), (100, 50), (100, 60)]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+        let result = PwlCurve::new(vec![(0, 0), (100, 50), (200, 40)]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        // 出力が平坦な区間を含むカーブは逆変換できない
+        let flat = PwlCurve::new(vec![(0, 0), (100, 50), (200, 50)]).unwrap();
+        assert!(flat.inverse().is_err());
+        assert!(flat.decompress(&NDRaw::<u16>::new(2, 2)).is_err());
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,220 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 量子効率(QE)マップ算出
+//   irradiance_image: 画素当たりの入射光子数レート [photons/us]
+//   response_image  : センサ出力 [DN] (黒レベル減算済み)
+//   gain            : 変換ゲイン [DN/e-]
+//   QE = (response / gain) / (irradiance * exposure_us)
+//   電子数がfull_well_electrons以上 (飽和) または入射光子数0の画素はNaN
+pub fn compute_qe_map<T: PixelType>(
+    irradiance_image: &NDRaw<f32>,
+    response_image: &NDRaw<T>,
+    exposure_us: f32,
+    gain: f32,
+    full_well_electrons: u32,
+) -> Result<NDRaw<f32>, SensorIoError> {
+    check_shape(
+        (irradiance_image.width(), irradiance_image.height()),
+        (response_image.width(), response_image.height()),
+    )?;
+    if exposure_us <= 0.0 {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "exposure_us must be positive: {}",
+            exposure_us
+        )));
+    }
+    if gain <= 0.0 {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "gain must be positive: {}",
+            gain
+        )));
+    }
+
+    let full_well = full_well_electrons as f64;
+    let mut data = ndarray::Array2::from_elem(irradiance_image.data.dim(), f32::NAN);
+    ndarray::Zip::from(&mut data)
+        .and(&irradiance_image.data)
+        .and(&response_image.data)
+        .for_each(|qe, irradiance, response| {
+            let photons = *irradiance as f64 * exposure_us as f64;
+            let electrons = response.to_f64().unwrap() / gain as f64;
+            if photons > 0.0 && electrons < full_well {
+                *qe = (electrons / photons) as f32;
+            }
+        });
+    Ok(NDRaw::from_ndarray(data))
+}
+
+// 分光感度 (波長毎のQEを区分線形で補間)
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SpectralResponse {
+    // (波長 [nm], QE) 波長昇順
+    points: Vec<(f32, f32)>,
+}
+
+impl SpectralResponse {
+    // 測定点取得 (波長昇順)
+    pub fn points(&self) -> &[(f32, f32)] {
+        &self.points
+    }
+
+    // 指定波長のQE (測定範囲外は端の値, 測定点なしは0)
+    pub fn qe_at(&self, wavelength_nm: f32) -> f32 {
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return 0.0,
+        };
+        if wavelength_nm <= first.0 {
+            return first.1;
+        }
+        if wavelength_nm >= last.0 {
+            return last.1;
+        }
+        let i = self.points.partition_point(|p| p.0 <= wavelength_nm);
+        let ((x0, y0), (x1, y1)) = (self.points[i - 1], self.points[i]);
+        y0 + (y1 - y0) * (wavelength_nm - x0) / (x1 - x0)
+    }
+
+    // ピーク (波長 [nm], QE) 取得 (測定点なしはNone)
+    pub fn peak(&self) -> Option<(f32, f32)> {
+        self.points
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+}
+
+// 波長とQEの組から分光感度モデルを作成 (同一波長は平均, 要素数不一致はエラー)
+pub fn compute_spectral_responsivity(
+    wavelength_nm: &[f32],
+    qe_values: &[f32],
+) -> Result<SpectralResponse, SensorIoError> {
+    if wavelength_nm.len() != qe_values.len() {
+        return Err(SensorIoError::InvalidArgument(format!(
+            "wavelength_nm has {} entries, qe_values has {}",
+            wavelength_nm.len(),
+            qe_values.len()
+        )));
+    }
+    let mut pairs: Vec<(f32, f32)> = wavelength_nm
+        .iter()
+        .copied()
+        .zip(qe_values.iter().copied())
+        .collect();
+    pairs.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut points: Vec<(f32, f32)> = Vec::new();
+    let mut count = 0;
+    for (wavelength, qe) in pairs {
+        match points.last_mut() {
+            Some(last) if last.0 == wavelength => {
+                count += 1;
+                last.1 += (qe - last.1) / count as f32;
+            }
+            _ => {
+                points.push((wavelength, qe));
+                count = 1;
+            }
+        }
+    }
+    Ok(SpectralResponse { points })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{compute_qe_map, compute_spectral_responsivity};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compute_qe_map() {
+        println!("radiometry::test::test_compute_qe_map()  {{");
+
+        // QE = 0.6, 露光100us, ゲイン0.5DN/e-
+        let (qe, exposure_us, gain) = (0.6, 100.0, 0.5);
+        let irradiance = NDRaw::<f32>::new_from_vector2d(
+            &(0..8)
+                .map(|y| (0..8).map(|x| 10.0 + (x + y) as f32).collect())
+                .collect::<Vec<_>>(),
+        );
+        let mut response = NDRaw::<u16>::new(8, 8);
+        for y in 0..8 {
+            for x in 0..8 {
+                let photons = *irradiance.pix(x, y) * exposure_us;
+                *response.pix_mut(x, y) = (photons * qe * gain).round() as u16;
+            }
+        }
+        // 飽和画素
+        *response.pix_mut(7, 7) = 5000;
+
+        let map = compute_qe_map(&irradiance, &response, exposure_us, gain, 8000).unwrap();
+        println!(
+            "  [radiometry][test_compute_qe_map()] qe(0, 0) = {}",
+            map.pix(0, 0)
+        );
+        for y in 0..8 {
+            for x in 0..8 {
+                if (x, y) == (7, 7) {
+                    assert!(map.pix(x, y).is_nan());
+                } else {
+                    assert!((map.pix(x, y) - qe).abs() < 0.01);
+                }
+            }
+        }
+
+        assert!(matches!(
+            compute_qe_map(
+                &irradiance,
+                &NDRaw::<u16>::new(4, 8),
+                exposure_us,
+                gain,
+                8000
+            ),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+        assert!(matches!(
+            compute_qe_map(&irradiance, &response, 0.0, gain, 8000),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            compute_qe_map(&irradiance, &response, exposure_us, 0.0, 8000),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_spectral_responsivity() {
+        println!("radiometry::test::test_compute_spectral_responsivity()  {{");
+
+        let response =
+            compute_spectral_responsivity(&[650.0, 450.0, 550.0, 550.0], &[0.3, 0.4, 0.5, 0.75])
+                .unwrap();
+        println!(
+            "  [radiometry][test_compute_spectral_responsivity()] points = {:?}",
+            response.points()
+        );
+        assert_eq!(
+            &[(450.0, 0.4), (550.0, 0.625), (650.0, 0.3)],
+            response.points()
+        );
+        assert!((response.qe_at(500.0) - 0.5125).abs() < 1e-6);
+        assert!((response.qe_at(600.0) - 0.4625).abs() < 1e-6);
+        assert_eq!(0.4, response.qe_at(400.0));
+        assert_eq!(0.3, response.qe_at(700.0));
+        assert_eq!(Some((550.0, 0.625)), response.peak());
+        assert_eq!(
+            0.0,
+            compute_spectral_responsivity(&[], &[])
+                .unwrap()
+                .qe_at(500.0)
+        );
+        let result = compute_spectral_responsivity(&[450.0], &[]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,225 @@
+use crate::bayer::BayerPattern;
+use crate::blend;
+use crate::centroid;
+use crate::dark::{self, CheckedSubError};
+use crate::defect::{self, DefectCorrection, DefectPixel};
+use crate::error::SensorIoError;
+use crate::flip::{self, FlipMode};
+use crate::gradient;
+use crate::heatmap::{self, PseudoColorMap};
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::rect::Rect;
+use crate::uniformity::{self, FlatnessReport};
+use std::path::Path;
+
+// NDRaw/NARaw共通インタフェース
+pub trait RawImage {
+    type Pixel: PixelType;
+
+    // width取得
+    fn width(&self) -> usize;
+
+    // height取得
+    fn height(&self) -> usize;
+
+    // pix取得
+    fn pix(&self, x: usize, y: usize) -> &Self::Pixel;
+    fn pix_mut(&mut self, x: usize, y: usize) -> &mut Self::Pixel;
+
+    // 欠陥画素補正
+    fn correct_defects(&mut self, defects: &[DefectPixel], method: DefectCorrection) {
+        defect::correct_defects(self, defects, method);
+    }
+
+    // 疑似カラーPNG保存 (min/max未指定は画像の最小値/最大値で0〜1に正規化)
+    fn export_to_heatmap_png(
+        &self,
+        path: impl AsRef<Path>,
+        colormap: &PseudoColorMap,
+        min: Option<Self::Pixel>,
+        max: Option<Self::Pixel>,
+    ) -> Result<(), SensorIoError> {
+        heatmap::export(self, path.as_ref(), colormap, min, max, false)
+    }
+
+    // 疑似カラーPNG保存 (右端にカラーバー付き, 上端が最大値)
+    fn export_to_heatmap_png_with_colorbar(
+        &self,
+        path: impl AsRef<Path>,
+        colormap: &PseudoColorMap,
+        min: Option<Self::Pixel>,
+        max: Option<Self::Pixel>,
+    ) -> Result<(), SensorIoError> {
+        heatmap::export(self, path.as_ref(), colormap, min, max, true)
+    }
+
+    // 輝度重心(x, y)取得 (全画素0の場合はNaN)
+    fn find_centroid_subpixel(&self) -> (f64, f64) {
+        centroid::centroid_in(self, Rect::new(0, 0, self.width(), self.height()))
+    }
+
+    // 輝度重心(x, y)取得 (find_centroid_subpixelの別名, 全画素0の場合はNaN)
+    fn centroid(&self) -> (f64, f64) {
+        self.find_centroid_subpixel()
+    }
+
+    // 指定領域の輝度重心(x, y)取得 (画像座標)
+    fn find_centroid_in_roi(&self, rect: Rect) -> Result<(f64, f64), SensorIoError> {
+        rect.check_within(self.width(), self.height())?;
+        Ok(centroid::centroid_in(self, rect))
+    }
+
+    // 局所最大検出 (threshold超, 互いにmin_distance画素を超えて離れたピークの(x, y)を輝度降順で返す)
+    fn find_local_maxima(
+        &self,
+        min_distance: usize,
+        threshold: Self::Pixel,
+    ) -> Vec<(usize, usize)> {
+        centroid::local_maxima(self, min_distance, threshold)
+    }
+
+    // Sobel勾配強度
+    fn gradient_magnitude(&self) -> NDRaw<f32> {
+        gradient::sobel(self).0
+    }
+
+    // Sobel勾配方向 (atan2(gy, gx) [rad], yは下向き正)
+    fn gradient_direction(&self) -> NDRaw<f32> {
+        gradient::sobel(self).1
+    }
+
+    // Brennerフォーカス評価値 (2画素隣との差分の二乗和)
+    fn compute_focus_score_brenner(&self) -> f64 {
+        gradient::focus_score_brenner(self)
+    }
+
+    // Laplacianフォーカス評価値 (Laplacianの分散, 端の画素は除外)
+    fn compute_focus_score_laplacian(&self) -> f64 {
+        gradient::focus_score_laplacian(self)
+    }
+
+    // 左右反転 => (反転画像, 反転後のBayer配列) (Preserveで配列を保てない幅2の画像はエラー)
+    fn flip_horizontal(
+        &self,
+        pattern: BayerPattern,
+        mode: FlipMode,
+    ) -> Result<(Self, BayerPattern), SensorIoError>
+    where
+        Self: Clone + Sized,
+    {
+        let mut flipped = self.clone();
+        let pattern = flipped.flip_horizontal_mut(pattern, mode)?;
+        Ok((flipped, pattern))
+    }
+
+    // 上下反転 => (反転画像, 反転後のBayer配列) (Preserveで配列を保てない高さ2の画像はエラー)
+    fn flip_vertical(
+        &self,
+        pattern: BayerPattern,
+        mode: FlipMode,
+    ) -> Result<(Self, BayerPattern), SensorIoError>
+    where
+        Self: Clone + Sized,
+    {
+        let mut flipped = self.clone();
+        let pattern = flipped.flip_vertical_mut(pattern, mode)?;
+        Ok((flipped, pattern))
+    }
+
+    // 左右反転 (in-place) => 反転後のBayer配列
+    fn flip_horizontal_mut(
+        &mut self,
+        pattern: BayerPattern,
+        mode: FlipMode,
+    ) -> Result<BayerPattern, SensorIoError> {
+        flip::flip_horizontal(self, pattern, mode)
+    }
+
+    // 上下反転 (in-place) => 反転後のBayer配列
+    fn flip_vertical_mut(
+        &mut self,
+        pattern: BayerPattern,
+        mode: FlipMode,
+    ) -> Result<BayerPattern, SensorIoError> {
+        flip::flip_vertical(self, pattern, mode)
+    }
+
+    // 重み付き合成 (alpha*self + (1-alpha)*other, alphaは0.0〜1.0, 最近接丸め・型の範囲に飽和)
+    fn blend(&self, other: &Self, alpha: f64) -> Result<Self, SensorIoError>
+    where
+        Self: Clone + Sized,
+    {
+        let mut blended = self.clone();
+        blend::blend_into(&mut blended, other, alpha)?;
+        Ok(blended)
+    }
+
+    // ダーク減算 (型の範囲に飽和, 符号なし型では0で止まる)
+    fn subtract_dark(&mut self, dark: &Self) -> Result<(), SensorIoError> {
+        dark::subtract_dark(self, dark, None)
+    }
+
+    // 面内均一性の評価 (ピークtoバレー, RMS, 中央/四隅比)
+    fn compute_flatness_error(&self) -> FlatnessReport {
+        uniformity::flatness_error(self)
+    }
+
+    // 減算 (アンダーフローする画素・サイズ不一致があれば結果の代わりにCheckedSubErrorを返す)
+    fn checked_sub(&self, other: &Self) -> Result<Self, CheckedSubError>
+    where
+        Self: Clone + Sized,
+    {
+        dark::checked_sub(self, other)
+    }
+
+    // ダーク減算 (hot_thresholdを超えるダーク画素はダークの同色近傍中央値で置き換える)
+    fn subtract_dark_ignoring_hot(
+        &mut self,
+        dark: &Self,
+        hot_threshold: Self::Pixel,
+    ) -> Result<(), SensorIoError> {
+        dark::subtract_dark(self, dark, Some(hot_threshold))
+    }
+}
+
+impl<T: PixelType> RawImage for NDRaw<T> {
+    type Pixel = T;
+
+    fn width(&self) -> usize {
+        NDRaw::width(self)
+    }
+
+    fn height(&self) -> usize {
+        NDRaw::height(self)
+    }
+
+    fn pix(&self, x: usize, y: usize) -> &T {
+        NDRaw::pix(self, x, y)
+    }
+
+    fn pix_mut(&mut self, x: usize, y: usize) -> &mut T {
+        NDRaw::pix_mut(self, x, y)
+    }
+}
+
+impl<T: PixelType> RawImage for NARaw<T> {
+    type Pixel = T;
+
+    fn width(&self) -> usize {
+        NARaw::width(self)
+    }
+
+    fn height(&self) -> usize {
+        NARaw::height(self)
+    }
+
+    fn pix(&self, x: usize, y: usize) -> &T {
+        NARaw::pix(self, x, y)
+    }
+
+    fn pix_mut(&mut self, x: usize, y: usize) -> &mut T {
+        NARaw::pix_mut(self, x, y)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::error::SensorIoError;
+
+// 矩形領域 (左上座標 + サイズ)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    // 右端(排他)
+    pub fn right(&self) -> usize {
+        self.x + self.width
+    }
+
+    // 下端(排他)
+    pub fn bottom(&self) -> usize {
+        self.y + self.height
+    }
+
+    // 画像(width x height)内に収まっているか確認
+    pub(crate) fn check_within(&self, width: usize, height: usize) -> Result<(), SensorIoError> {
+        if self.right() > width || self.bottom() > height {
+            return Err(SensorIoError::OutOfBounds(format!(
+                "rect {}x{}+{}+{} exceeds {}x{} image",
+                self.width, self.height, self.x, self.y, width, height
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Rect;
+    use crate::error::SensorIoError;
+
+    #[test]
+    fn test_check_within() {
+        println!("rect::test::test_check_within()  {{");
+
+        let rect = Rect::new(2, 1, 4, 3);
+        assert_eq!((6, 4), (rect.right(), rect.bottom()));
+        assert!(rect.check_within(6, 4).is_ok());
+        assert!(matches!(
+            rect.check_within(5, 4),
+            Err(SensorIoError::OutOfBounds(_))
+        ));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,145 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::spectrum::fft2d;
+use rustfft::num_complex::Complex;
+
+impl<T: PixelType> NDRaw<T> {
+    // 位相限定相関による平行移動量推定
+    //   otherがselfを(dx, dy)だけ平行移動した画像となる(dx, dy)を返す (ピーク近傍の放物線補間で小数精度)
+    //   画像は周期境界として扱う(窓関数は推定値を偏らせるため掛けない), 幅・高さは2のべき乗のみ対応
+    pub fn compute_phase_correlation(&self, other: &NDRaw<T>) -> Result<(f64, f64), SensorIoError> {
+        check_shape(
+            (self.width(), self.height()),
+            (other.width(), other.height()),
+        )?;
+        let (width, height) = (self.width(), self.height());
+        if !width.is_power_of_two() || !height.is_power_of_two() {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "image size {}x{} must be a power of two",
+                width, height
+            )));
+        }
+
+        let spectrum = |raw: &NDRaw<T>| {
+            let mut buf: Vec<Complex<f64>> = raw
+                .data
+                .iter()
+                .map(|p| Complex::new(p.to_f64().unwrap(), 0.0))
+                .collect();
+            fft2d(&mut buf, width, height, false);
+            buf
+        };
+        let f1 = spectrum(self);
+        let mut cross = spectrum(other);
+
+        // 正規化クロスパワースペクトル
+        for (c, a) in cross.iter_mut().zip(f1.iter()) {
+            let v = *c * a.conj();
+            let norm = v.norm();
+            *c = if norm > 1e-12 {
+                v / norm
+            } else {
+                Complex::new(0.0, 0.0)
+            };
+        }
+        fft2d(&mut cross, width, height, true);
+
+        let corr: Vec<f64> = cross.iter().map(|c| c.re).collect();
+        let (peak, _) =
+            corr.iter().enumerate().fold(
+                (0, f64::MIN),
+                |(i, m), (j, v)| if *v > m { (j, *v) } else { (i, m) },
+            );
+        let (px, py) = (peak % width, peak / width);
+
+        let at = |x: usize, y: usize| corr[(y % height) * width + (x % width)];
+        let dx = px as f64 + parabolic_offset(at(px + width - 1, py), at(px, py), at(px + 1, py));
+        let dy = py as f64 + parabolic_offset(at(px, py + height - 1), at(px, py), at(px, py + 1));
+
+        // 半分以上の移動量は負方向の移動とみなす
+        let wrap = |d: f64, n: usize| if d > n as f64 / 2.0 { d - n as f64 } else { d };
+        Ok((wrap(dx, width), wrap(dy, height)))
+    }
+}
+
+// 3点の放物線補間による頂点オフセット(-0.5〜0.5)
+fn parabolic_offset(left: f64, center: f64, right: f64) -> f64 {
+    let denom = left - 2.0 * center + right;
+    if denom.abs() < 1e-12 {
+        return 0.0;
+    }
+    (0.5 * (left - right) / denom).clamp(-0.5, 0.5)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // ガウシアン輝点を並べた画像 (全体を(shift_x, shift_y)だけ平行移動)
+    fn blobs(shift_x: f64, shift_y: f64) -> NDRaw<u16> {
+        let centers = [(20.0, 24.0), (35.0, 30.0), (28.0, 42.0), (44.0, 20.0)];
+        let vec2d: Vec<Vec<u16>> = (0..64)
+            .map(|y| {
+                (0..64)
+                    .map(|x| {
+                        let v: f64 = centers
+                            .iter()
+                            .map(|(cx, cy)| {
+                                let dx = x as f64 - cx - shift_x;
+                                let dy = y as f64 - cy - shift_y;
+                                1000.0 * (-(dx * dx + dy * dy) / (2.0 * 2.0 * 2.0)).exp()
+                            })
+                            .sum();
+                        (100.0 + v).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        NDRaw::<u16>::new_from_vector2d(&vec2d)
+    }
+
+    #[test]
+    fn test_compute_phase_correlation() {
+        println!("registration::test::test_compute_phase_correlation()  {{");
+
+        let reference = blobs(0.0, 0.0);
+        let (dx, dy) = reference
+            .compute_phase_correlation(&blobs(3.5, 0.0))
+            .unwrap();
+        println!(
+            "  [registration][test_compute_phase_correlation()] (dx, dy) = ({}, {})",
+            dx, dy
+        );
+        assert!((dx - 3.5).abs() < 0.1);
+        assert!(dy.abs() < 0.1);
+
+        // 負方向の移動
+        let (dx, dy) = reference
+            .compute_phase_correlation(&blobs(-2.0, 5.0))
+            .unwrap();
+        println!(
+            "  [registration][test_compute_phase_correlation()] (dx, dy) = ({}, {})",
+            dx, dy
+        );
+        assert!((dx + 2.0).abs() < 0.1);
+        assert!((dy - 5.0).abs() < 0.1);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_phase_correlation_invalid() {
+        println!("registration::test::test_compute_phase_correlation_invalid()  {{");
+
+        let a = NDRaw::<u16>::new(64, 64);
+        let result = a.compute_phase_correlation(&NDRaw::<u16>::new(64, 32));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+        let b = NDRaw::<u16>::new(48, 32);
+        let result = b.compute_phase_correlation(&b.clone());
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
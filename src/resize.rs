@@ -0,0 +1,309 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// リサイズ補間方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ResizeMode {
+    // 最近傍
+    Nearest,
+    // バイリニア
+    Bilinear,
+    // バイキュービック (Catmull-Rom)
+    Bicubic,
+    // 面積平均 (出力画素が覆う入力画素を重なり面積で加重平均, 縮小時のアンチエイリアス)
+    Area,
+}
+
+impl ResizeMode {
+    // 出力座標毎の(入力座標, 重み)一覧 (画素中心を合わせ, 範囲外は端の画素で補完)
+    fn taps(&self, src_len: usize, dst_len: usize) -> Vec<Vec<(usize, f64)>> {
+        let scale = src_len as f64 / dst_len as f64;
+        let clamp = |i: isize| i.clamp(0, src_len as isize - 1) as usize;
+        (0..dst_len)
+            .map(|d| {
+                let s = (d as f64 + 0.5) * scale - 0.5;
+                let base = s.floor();
+                let t = s - base;
+                let base = base as isize;
+                match self {
+                    ResizeMode::Nearest => vec![(clamp(s.round() as isize), 1.0)],
+                    ResizeMode::Bilinear => {
+                        vec![(clamp(base), 1.0 - t), (clamp(base + 1), t)]
+                    }
+                    ResizeMode::Bicubic => (-1..=2)
+                        .map(|k| (clamp(base + k), catmull_rom(t - k as f64)))
+                        .collect(),
+                    ResizeMode::Area => area_taps(d, scale, src_len),
+                }
+            })
+            .collect()
+    }
+}
+
+// 出力画素dが覆う入力区間[d * scale, (d + 1) * scale)との重なりを重みとする
+fn area_taps(d: usize, scale: f64, src_len: usize) -> Vec<(usize, f64)> {
+    let (start, end) = (
+        d as f64 * scale,
+        ((d + 1) as f64 * scale).min(src_len as f64),
+    );
+    let first = start.floor() as usize;
+    let last = (end.ceil() as usize).clamp(first + 1, src_len);
+    (first..last)
+        .map(|i| {
+            let overlap = end.min((i + 1) as f64) - start.max(i as f64);
+            (i, overlap.max(0.0) / scale)
+        })
+        .filter(|&(_, w)| w > 0.0)
+        .collect()
+}
+
+// Lanczosの出力座標毎の(入力座標, 重み)一覧
+//   縮小時はカーネルを倍率分広げてアンチエイリアスし, 重みは和が1になるよう正規化 (範囲外は端の画素で補完)
+fn lanczos_taps(src_len: usize, dst_len: usize, a: u8) -> Vec<Vec<(usize, f64)>> {
+    let scale = src_len as f64 / dst_len as f64;
+    let stretch = scale.max(1.0);
+    let support = a as f64 * stretch;
+    let clamp = |i: isize| i.clamp(0, src_len as isize - 1) as usize;
+    (0..dst_len)
+        .map(|d| {
+            let s = (d as f64 + 0.5) * scale - 0.5;
+            let (first, last) = (
+                (s - support).ceil() as isize,
+                (s + support).floor() as isize,
+            );
+            let mut taps: Vec<(usize, f64)> = (first..=last)
+                .map(|i| (clamp(i), lanczos((i as f64 - s) / stretch, a as f64)))
+                .filter(|&(_, w)| w != 0.0)
+                .collect();
+            let sum: f64 = taps.iter().map(|(_, w)| w).sum();
+            taps.iter_mut().for_each(|(_, w)| *w /= sum);
+            taps
+        })
+        .collect()
+}
+
+// Lanczosカーネル sinc(x) * sinc(x / a) (|x| < a)
+fn lanczos(x: f64, a: f64) -> f64 {
+    let sinc = |x: f64| {
+        if x == 0.0 {
+            1.0
+        } else {
+            let px = std::f64::consts::PI * x;
+            px.sin() / px
+        }
+    };
+    if x.abs() < a {
+        sinc(x) * sinc(x / a)
+    } else {
+        0.0
+    }
+}
+
+// 分離型の再標本化 (水平方向 => 垂直方向)
+fn resample<T: PixelType>(
+    src: &ndarray::Array2<T>,
+    taps_x: &[Vec<(usize, f64)>],
+    taps_y: &[Vec<(usize, f64)>],
+) -> ndarray::Array2<f64> {
+    let horizontal = ndarray::Array2::from_shape_fn((src.nrows(), taps_x.len()), |(y, x)| {
+        taps_x[x]
+            .iter()
+            .map(|&(sx, w)| w * src[[y, sx]].to_f64().unwrap())
+            .sum::<f64>()
+    });
+    ndarray::Array2::from_shape_fn((taps_y.len(), taps_x.len()), |(y, x)| {
+        taps_y[y]
+            .iter()
+            .map(|&(sy, w)| w * horizontal[[sy, x]])
+            .sum()
+    })
+}
+
+// Catmull-Rom (Keys, a = -0.5) キュービックカーネル
+fn catmull_rom(x: f64) -> f64 {
+    let x = x.abs();
+    if x < 1.0 {
+        1.5 * x * x * x - 2.5 * x * x + 1.0
+    } else if x < 2.0 {
+        -0.5 * x * x * x + 2.5 * x * x - 4.0 * x + 2.0
+    } else {
+        0.0
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // リサイズ (計算はf64, 結果は最近接丸め・型の範囲に飽和)
+    pub fn resize(&self, width: usize, height: usize, mode: ResizeMode) -> Self {
+        if self.width() == 0 || self.height() == 0 {
+            return NDRaw::new(width, height);
+        }
+        let taps_x = mode.taps(self.width(), width);
+        let taps_y = mode.taps(self.height(), height);
+        let data = resample(&self.data, &taps_x, &taps_y).mapv(T::from_f64_saturating);
+        NDRaw::from_ndarray(data)
+    }
+
+    // Lanczos縮小 (aはカーネルの広さ, 通常2か3, 結果はf32)
+    pub fn downsample_lanczos(&self, new_width: usize, new_height: usize, a: u8) -> NDRaw<f32> {
+        if self.width() == 0 || self.height() == 0 || a == 0 {
+            return NDRaw::new(new_width, new_height);
+        }
+        let taps_x = lanczos_taps(self.width(), new_width, a);
+        let taps_y = lanczos_taps(self.height(), new_height, a);
+        let data = resample(&self.data, &taps_x, &taps_y).mapv(|v| v as f32);
+        NDRaw::from_ndarray(data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ResizeMode;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_resize_nearest_bilinear() {
+        println!("resize::test::test_resize_nearest_bilinear()  {{");
+
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&[vec![0, 100], vec![200, 300]]);
+        let nearest = raw_in.resize(4, 4, ResizeMode::Nearest);
+        println!(
+            "  [resize][test_resize_nearest_bilinear()] nearest = \n{}",
+            nearest.data()
+        );
+        assert_eq!(0, *nearest.pix(1, 1));
+        assert_eq!(300, *nearest.pix(2, 2));
+
+        let bilinear = raw_in.resize(4, 4, ResizeMode::Bilinear);
+        println!(
+            "  [resize][test_resize_nearest_bilinear()] bilinear = \n{}",
+            bilinear.data()
+        );
+        // 出力(1, 0)は入力x = 0.25
+        assert_eq!(25, *bilinear.pix(1, 0));
+        assert_eq!(0, *bilinear.pix(0, 0));
+        assert_eq!(300, *bilinear.pix(3, 3));
+
+        // 同サイズは恒等
+        for mode in [
+            ResizeMode::Nearest,
+            ResizeMode::Bilinear,
+            ResizeMode::Bicubic,
+            ResizeMode::Area,
+        ] {
+            assert_eq!(raw_in.data(), raw_in.resize(2, 2, mode).data());
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_resize_bicubic() {
+        println!("resize::test::test_resize_bicubic()  {{");
+
+        // 滑らかな2次のグラデーション
+        let f = |x: f64, y: f64| 0.5 * x * x + 0.25 * y * y + x * y;
+        let (width, height) = (16, 12);
+        let vec2d: Vec<Vec<f64>> = (0..height)
+            .map(|y| (0..width).map(|x| f(x as f64, y as f64)).collect())
+            .collect();
+        let raw_in = NDRaw::<f64>::new_from_vector2d(&vec2d);
+
+        let error = |mode: ResizeMode| {
+            let raw_out = raw_in.resize(width * 2, height * 2, mode);
+            let mut sum = 0.0;
+            // 端の補完の影響を受けない内側で比較
+            for y in 4..height * 2 - 4 {
+                for x in 4..width * 2 - 4 {
+                    let (sx, sy) = ((x as f64 + 0.5) / 2.0 - 0.5, (y as f64 + 0.5) / 2.0 - 0.5);
+                    sum += (raw_out.pix(x, y) - f(sx, sy)).abs();
+                }
+            }
+            sum
+        };
+        let (error_bilinear, error_bicubic) =
+            (error(ResizeMode::Bilinear), error(ResizeMode::Bicubic));
+        println!(
+            "  [resize][test_resize_bicubic()] error bilinear = {}, bicubic = {}",
+            error_bilinear, error_bicubic
+        );
+        assert!(error_bicubic < error_bilinear / 10.0);
+
+        // 整数型では丸め・飽和
+        let raw_u8 = NDRaw::<u8>::new_from_vector2d(&[vec![0, 255, 0, 255]]);
+        let raw_out = raw_u8.resize(8, 1, ResizeMode::Bicubic);
+        println!(
+            "  [resize][test_resize_bicubic()] raw_out = {}",
+            raw_out.data()
+        );
+        assert!(raw_out.data().iter().any(|v| *v == 255));
+        assert!(raw_out.data().iter().any(|v| *v == 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_resize_area() {
+        println!("resize::test::test_resize_area()  {{");
+
+        // 白黒の市松模様を非整数倍率で大きく縮小
+        let vec2d: Vec<Vec<u16>> = (0..48)
+            .map(|y| {
+                (0..64)
+                    .map(|x| if (x + y) % 2 == 0 { 1000 } else { 0 })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let area = raw_in.resize(5, 5, ResizeMode::Area);
+        println!("  [resize][test_resize_area()] area = {}", area.data());
+        assert!(area.data().iter().all(|v| v.abs_diff(500) <= 30));
+
+        // 最近傍ではエイリアスで0か1000になる
+        let nearest = raw_in.resize(5, 5, ResizeMode::Nearest);
+        assert!(nearest.data().iter().all(|v| *v == 0 || *v == 1000));
+
+        // 整数倍率では単純平均
+        let raw_in = NDRaw::<f64>::new_from_vector2d(&[vec![1.0, 2.0, 3.0, 6.0]]);
+        let area = raw_in.resize(2, 1, ResizeMode::Area);
+        assert_eq!(1.5, *area.pix(0, 0));
+        assert_eq!(4.5, *area.pix(1, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_downsample_lanczos() {
+        println!("resize::test::test_downsample_lanczos()  {{");
+
+        // 水平ランプ (値 = x) を1/2に縮小: 出力xは入力2x, 2x + 1の中央 (2x + 0.5)
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec![(0..64).collect::<Vec<u16>>(); 32]);
+        for a in [2, 3] {
+            let raw_out = raw_in.downsample_lanczos(32, 16, a);
+            assert_eq!((32, 16), (raw_out.width(), raw_out.height()));
+            println!(
+                "  [resize][test_downsample_lanczos()] a = {}, row = {}",
+                a,
+                raw_out.data().row(8)
+            );
+            // 端の補完の影響を受けない内側で比較
+            for y in 0..16 {
+                for x in 3..29 {
+                    let decimated = *raw_in.pix(2 * x, 2 * y) as f32;
+                    assert!((raw_out.pix(x, y) - decimated).abs() <= 0.5 + 1e-4);
+                    assert!((raw_out.pix(x, y) - (decimated + 0.5)).abs() < 1e-3);
+                }
+            }
+        }
+
+        // 出力サイズは指定通り (非整数倍率)
+        let raw_out = raw_in.downsample_lanczos(27, 11, 3);
+        assert_eq!((27, 11), (raw_out.width(), raw_out.height()));
+
+        // 一様画像は一様のまま (重みの正規化)
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec![vec![1000; 50]; 30]);
+        let raw_out = flat.downsample_lanczos(17, 9, 3);
+        assert!(raw_out.data().iter().all(|v| (v - 1000.0).abs() < 1e-3));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,126 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// フル解像度RGB画像 (チャネル毎のプレーン)
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct RgbRaw<T: PixelType> {
+    pub(crate) r: NDRaw<T>,
+    pub(crate) g: NDRaw<T>,
+    pub(crate) b: NDRaw<T>,
+}
+
+impl<T: PixelType> RgbRaw<T> {
+    // 画サイズ指定コンストラクタ
+    pub fn new(width: usize, height: usize) -> Self {
+        RgbRaw {
+            r: NDRaw::new(width, height),
+            g: NDRaw::new(width, height),
+            b: NDRaw::new(width, height),
+        }
+    }
+
+    // プレーン指定コンストラクタ (サイズ不一致はエラー)
+    pub fn from_planes(r: NDRaw<T>, g: NDRaw<T>, b: NDRaw<T>) -> Result<Self, SensorIoError> {
+        check_shape((r.width(), r.height()), (g.width(), g.height()))?;
+        check_shape((r.width(), r.height()), (b.width(), b.height()))?;
+        Ok(RgbRaw { r, g, b })
+    }
+
+    // プレーン取得 => [R, G, B]
+    pub fn into_planes(self) -> [NDRaw<T>; 3] {
+        [self.r, self.g, self.b]
+    }
+
+    // Rプレーン取得
+    pub fn r(&self) -> &NDRaw<T> {
+        &self.r
+    }
+
+    // Gプレーン取得
+    pub fn g(&self) -> &NDRaw<T> {
+        &self.g
+    }
+
+    // Bプレーン取得
+    pub fn b(&self) -> &NDRaw<T> {
+        &self.b
+    }
+
+    // width取得
+    pub fn width(&self) -> usize {
+        self.r.width()
+    }
+
+    // height取得
+    pub fn height(&self) -> usize {
+        self.r.height()
+    }
+
+    // 画素値取得 => [R, G, B]
+    pub fn pix(&self, x: usize, y: usize) -> [T; 3] {
+        [*self.r.pix(x, y), *self.g.pix(x, y), *self.b.pix(x, y)]
+    }
+
+    // 画素値設定
+    pub fn set_pix(&mut self, x: usize, y: usize, rgb: [T; 3]) {
+        *self.r.pix_mut(x, y) = rgb[0];
+        *self.g.pix_mut(x, y) = rgb[1];
+        *self.b.pix_mut(x, y) = rgb[2];
+    }
+}
+
+// 入力RGB画像の画素値
+//   8bitを超える画像は16bitのまま読み込む (Tが16bit値を表せない場合は8bitに縮小)
+pub(crate) enum RgbSource {
+    Rgb8(image::RgbImage),
+    Rgb16(image::ImageBuffer<image::Rgb<u16>, Vec<u16>>),
+}
+
+impl RgbSource {
+    pub(crate) fn new<T: PixelType>(img_in: &image::DynamicImage) -> Self {
+        let high_depth = img_in.color().bytes_per_pixel() > img_in.color().channel_count();
+        let holds_16bit = T::max_value().to_f64().unwrap() >= u16::MAX as f64;
+        if high_depth && holds_16bit {
+            RgbSource::Rgb16(img_in.to_rgb16())
+        } else {
+            RgbSource::Rgb8(img_in.to_rgb8())
+        }
+    }
+
+    // 座標(x, y)の[R, G, B]
+    pub(crate) fn rgb_at(&self, x: usize, y: usize) -> [f64; 3] {
+        match self {
+            RgbSource::Rgb8(img) => img.get_pixel(x as u32, y as u32).0.map(f64::from),
+            RgbSource::Rgb16(img) => img.get_pixel(x as u32, y as u32).0.map(f64::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RgbRaw;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_rgb_raw() {
+        println!("rgb::test::test_rgb_raw()  {{");
+
+        let mut rgb = RgbRaw::<u16>::new(4, 3);
+        assert_eq!((4, 3), (rgb.width(), rgb.height()));
+        rgb.set_pix(2, 1, [10, 20, 30]);
+        assert_eq!([10, 20, 30], rgb.pix(2, 1));
+        assert_eq!(20, *rgb.g().pix(2, 1));
+
+        let [r, g, b] = rgb.into_planes();
+        let rgb = RgbRaw::from_planes(r, g, b).unwrap();
+        assert_eq!([10, 20, 30], rgb.pix(2, 1));
+
+        let result =
+            RgbRaw::from_planes(NDRaw::<u16>::new(4, 3), NDRaw::new(4, 3), NDRaw::new(3, 4));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
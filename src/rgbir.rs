@@ -0,0 +1,211 @@
+use crate::cfa::{CfaChannel, CfaLayout};
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 可視光チャネル毎のIR混入係数 (画素値 -= 係数 * 周辺のIR値)
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct IrCoefficients {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub white: f64,
+}
+
+impl IrCoefficients {
+    // チャネルの係数 (Ir・Yellowは0)
+    pub fn coefficient(&self, channel: CfaChannel) -> f64 {
+        match channel {
+            CfaChannel::R => self.r,
+            CfaChannel::G => self.g,
+            CfaChannel::B => self.b,
+            CfaChannel::White => self.white,
+            CfaChannel::Ir | CfaChannel::Yellow => 0.0,
+        }
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // IR面分離
+    pub fn extract_ir_plane(&self, layout: &CfaLayout) -> Result<NDRaw<T>, SensorIoError> {
+        self.extract_cfa_plane(layout, CfaChannel::Ir)
+    }
+
+    // IR混入除去 (可視光画素のみ, 結果は0〜型の最大値にクランプ)
+    //   各画素のIR値は, IR画素が見つかる最小の正方窓 (半径1から周期まで) 内のIR画素の平均
+    pub fn subtract_ir_contamination(
+        &mut self,
+        layout: &CfaLayout,
+        coefficients: IrCoefficients,
+    ) -> Result<(), SensorIoError> {
+        let (width, height) = (self.width(), self.height());
+        let (period_x, period_y) = layout.period();
+        let max_radius = period_x.max(period_y) as isize;
+        if !(0..period_y).any(|y| (0..period_x).any(|x| layout.color_at(x, y) == CfaChannel::Ir)) {
+            return Err(SensorIoError::InvalidArgument(
+                "CFA layout has no IR sites".to_string(),
+            ));
+        }
+
+        let source = self.data.clone();
+        let ir_at = |x: usize, y: usize| {
+            for r in 1..=max_radius {
+                let (mut sum, mut count) = (0.0, 0);
+                for sy in (y as isize - r).max(0)..=(y as isize + r).min(height as isize - 1) {
+                    for sx in (x as isize - r).max(0)..=(x as isize + r).min(width as isize - 1) {
+                        let (sx, sy) = (sx as usize, sy as usize);
+                        if layout.color_at(sx, sy) == CfaChannel::Ir {
+                            sum += source[[sy, sx]].to_f64().unwrap();
+                            count += 1;
+                        }
+                    }
+                }
+                if count > 0 {
+                    return sum / count as f64;
+                }
+            }
+            0.0
+        };
+
+        let max = T::max_value().to_f64().unwrap();
+        for ((y, x), v) in self.data.indexed_iter_mut() {
+            let channel = layout.color_at(x, y);
+            let k = coefficients.coefficient(channel);
+            if !channel.is_visible() || k == 0.0 {
+                continue;
+            }
+            let corrected = v.to_f64().unwrap() - k * ir_at(x, y);
+            *v = T::from_f64_saturating(corrected.clamp(0.0, max));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::IrCoefficients;
+    use crate::cfa::{CfaChannel, CfaLayout};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 色毎に値域の異なるモザイク (R: 1000台, G: 2000台, B: 3000台, Ir: 4000台, White: 5000台, 下位は座標 x + y * 10)
+    fn labeled(layout: &CfaLayout, width: usize, height: usize) -> NDRaw<u16> {
+        let mut raw = NDRaw::<u16>::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                let channel = layout.color_at(x, y) as u16;
+                *raw.pix_mut(x, y) = (channel + 1) * 1000 + (x + y * 10) as u16;
+            }
+        }
+        raw
+    }
+
+    #[test]
+    fn test_rgbir_layout() {
+        println!("rgbir::test::test_rgbir_layout()  {{");
+
+        let layout = CfaLayout::rgbir();
+        assert_eq!((4, 4), layout.period());
+        let count = |c: CfaChannel| {
+            (0..16)
+                .filter(|i| layout.color_at(i % 4, i / 4) == c)
+                .count()
+        };
+        assert_eq!(
+            (2, 8, 2, 4),
+            (
+                count(CfaChannel::R),
+                count(CfaChannel::G),
+                count(CfaChannel::B),
+                count(CfaChannel::Ir)
+            )
+        );
+        for (x, y) in [(1, 1), (3, 1), (1, 3), (3, 3), (5, 7)] {
+            assert_eq!(CfaChannel::Ir, layout.color_at(x, y));
+        }
+        assert_eq!(CfaChannel::B, layout.color_at(0, 0));
+        assert_eq!(CfaChannel::R, layout.color_at(2, 0));
+        assert_eq!(CfaChannel::R, layout.color_at(0, 2));
+
+        // IR面 (1/2 x 1/2)
+        let raw = labeled(&layout, 8, 8);
+        let ir = raw.extract_ir_plane(&layout).unwrap();
+        println!("  [rgbir][test_rgbir_layout()] ir = \n{}", ir.data());
+        assert_eq!((4, 4), (ir.width(), ir.height()));
+        assert!(ir.data().iter().all(|v| *v / 1000 == 4));
+        assert_eq!(4000 + 3 + 50, *ir.pix(1, 2));
+        let stats = raw.compute_cfa_statistics(&layout);
+        assert_eq!(16, stats[CfaChannel::Ir].count);
+
+        // R面は等間隔格子でないため非対応
+        assert!(matches!(
+            raw.extract_cfa_plane(&layout, CfaChannel::R),
+            Err(SensorIoError::Unsupported(_))
+        ));
+
+        // RGBW (2x2にWを含むタイル)
+        use CfaChannel::{White as W, B, G, R};
+        let rgbw = CfaLayout::tile(2, 2, vec![R, G, W, B]).unwrap();
+        let raw = labeled(&rgbw, 4, 4);
+        let white = raw.extract_cfa_plane(&rgbw, W).unwrap();
+        assert_eq!(&[5010, 5012], white.data().row(0).as_slice().unwrap());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_subtract_ir_contamination() {
+        println!("rgbir::test::test_subtract_ir_contamination()  {{");
+
+        let layout = CfaLayout::rgbir();
+        // 可視光は全て1000, IRは200
+        let mut raw = NDRaw::<u16>::new(8, 8);
+        raw.map_cfa_channels(&layout, |c, _| if c == CfaChannel::Ir { 200 } else { 1000 });
+        let original = raw.clone();
+        let coefficients = IrCoefficients {
+            r: 1.0,
+            g: 0.5,
+            b: 2.0,
+            white: 0.0,
+        };
+        raw.subtract_ir_contamination(&layout, coefficients)
+            .unwrap();
+        println!(
+            "  [rgbir][test_subtract_ir_contamination()] raw = \n{}",
+            raw.data()
+        );
+        for y in 0..8 {
+            for x in 0..8 {
+                let expected = match layout.color_at(x, y) {
+                    CfaChannel::R => 800,
+                    CfaChannel::G => 900,
+                    CfaChannel::B => 600,
+                    _ => *original.pix(x, y),
+                };
+                assert_eq!(expected, *raw.pix(x, y));
+            }
+        }
+
+        // 負になる値は0にクランプ
+        let mut raw = original.clone();
+        let strong = IrCoefficients {
+            r: 10.0,
+            g: 0.0,
+            b: 0.0,
+            white: 0.0,
+        };
+        raw.subtract_ir_contamination(&layout, strong).unwrap();
+        assert_eq!(0, *raw.pix(2, 0));
+        assert_eq!(1000, *raw.pix(0, 0));
+
+        // IRのない配列はエラー
+        assert!(matches!(
+            original
+                .clone()
+                .subtract_ir_contamination(&CfaLayout::xtrans(), coefficients),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,156 @@
+use crate::binfmt;
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use byteorder::{ReadBytesExt, WriteBytesExt};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+
+// ランレングス符号化 (行優先, 連続する同じ値を(値, 個数)にまとめる)
+pub fn encode_run_length<T: PixelType>(img: &NDRaw<T>) -> Vec<(T, usize)> {
+    let mut runs: Vec<(T, usize)> = Vec::new();
+    for &v in img.data.iter() {
+        match runs.last_mut() {
+            Some((last, count)) if *last == v => *count += 1,
+            _ => runs.push((v, 1)),
+        }
+    }
+    runs
+}
+
+// ランレングス復号 (個数の合計がwidth * heightと一致しない場合はエラー)
+pub fn decode_run_length<T: PixelType>(
+    runs: &[(T, usize)],
+    width: usize,
+    height: usize,
+) -> Result<NDRaw<T>, SensorIoError> {
+    let total: usize = runs.iter().map(|(_, count)| count).sum();
+    if total != width * height {
+        return Err(SensorIoError::ShapeMismatch(format!(
+            "run length total {} != {}x{}",
+            total, width, height
+        )));
+    }
+    let pixels: Vec<T> = runs
+        .iter()
+        .flat_map(|&(v, count)| std::iter::repeat_n(v, count))
+        .collect();
+    let data = ndarray::Array2::from_shape_vec((height, width), pixels).unwrap();
+    Ok(NDRaw::from_ndarray(data))
+}
+
+// RLE bin画像フォーマット (全てLittle Endian)
+//   width(u16), height(u16), runs(u32), (value(u16), count(u32)) x runs
+impl<T: PixelType> NDRaw<T> {
+    // RLE bin画像書き込み
+    pub fn write_rle_binimage(&self, path_raw_out: String) -> Result<(), SensorIoError> {
+        let runs = encode_run_length(self);
+        let out_of_range = |name: &str, v: usize| {
+            SensorIoError::InvalidArgument(format!("{} {} out of rle header range", name, v))
+        };
+        let width = u16::try_from(self.width()).map_err(|_| out_of_range("width", self.width()))?;
+        let height =
+            u16::try_from(self.height()).map_err(|_| out_of_range("height", self.height()))?;
+        let num_runs = u32::try_from(runs.len()).map_err(|_| out_of_range("runs", runs.len()))?;
+
+        let mut f_write = BufWriter::new(File::create(path_raw_out)?);
+        f_write.write_u16::<byteorder::LittleEndian>(width)?;
+        f_write.write_u16::<byteorder::LittleEndian>(height)?;
+        f_write.write_u32::<byteorder::LittleEndian>(num_runs)?;
+        for (v, count) in runs {
+            let word = v.to_bin_word().ok_or_else(|| {
+                SensorIoError::InvalidArgument(format!("pixel value {} out of bin range", v))
+            })?;
+            let count = u32::try_from(count).map_err(|_| out_of_range("run length", count))?;
+            f_write.write_u16::<byteorder::LittleEndian>(word)?;
+            f_write.write_u32::<byteorder::LittleEndian>(count)?;
+        }
+        f_write.flush()?;
+        Ok(())
+    }
+
+    // RLE bin画像変換コンストラクタ
+    pub fn new_from_rle_binimage(path_raw_in: String) -> Result<Self, SensorIoError> {
+        let mut f_read = BufReader::new(File::open(path_raw_in)?);
+        let width = f_read.read_u16::<byteorder::LittleEndian>()? as usize;
+        let height = f_read.read_u16::<byteorder::LittleEndian>()? as usize;
+        let count = f_read.read_u32::<byteorder::LittleEndian>()? as usize;
+        // ラン数は画素数を超えない (壊れたヘッダで巨大な確保をしない)
+        let mut runs = Vec::with_capacity(count.min(width * height));
+        for _ in 0..count {
+            let v = binfmt::convert_pixel(f_read.read_u16::<byteorder::LittleEndian>()?)?;
+            runs.push((v, f_read.read_u32::<byteorder::LittleEndian>()? as usize));
+        }
+        decode_run_length(&runs, width, height)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_run_length, encode_run_length};
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_run_length() {
+        println!("rle::test::test_run_length()  {{");
+
+        // 市松模様 (連続しないので画素数と同じラン数)
+        let checker = NDRaw::<u16>::new_from_vector2d(
+            &(0..4)
+                .map(|y| (0..5).map(|x| ((x + y) % 2) as u16).collect())
+                .collect::<Vec<_>>(),
+        );
+        let runs = encode_run_length(&checker);
+        println!("  [rle][test_run_length()] runs = {:?}", runs);
+        assert_eq!(20, runs.len());
+        let decoded = decode_run_length(&runs, 5, 4).unwrap();
+        assert_eq!(checker.data(), decoded.data());
+
+        // 単一値は1ラン
+        let solid = NDRaw::<u16>::new_from_vector2d(&vec![vec![7; 6]; 3]);
+        assert_eq!(vec![(7, 18)], encode_run_length(&solid));
+
+        // 個数の不一致はエラー
+        assert!(matches!(
+            decode_run_length(&[(1u16, 5)], 2, 2),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rle_binimage() {
+        println!("rle::test::test_rle_binimage()  {{");
+
+        let mut raw = NDRaw::<u16>::new(32, 16);
+        *raw.pix_mut(3, 2) = 1;
+        *raw.pix_mut(30, 15) = 1;
+        let path = std::env::temp_dir().join(format!("sensor_io_rle_{}.bin", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+        raw.write_rle_binimage(path_str.clone()).unwrap();
+        let size = std::fs::metadata(&path).unwrap().len();
+        let loaded = NDRaw::<u16>::new_from_rle_binimage(path_str).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        println!("  [rle][test_rle_binimage()] file size = {}", size);
+        assert_eq!(raw.data(), loaded.data());
+        // 5ラン = 8 + 5 * 6 byte
+        assert_eq!(38, size);
+
+        // ヘッダに収まらないサイズはエラー
+        let wide = NDRaw::<u16>::new(70000, 1);
+        let result = wide.write_rle_binimage(String::from("write_rle_wide.bin"));
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        // 壊れたラン数 (u32::MAX) でも巨大な確保をせずIoエラー
+        let mut bytes = vec![2, 0, 2, 0];
+        bytes.extend_from_slice(&u32::MAX.to_le_bytes());
+        std::fs::write(&path, bytes).unwrap();
+        let result = NDRaw::<u16>::new_from_rle_binimage(path.to_str().unwrap().to_string());
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(SensorIoError::Io(_))));
+
+        println!("}}");
+    }
+}
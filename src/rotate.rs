@@ -0,0 +1,244 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+// 回転後のBayer配列 (R画素の移動先で決まる, Gr/GbはR行/B行に合わせて付け替え)
+//   rotate: 回転前の座標(x, y) => 回転後の座標
+fn rotated_pattern(
+    pattern: BayerPattern,
+    rotate: impl Fn(usize, usize) -> (usize, usize),
+) -> BayerPattern {
+    let (rx, ry) = pattern.offset(BayerChannel::R);
+    let (nx, ny) = rotate(rx, ry);
+    BayerPattern::ALL
+        .into_iter()
+        .find(|p| p.offset(BayerChannel::R) == (nx % 2, ny % 2))
+        .unwrap()
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 90度回転 (時計回り) => (回転画像, 回転後のBayer配列)
+    pub fn rotate90(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let height = self.height();
+        let data = self.data.t().slice(ndarray::s![.., ..;-1]).to_owned();
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NDRaw::from_ndarray(data), pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (height - 1 - y, x));
+        (NDRaw::from_ndarray(data), pattern)
+    }
+
+    // 180度回転 => (回転画像, 回転後のBayer配列)
+    pub fn rotate180(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let (width, height) = (self.width(), self.height());
+        let data = self.data.slice(ndarray::s![..;-1, ..;-1]).to_owned();
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NDRaw::from_ndarray(data), pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (width - 1 - x, height - 1 - y));
+        (NDRaw::from_ndarray(data), pattern)
+    }
+
+    // 270度回転 (反時計回りに90度) => (回転画像, 回転後のBayer配列)
+    pub fn rotate270(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let width = self.width();
+        let data = self.data.t().slice(ndarray::s![..;-1, ..]).to_owned();
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NDRaw::from_ndarray(data), pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (y, width - 1 - x));
+        (NDRaw::from_ndarray(data), pattern)
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // 90度回転 (時計回り) => (回転画像, 回転後のBayer配列)
+    pub fn rotate90(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let (width, height) = (self.width(), self.height());
+        let data = nalgebra::DMatrix::from_fn(width, height, |r, c| self.data[(height - 1 - c, r)]);
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NARaw { data }, pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (height - 1 - y, x));
+        (NARaw { data }, pattern)
+    }
+
+    // 180度回転 => (回転画像, 回転後のBayer配列)
+    pub fn rotate180(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let (width, height) = (self.width(), self.height());
+        let data = nalgebra::DMatrix::from_fn(height, width, |r, c| {
+            self.data[(height - 1 - r, width - 1 - c)]
+        });
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NARaw { data }, pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (width - 1 - x, height - 1 - y));
+        (NARaw { data }, pattern)
+    }
+
+    // 270度回転 (反時計回りに90度) => (回転画像, 回転後のBayer配列)
+    pub fn rotate270(&self, pattern: BayerPattern) -> (Self, BayerPattern) {
+        let (width, height) = (self.width(), self.height());
+        let data = nalgebra::DMatrix::from_fn(width, height, |r, c| self.data[(c, width - 1 - r)]);
+        // 空画像は座標計算せずに返す (Bayer配列はそのまま)
+        if data.is_empty() {
+            return (NARaw { data }, pattern);
+        }
+        let pattern = rotated_pattern(pattern, |x, y| (y, width - 1 - x));
+        (NARaw { data }, pattern)
+    }
+
+    // 任意角度回転 (時計回り, 入力画像の中心を出力画像の中心に合わせる)
+    //   warp_affineで出力画素を逆写像しバイリニア補間, 範囲外は0
+    pub fn rotate(&self, degrees: f64, out_w: usize, out_h: usize) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let center_in = (
+            (self.width() as f64 - 1.0) / 2.0,
+            (self.height() as f64 - 1.0) / 2.0,
+        );
+        let center_out = ((out_w as f64 - 1.0) / 2.0, (out_h as f64 - 1.0) / 2.0);
+        let tx = center_out.0 - (cos * center_in.0 - sin * center_in.1);
+        let ty = center_out.1 - (sin * center_in.0 + cos * center_in.1);
+        let matrix = nalgebra::Matrix2x3::new(cos, -sin, tx, sin, cos, ty);
+        self.warp_affine(matrix, out_w, out_h)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::BayerPattern;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_rotate_3x2() {
+        println!("rotate::test::test_rotate_3x2()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        let nd = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let na = NARaw::<u16>::new_from_vector2d(&vec2d);
+
+        let (nd90, pattern) = nd.rotate90(BayerPattern::Rggb);
+        println!("  [rotate][test_rotate_3x2()] rotate90 = \n{}", nd90.data());
+        assert_eq!(ndarray::arr2(&[[4, 1], [5, 2], [6, 3]]), nd90.data());
+        assert_eq!(BayerPattern::Grbg, pattern);
+        let (na90, pattern) = na.rotate90(BayerPattern::Rggb);
+        assert_eq!(nalgebra::dmatrix![4, 1; 5, 2; 6, 3], *na90.data());
+        assert_eq!(BayerPattern::Grbg, pattern);
+
+        let (nd180, pattern) = nd.rotate180(BayerPattern::Rggb);
+        assert_eq!(ndarray::arr2(&[[6, 5, 4], [3, 2, 1]]), nd180.data());
+        assert_eq!(BayerPattern::Gbrg, pattern);
+        let (na180, pattern) = na.rotate180(BayerPattern::Rggb);
+        assert_eq!(nalgebra::dmatrix![6, 5, 4; 3, 2, 1], *na180.data());
+        assert_eq!(BayerPattern::Gbrg, pattern);
+
+        let (nd270, pattern) = nd.rotate270(BayerPattern::Rggb);
+        assert_eq!(ndarray::arr2(&[[3, 6], [2, 5], [1, 4]]), nd270.data());
+        assert_eq!(BayerPattern::Rggb, pattern);
+        let (na270, pattern) = na.rotate270(BayerPattern::Rggb);
+        assert_eq!(nalgebra::dmatrix![3, 6; 2, 5; 1, 4], *na270.data());
+        assert_eq!(BayerPattern::Rggb, pattern);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rotate90_four_times() {
+        println!("rotate::test::test_rotate90_four_times()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| (0..6).map(|x| (y * 6 + x) as u16).collect())
+            .collect();
+        for pattern in BayerPattern::ALL {
+            let mut nd = (NDRaw::<u16>::new_from_vector2d(&vec2d), pattern);
+            let mut na = (NARaw::<u16>::new_from_vector2d(&vec2d), pattern);
+            for _ in 0..4 {
+                nd = nd.0.rotate90(nd.1);
+                na = na.0.rotate90(na.1);
+                // NDRaw/NARawで同じBayer配列
+                assert_eq!(nd.1, na.1);
+            }
+            assert_eq!(NDRaw::<u16>::new_from_vector2d(&vec2d).data(), nd.0.data());
+            assert_eq!(NARaw::<u16>::new_from_vector2d(&vec2d).data(), na.0.data());
+            assert_eq!(pattern, nd.1);
+            assert_eq!(pattern, na.1);
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rotate_empty() {
+        println!("rotate::test::test_rotate_empty()  {{");
+
+        let nd = NDRaw::<u16>::new(0, 3);
+        let na = NARaw::<u16>::new(3, 0);
+        let (nd90, pattern) = nd.rotate90(BayerPattern::Rggb);
+        println!(
+            "  [rotate][test_rotate_empty()] rotate90 shape = {:?}",
+            nd90.shape()
+        );
+        assert_eq!((3, 0), (nd90.width(), nd90.height()));
+        assert_eq!(BayerPattern::Rggb, pattern);
+        assert_eq!(
+            (0, 3),
+            (
+                nd.rotate180(pattern).0.width(),
+                nd.rotate180(pattern).0.height()
+            )
+        );
+        assert_eq!(3, nd.rotate270(pattern).0.width());
+        assert_eq!(
+            (0, 3),
+            (
+                na.rotate90(pattern).0.width(),
+                na.rotate90(pattern).0.height()
+            )
+        );
+        assert_eq!(3, na.rotate180(pattern).0.width());
+        assert_eq!(0, na.rotate270(pattern).0.width());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rotate_arbitrary() {
+        println!("rotate::test::test_rotate_arbitrary()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..4)
+            .map(|y| (0..6).map(|x| (y * 6 + x) as u16 * 10).collect())
+            .collect();
+        let na = NARaw::<u16>::new_from_vector2d(&vec2d);
+
+        // 0度は恒等
+        assert_eq!(na.data(), na.rotate(0.0, 6, 4).data());
+
+        // 90度はrotate90と一致
+        let rotated = na.rotate(90.0, 4, 6);
+        println!(
+            "  [rotate][test_rotate_arbitrary()] rotate(90) = {}",
+            rotated.data()
+        );
+        assert_eq!(na.rotate90(BayerPattern::Rggb).0.data(), rotated.data());
+        assert_eq!(
+            na.rotate270(BayerPattern::Rggb).0.data(),
+            na.rotate(-90.0, 4, 6).data()
+        );
+
+        // 45度: 中心は保たれ, 四隅は範囲外で0
+        let na = NARaw::<f32>::new_from_vector2d(&vec![vec![100.0; 5]; 5]);
+        let rotated = na.rotate(45.0, 5, 5);
+        assert_eq!(100.0, *rotated.pix(2, 2));
+        assert_eq!(0.0, *rotated.pix(0, 0));
+        assert_eq!(0.0, *rotated.pix(4, 4));
+
+        println!("}}");
+    }
+}
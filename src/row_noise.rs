@@ -0,0 +1,187 @@
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use std::ops::Range;
+
+// 補正後のOB(オプティカルブラック)列の扱い
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ObColumns {
+    // 補正せずそのまま残す
+    Keep,
+    // 画像から取り除く
+    Remove,
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // OB列による行ノイズ補正
+    //   各行のOB列平均(smoothing行の移動平均)を行オフセットとして減算し0でクランプ
+    pub fn correct_row_noise(
+        &mut self,
+        ob_columns: Range<usize>,
+        smoothing: usize,
+        ob: ObColumns,
+    ) -> Result<(), SensorIoError> {
+        if ob_columns.is_empty() || ob_columns.end > self.width() {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "OB columns {:?} are empty or exceed width {}",
+                ob_columns,
+                self.width()
+            )));
+        }
+
+        let offsets = smooth(&self.ob_row_means(ob_columns.clone()), smoothing.max(1));
+        for ((y, x), pix) in self.data.indexed_iter_mut() {
+            if ob_columns.contains(&x) {
+                continue;
+            }
+            let v = pix.to_f64().unwrap() - offsets[y];
+            *pix = T::from_f64_saturating(v.max(0.0));
+        }
+
+        if ob == ObColumns::Remove {
+            let keep: Vec<usize> = (0..self.width())
+                .filter(|x| !ob_columns.contains(x))
+                .collect();
+            self.data = self.data.select(ndarray::Axis(1), &keep);
+        }
+        Ok(())
+    }
+
+    // 行毎のOB列平均
+    fn ob_row_means(&self, ob_columns: Range<usize>) -> Vec<f64> {
+        self.data
+            .slice(ndarray::s![.., ob_columns])
+            .rows()
+            .into_iter()
+            .map(|row| row.iter().map(|p| p.to_f64().unwrap()).sum::<f64>() / row.len() as f64)
+            .collect()
+    }
+}
+
+// 移動平均 (窓は注目行を中心にwindow行, 端では画像内の行のみ)
+fn smooth(values: &[f64], window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let start = i.saturating_sub(window / 2);
+            let end = (start + window).min(values.len());
+            let lane = &values[start..end];
+            lane.iter().sum::<f64>() / lane.len() as f64
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::ObColumns;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    const OB: usize = 4;
+
+    // 行オフセット(黒レベル64 + 行毎の揺らぎ)を加えた画像
+    //   ob_noise: OB画素に加える±1の市松ノイズ
+    fn banded_image(ob_noise: bool) -> (NDRaw<u16>, NDRaw<u16>) {
+        let (width, height) = (24, 16);
+        let signal: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| ((x * 13 + y * 7) % 50) as u16 * 10)
+                    .collect()
+            })
+            .collect();
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                let offset = 64 + ((y * 37) % 23) as u16;
+                (0..width)
+                    .map(|x| {
+                        if x < OB {
+                            let noise = if ob_noise && (x + y) % 2 == 0 { 2 } else { 0 };
+                            offset - 1 + noise
+                        } else {
+                            offset + signal[y][x]
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        (
+            NDRaw::<u16>::new_from_vector2d(&vec2d),
+            NDRaw::<u16>::new_from_vector2d(&signal),
+        )
+    }
+
+    #[test]
+    fn test_correct_row_noise() {
+        println!("row_noise::test::test_correct_row_noise()  {{");
+
+        let (mut raw, signal) = banded_image(true);
+        let ob_before: Vec<u16> = raw.data().column(0).to_vec();
+        raw.correct_row_noise(0..OB, 1, ObColumns::Keep).unwrap();
+        println!(
+            "  [row_noise][test_correct_row_noise()] raw = \n{}",
+            raw.data()
+        );
+        for y in 0..raw.height() {
+            for x in OB..raw.width() {
+                let diff = *raw.pix(x, y) as i32 - *signal.pix(x, y) as i32;
+                assert!(diff.abs() <= 1, "({}, {}) diff = {}", x, y, diff);
+            }
+        }
+        assert_eq!(ob_before, raw.data().column(0).to_vec());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_correct_row_noise_remove_ob() {
+        println!("row_noise::test::test_correct_row_noise_remove_ob()  {{");
+
+        let (mut raw, signal) = banded_image(false);
+        raw.correct_row_noise(0..OB, 1, ObColumns::Remove).unwrap();
+        assert_eq!(24 - OB, raw.width());
+        for y in 0..raw.height() {
+            for x in 0..raw.width() {
+                let diff = *raw.pix(x, y) as i32 - *signal.pix(x + OB, y) as i32;
+                assert!(diff.abs() <= 1, "({}, {}) diff = {}", x, y, diff);
+            }
+        }
+
+        let result = raw.correct_row_noise(18..22, 1, ObColumns::Keep);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_correct_row_noise_smoothing() {
+        println!("row_noise::test::test_correct_row_noise_smoothing()  {{");
+
+        // 行オフセット一定・OBのみノイズ -> 平滑化で行間の段差が減る
+        let vec2d: Vec<Vec<u16>> = (0..16)
+            .map(|y| {
+                (0..12)
+                    .map(|x| match x {
+                        0 => 100 + (y % 2) as u16 * 6,
+                        _ => 600,
+                    })
+                    .collect()
+            })
+            .collect();
+        let spread = |smoothing: usize| {
+            let mut raw = NDRaw::<u16>::new_from_vector2d(&vec2d);
+            raw.correct_row_noise(0..1, smoothing, ObColumns::Remove)
+                .unwrap();
+            let column: Vec<u16> = raw.data().column(0).to_vec();
+            column.iter().max().unwrap() - column.iter().min().unwrap()
+        };
+        println!(
+            "  [row_noise][test_correct_row_noise_smoothing()] spread = {} -> {}",
+            spread(1),
+            spread(4)
+        );
+        assert_eq!(6, spread(1));
+        assert!(spread(4) <= 2);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,125 @@
+use crate::bayer::BayerPattern;
+use crate::error::SensorIoError;
+use crate::headerless::Endianness;
+use std::path::Path;
+
+// 画素フォーマット (NDRaw<T>の型パラメータに対応)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum PixelFormatTag {
+    U8,
+    U16,
+    I16,
+    F32,
+    F64,
+}
+
+// センサ構成 (カメラ機種毎のTOML設定ファイル)
+//   width = 1920
+//   height = 1080
+//   pixel_format = "U16"
+//   bayer_pattern = "Rggb"  (省略可)
+//   pixel_pitch_um = 2.9    (省略可)
+//   binning = 1
+//   endianness = "Little"
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SensorConfig {
+    pub width: usize,
+    pub height: usize,
+    pub pixel_format: PixelFormatTag,
+    pub bayer_pattern: Option<BayerPattern>,
+    // 画素ピッチ [um]
+    pub pixel_pitch_um: Option<f32>,
+    pub binning: u8,
+    pub endianness: Endianness,
+}
+
+impl TryFrom<&str> for SensorConfig {
+    type Error = SensorIoError;
+
+    // TOML文字列からの変換 (不正な値・欠けたキーはParseエラー)
+    fn try_from(toml_str: &str) -> Result<Self, Self::Error> {
+        toml::from_str(toml_str).map_err(|e| SensorIoError::Parse(e.to_string()))
+    }
+}
+
+impl SensorConfig {
+    // TOMLファイル読み込み
+    pub fn from_toml_file(path: impl AsRef<Path>) -> Result<Self, SensorIoError> {
+        let toml_str = std::fs::read_to_string(path)?;
+        SensorConfig::try_from(toml_str.as_str())
+    }
+
+    // TOMLファイル書き込み
+    pub fn to_toml_file(&self, path: impl AsRef<Path>) -> Result<(), SensorIoError> {
+        let toml_str =
+            toml::to_string(self).map_err(|e| SensorIoError::InvalidArgument(e.to_string()))?;
+        std::fs::write(path, toml_str)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PixelFormatTag, SensorConfig};
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::headerless::Endianness;
+
+    #[test]
+    fn test_toml_round_trip() {
+        println!("sensor_config::test::test_toml_round_trip()  {{");
+
+        let config = SensorConfig {
+            width: 1920,
+            height: 1080,
+            pixel_format: PixelFormatTag::U16,
+            bayer_pattern: Some(BayerPattern::Gbrg),
+            pixel_pitch_um: Some(2.9),
+            binning: 2,
+            endianness: Endianness::Big,
+        };
+        let path = std::env::temp_dir().join(format!(
+            "sensor_io_sensor_config_{}.toml",
+            std::process::id()
+        ));
+        config.to_toml_file(&path).unwrap();
+        println!(
+            "  [sensor_config][test_toml_round_trip()] toml = \n{}",
+            std::fs::read_to_string(&path).unwrap()
+        );
+        let restored = SensorConfig::from_toml_file(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(config, restored);
+
+        // 省略可能な項目なし
+        let mono = SensorConfig {
+            bayer_pattern: None,
+            pixel_pitch_um: None,
+            ..config
+        };
+        let toml_str = toml::to_string(&mono).unwrap();
+        assert_eq!(mono, SensorConfig::try_from(toml_str.as_str()).unwrap());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_invalid_pixel_format() {
+        println!("sensor_config::test::test_invalid_pixel_format()  {{");
+
+        let toml_str = "width = 640\nheight = 480\npixel_format = \"U12\"\nbinning = 1\nendianness = \"Little\"\n";
+        let result = SensorConfig::try_from(toml_str);
+        println!(
+            "  [sensor_config][test_invalid_pixel_format()] result = {:?}",
+            result
+        );
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        let valid = toml_str.replace("U12", "U8");
+        let config = SensorConfig::try_from(valid.as_str()).unwrap();
+        assert_eq!(PixelFormatTag::U8, config.pixel_format);
+        assert_eq!(None, config.bayer_pattern);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,543 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use nalgebra;
+use ndarray;
+
+// シェーディング補正ゲインマップ (CFAチャネル毎の粗い格子, 画像全体に等間隔配置)
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ShadingMap {
+    pattern: BayerPattern,
+    // [R, Gr, Gb, B] 各 (grid_height, grid_width)
+    gains: [ndarray::Array2<f32>; 4],
+}
+
+impl ShadingMap {
+    pub const DEFAULT_GRID_WIDTH: usize = 17;
+    pub const DEFAULT_GRID_HEIGHT: usize = 13;
+
+    // ゲイン格子指定コンストラクタ ([R, Gr, Gb, B]の順, 全て同形状・2x2以上)
+    pub fn new(
+        pattern: BayerPattern,
+        gains: [ndarray::Array2<f32>; 4],
+    ) -> Result<Self, SensorIoError> {
+        let dim = gains[0].dim();
+        if dim.0 < 2 || dim.1 < 2 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "gain grid must be at least 2x2, got {}x{}",
+                dim.1, dim.0
+            )));
+        }
+        if gains.iter().any(|g| g.dim() != dim) {
+            return Err(SensorIoError::ShapeMismatch(
+                "all channel gain grids must have the same size".to_string(),
+            ));
+        }
+        Ok(ShadingMap { pattern, gains })
+    }
+
+    // 全チャネル一様ゲイン
+    pub fn uniform(
+        pattern: BayerPattern,
+        grid_width: usize,
+        grid_height: usize,
+        gain: f32,
+    ) -> Result<Self, SensorIoError> {
+        let grid = ndarray::Array2::<f32>::from_elem((grid_height, grid_width), gain);
+        Self::new(pattern, [grid.clone(), grid.clone(), grid.clone(), grid])
+    }
+
+    // フラットフィールドからゲインマップ生成 (既定格子サイズ)
+    pub fn from_flat_field<T: PixelType>(
+        flat: &NDRaw<T>,
+        pattern: BayerPattern,
+        target: f64,
+    ) -> Result<Self, SensorIoError> {
+        Self::from_flat_field_with_grid(
+            flat,
+            pattern,
+            target,
+            Self::DEFAULT_GRID_WIDTH,
+            Self::DEFAULT_GRID_HEIGHT,
+        )
+    }
+
+    // フラットフィールドからゲインマップ生成 (格子点周辺の同色画素平均をtargetに合わせる)
+    pub fn from_flat_field_with_grid<T: PixelType>(
+        flat: &NDRaw<T>,
+        pattern: BayerPattern,
+        target: f64,
+        grid_width: usize,
+        grid_height: usize,
+    ) -> Result<Self, SensorIoError> {
+        if grid_width < 2 || grid_height < 2 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "gain grid must be at least 2x2, got {}x{}",
+                grid_width, grid_height
+            )));
+        }
+        if flat.width() < 2 || flat.height() < 2 {
+            return Err(SensorIoError::InvalidArgument(
+                "flat field must be at least 2x2".to_string(),
+            ));
+        }
+        if target <= 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "target must be positive, got {}",
+                target
+            )));
+        }
+
+        let (width, height) = (flat.width(), flat.height());
+        let step_x = (width - 1) as f64 / (grid_width - 1) as f64;
+        let step_y = (height - 1) as f64 / (grid_height - 1) as f64;
+        // 端の格子点でも各チャネルが2行2列以上含まれる窓サイズ
+        let radius_x = ((step_x / 2.0).ceil() as usize).max(3);
+        let radius_y = ((step_y / 2.0).ceil() as usize).max(3);
+
+        let gains = BayerChannel::ALL.map(|channel| {
+            ndarray::Array2::<f32>::from_shape_fn((grid_height, grid_width), |(gy, gx)| {
+                let cx = gx as f64 * step_x;
+                let cy = gy as f64 * step_y;
+                let mean =
+                    Self::local_plane_mean(flat, pattern, channel, cx, cy, radius_x, radius_y);
+                if mean > 0.0 {
+                    (target / mean) as f32
+                } else {
+                    1.0
+                }
+            })
+        });
+
+        Ok(ShadingMap { pattern, gains })
+    }
+
+    // 格子点周辺の同色画素に平面を最小二乗フィットし格子点での値を返す (端でも偏らない)
+    fn local_plane_mean<T: PixelType>(
+        flat: &NDRaw<T>,
+        pattern: BayerPattern,
+        channel: BayerChannel,
+        cx: f64,
+        cy: f64,
+        radius_x: usize,
+        radius_y: usize,
+    ) -> f64 {
+        let (ix, iy) = (cx.round() as usize, cy.round() as usize);
+        let mut ata = nalgebra::Matrix3::<f64>::zeros();
+        let mut atb = nalgebra::Vector3::<f64>::zeros();
+        let mut sum = 0.0;
+        let mut count = 0usize;
+        for y in iy.saturating_sub(radius_y)..(iy + radius_y + 1).min(flat.height()) {
+            for x in ix.saturating_sub(radius_x)..(ix + radius_x + 1).min(flat.width()) {
+                if pattern.channel_at(x, y) != channel {
+                    continue;
+                }
+                let v = flat.data[[y, x]].to_f64().unwrap();
+                let row = nalgebra::Vector3::new(1.0, x as f64 - cx, y as f64 - cy);
+                ata += row * row.transpose();
+                atb += row * v;
+                sum += v;
+                count += 1;
+            }
+        }
+        match ata.try_inverse() {
+            Some(inverse) => (inverse * atb)[0],
+            None => sum / count.max(1) as f64,
+        }
+    }
+
+    // Bayer配列取得
+    pub fn pattern(&self) -> BayerPattern {
+        self.pattern
+    }
+
+    // 格子サイズ取得
+    pub fn grid_width(&self) -> usize {
+        self.gains[0].ncols()
+    }
+    pub fn grid_height(&self) -> usize {
+        self.gains[0].nrows()
+    }
+
+    // チャネル毎のゲイン格子取得
+    pub fn gains(&self, channel: BayerChannel) -> &ndarray::Array2<f32> {
+        &self.gains[channel as usize]
+    }
+
+    // 画素(x, y)のゲイン取得 (width x heightの画像に対し格子をバイリニア補間)
+    pub fn gain_at(&self, x: usize, y: usize, width: usize, height: usize) -> f32 {
+        let grid = self.gains(self.pattern.channel_at(x, y));
+        let gx = Self::grid_coord(x, width, self.grid_width());
+        let gy = Self::grid_coord(y, height, self.grid_height());
+        let x0 = (gx.floor() as usize).min(self.grid_width() - 2);
+        let y0 = (gy.floor() as usize).min(self.grid_height() - 2);
+        let fx = gx - x0 as f32;
+        let fy = gy - y0 as f32;
+        let top = grid[[y0, x0]] * (1.0 - fx) + grid[[y0, x0 + 1]] * fx;
+        let bottom = grid[[y0 + 1, x0]] * (1.0 - fx) + grid[[y0 + 1, x0 + 1]] * fx;
+        top * (1.0 - fy) + bottom * fy
+    }
+
+    fn grid_coord(pos: usize, size: usize, grid_size: usize) -> f32 {
+        if size <= 1 {
+            return 0.0;
+        }
+        pos as f32 * (grid_size - 1) as f32 / (size - 1) as f32
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // シェーディング補正 (white_levelでクランプ)
+    pub fn apply_shading_correction(&mut self, gain_map: &ShadingMap, white_level: T) {
+        let (width, height) = (self.width(), self.height());
+        let white_level = white_level.to_f64().unwrap();
+        for ((y, x), pix) in self.data.indexed_iter_mut() {
+            let gain = gain_map.gain_at(x, y, width, height) as f64;
+            let v = pix.to_f64().unwrap() * gain;
+            *pix = T::from_f64_saturating(v.min(white_level));
+        }
+    }
+
+    // 画素毎のゲイン・オフセットによるフラット補正 ((pixel - offset) * gainをf32で計算し型の範囲に飽和)
+    //   引数はgain, offsetの順 (自身を含め全て同じサイズであること)
+    //   フラット画像(平均で正規化)からの補正はapply_flat_field (名前が衝突するためこちらはapply_gain_offset)
+    pub fn apply_gain_offset(
+        &self,
+        gain: &NDRaw<f32>,
+        offset: &Self,
+    ) -> Result<Self, SensorIoError> {
+        check_shape((self.width(), self.height()), (gain.width(), gain.height()))?;
+        check_shape(
+            (self.width(), self.height()),
+            (offset.width(), offset.height()),
+        )?;
+
+        let data = ndarray::Array2::from_shape_fn(self.data.dim(), |(y, x)| {
+            let v = (self.data[[y, x]].to_f32().unwrap() - offset.data[[y, x]].to_f32().unwrap())
+                * gain.data[[y, x]];
+            T::from_f64_saturating(v as f64)
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+// 放射状シェーディングモデル
+//   gain = 1 + a*r^2 + b*r^4 + c*r^6
+//   rは光学中心から最も遠い画像隅までの距離で正規化 (隅でr = 1)
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RadialShading {
+    // 光学中心(x, y)
+    pub center: (f64, f64),
+    // [a, b, c]
+    pub coeffs: [f64; 3],
+}
+
+// 放射状モデルのフィット方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RadialFitMode {
+    // チャネル毎にフィットし係数を平均 (チャネル感度差の影響を受けない)
+    PerChannel,
+    // 2x2毎の平均(輝度)でフィット
+    Luma,
+}
+
+impl RadialShading {
+    pub fn new(center: (f64, f64), coeffs: [f64; 3]) -> Self {
+        RadialShading { center, coeffs }
+    }
+
+    // 画素(x, y)のゲイン取得
+    pub fn gain(&self, x: f64, y: f64, width: usize, height: usize) -> f64 {
+        let r2 = self.normalized_radius2(x, y, width, height);
+        let [a, b, c] = self.coeffs;
+        1.0 + a * r2 + b * r2 * r2 + c * r2 * r2 * r2
+    }
+
+    // シェーディング補正 (white_levelでクランプ)
+    pub fn apply<T: PixelType>(&self, raw: &mut NDRaw<T>, white_level: T) {
+        let (width, height) = (raw.width(), raw.height());
+        let white_level = white_level.to_f64().unwrap();
+        for ((y, x), pix) in raw.data.indexed_iter_mut() {
+            let v = pix.to_f64().unwrap() * self.gain(x as f64, y as f64, width, height);
+            *pix = T::from_f64_saturating(v.min(white_level));
+        }
+    }
+
+    // フラットフィールドからフィット (光学中心は画像中心)
+    pub fn fit<T: PixelType>(
+        flat: &NDRaw<T>,
+        pattern: BayerPattern,
+        mode: RadialFitMode,
+    ) -> Result<Self, SensorIoError> {
+        let center = (
+            (flat.width() as f64 - 1.0) / 2.0,
+            (flat.height() as f64 - 1.0) / 2.0,
+        );
+        Self::fit_with_center(flat, pattern, center, mode)
+    }
+
+    // フラットフィールドからフィット (光学中心指定)
+    //   1/v = k * gain(r) を最小二乗で解き, 係数をkで正規化する
+    pub fn fit_with_center<T: PixelType>(
+        flat: &NDRaw<T>,
+        pattern: BayerPattern,
+        center: (f64, f64),
+        mode: RadialFitMode,
+    ) -> Result<Self, SensorIoError> {
+        if flat.width() < 2 || flat.height() < 2 {
+            return Err(SensorIoError::InvalidArgument(
+                "flat field must be at least 2x2".to_string(),
+            ));
+        }
+        let model = RadialShading::new(center, [0.0; 3]);
+        let (width, height) = (flat.width(), flat.height());
+
+        let coeffs = match mode {
+            RadialFitMode::PerChannel => {
+                let mut sum = [0.0; 3];
+                for channel in BayerChannel::ALL {
+                    let samples = flat
+                        .data
+                        .indexed_iter()
+                        .filter(|((y, x), _)| pattern.channel_at(*x, *y) == channel)
+                        .map(|((y, x), pix)| {
+                            let r2 = model.normalized_radius2(x as f64, y as f64, width, height);
+                            (r2, pix.to_f64().unwrap())
+                        });
+                    let c = Self::fit_samples(samples)?;
+                    for i in 0..3 {
+                        sum[i] += c[i] / 4.0;
+                    }
+                }
+                sum
+            }
+            RadialFitMode::Luma => {
+                let samples = (0..height / 2).flat_map(|qy| {
+                    (0..width / 2).map(move |qx| {
+                        let (x, y) = (qx * 2, qy * 2);
+                        let luma = (flat.data[[y, x]].to_f64().unwrap()
+                            + flat.data[[y, x + 1]].to_f64().unwrap()
+                            + flat.data[[y + 1, x]].to_f64().unwrap()
+                            + flat.data[[y + 1, x + 1]].to_f64().unwrap())
+                            / 4.0;
+                        let r2 =
+                            model.normalized_radius2(x as f64 + 0.5, y as f64 + 0.5, width, height);
+                        (r2, luma)
+                    })
+                });
+                Self::fit_samples(samples)?
+            }
+        };
+
+        Ok(RadialShading { center, coeffs })
+    }
+
+    // (r^2, 画素値)の組から係数を推定
+    fn fit_samples(samples: impl Iterator<Item = (f64, f64)>) -> Result<[f64; 3], SensorIoError> {
+        let mut ata = nalgebra::Matrix4::<f64>::zeros();
+        let mut atb = nalgebra::Vector4::<f64>::zeros();
+        for (r2, v) in samples {
+            if v <= 0.0 {
+                continue;
+            }
+            let row = nalgebra::Vector4::new(1.0, r2, r2 * r2, r2 * r2 * r2);
+            ata += row * row.transpose();
+            atb += row * (1.0 / v);
+        }
+        let solution = ata
+            .try_inverse()
+            .map(|inverse| inverse * atb)
+            .ok_or_else(|| {
+                SensorIoError::InvalidArgument(
+                    "not enough samples to fit radial shading".to_string(),
+                )
+            })?;
+        let k = solution[0];
+        Ok([solution[1] / k, solution[2] / k, solution[3] / k])
+    }
+
+    fn normalized_radius2(&self, x: f64, y: f64, width: usize, height: usize) -> f64 {
+        let (cx, cy) = self.center;
+        let max_x = (width as f64 - 1.0).max(0.0);
+        let max_y = (height as f64 - 1.0).max(0.0);
+        let max_r2 = [(0.0, 0.0), (max_x, 0.0), (0.0, max_y), (max_x, max_y)]
+            .iter()
+            .map(|(px, py)| (px - cx).powi(2) + (py - cy).powi(2))
+            .fold(0.0, f64::max);
+        if max_r2 == 0.0 {
+            return 0.0;
+        }
+        ((x - cx).powi(2) + (y - cy).powi(2)) / max_r2
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RadialFitMode, RadialShading, ShadingMap};
+    use crate::bayer::BayerPattern;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    // 中心0, 四隅1の正規化半径の2乗
+    fn radius2(x: f64, y: f64, width: usize, height: usize) -> f64 {
+        let cx = (width - 1) as f64 / 2.0;
+        let cy = (height - 1) as f64 / 2.0;
+        ((x - cx).powi(2) + (y - cy).powi(2)) / (cx * cx + cy * cy)
+    }
+
+    #[test]
+    fn test_apply_radial_map() {
+        println!("shading::test::test_apply_radial_map()  {{");
+
+        let (grid_w, grid_h) = (5, 5);
+        let grid = ndarray::Array2::<f32>::from_shape_fn((grid_h, grid_w), |(gy, gx)| {
+            1.0 + 0.5 * radius2(gx as f64, gy as f64, grid_w, grid_h) as f32
+        });
+        let map = ShadingMap::new(
+            BayerPattern::Rggb,
+            [grid.clone(), grid.clone(), grid.clone(), grid],
+        )
+        .unwrap();
+
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![1000; 16]; 12]);
+        raw_in.apply_shading_correction(&map, 4095);
+        println!(
+            "  [shading][test_apply_radial_map()] raw_in.data() = \n{}",
+            raw_in.data()
+        );
+        assert_eq!(1500, *raw_in.pix(0, 0));
+        assert_eq!(1500, *raw_in.pix(15, 11));
+        assert!(*raw_in.pix(0, 0) > *raw_in.pix(8, 6));
+        assert!(*raw_in.pix(8, 6) < 1050);
+
+        // white_levelでクランプ
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![1000; 16]; 12]);
+        raw_in.apply_shading_correction(&map, 1200);
+        assert_eq!(1200, *raw_in.pix(0, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_flat_field_roundtrip() {
+        println!("shading::test::test_flat_field_roundtrip()  {{");
+
+        // 周辺減光 + チャネル感度差のあるフラットフィールド
+        let (width, height) = (64, 48);
+        let pattern = BayerPattern::Grbg;
+        let channel_scale = [0.5, 1.0, 0.98, 0.8];
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let scale = channel_scale[pattern.channel_at(x, y) as usize];
+                        let falloff = 1.0 - 0.4 * radius2(x as f64, y as f64, width, height);
+                        (2000.0 * scale * falloff).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec2d);
+
+        let map = ShadingMap::from_flat_field(&flat, pattern, 2000.0).unwrap();
+        let mut corrected = flat.clone();
+        corrected.apply_shading_correction(&map, u16::MAX);
+        let stats = corrected.compute_statistics();
+        println!(
+            "  [shading][test_flat_field_roundtrip()] stats = {:?}",
+            stats
+        );
+        assert!((stats.mean - 2000.0).abs() < 20.0);
+        assert!(stats.min > 1960.0 && stats.max < 2040.0);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_radial_fit_recovers_coeffs() {
+        println!("shading::test::test_radial_fit_recovers_coeffs()  {{");
+
+        let (width, height) = (96, 64);
+        let pattern = BayerPattern::Rggb;
+        let truth = RadialShading::new((47.5, 31.5), [0.3, 0.1, 0.05]);
+        let channel_scale = [0.6, 1.0, 1.0, 0.8];
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| {
+                        let scale = channel_scale[pattern.channel_at(x, y) as usize];
+                        let gain = truth.gain(x as f64, y as f64, width, height);
+                        (4000.0 * scale / gain).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let flat = NDRaw::<u16>::new_from_vector2d(&vec2d);
+
+        for mode in [RadialFitMode::PerChannel, RadialFitMode::Luma] {
+            let fitted = RadialShading::fit(&flat, pattern, mode).unwrap();
+            println!(
+                "  [shading][test_radial_fit_recovers_coeffs()] {:?} => {:?}",
+                mode, fitted.coeffs
+            );
+            for (c, t) in fitted.coeffs.iter().zip(truth.coeffs.iter()) {
+                assert!((c - t).abs() < 0.02);
+            }
+        }
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_radial_apply_saturates() {
+        println!("shading::test::test_radial_apply_saturates()  {{");
+
+        let model = RadialShading::new((7.5, 5.5), [1.0, 0.0, 0.0]);
+        let mut raw_in = NDRaw::<u16>::new_from_vector2d(&vec![vec![3000; 16]; 12]);
+        model.apply(&mut raw_in, 4095);
+        println!(
+            "  [shading][test_radial_apply_saturates()] raw_in.data() = \n{}",
+            raw_in.data()
+        );
+        // 隅はゲイン2.0 => white_levelでクランプ
+        assert_eq!(4095, *raw_in.pix(0, 0));
+        assert_eq!(4095, *raw_in.pix(15, 11));
+        assert!(*raw_in.pix(7, 5) < 3030);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_apply_gain_offset() {
+        println!("shading::test::test_apply_gain_offset()  {{");
+
+        let raw_in =
+            NDRaw::<u16>::new_from_vector2d(&[vec![0, 100, 30000], vec![32767, 40000, 65535]]);
+        let gain = NDRaw::<f32>::new_from_vector2d(&vec![vec![2.0; 3]; 2]);
+        let offset = NDRaw::<u16>::new(3, 2);
+        let raw_out = raw_in.apply_gain_offset(&gain, &offset).unwrap();
+        println!(
+            "  [shading][test_apply_gain_offset()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert_eq!(
+            vec![0, 200, 60000, 65534, 65535, 65535],
+            raw_out.data().iter().copied().collect::<Vec<u16>>()
+        );
+
+        // オフセット超過分は0に飽和
+        let offset = NDRaw::<u16>::new_from_vector2d(&vec![vec![50; 3]; 2]);
+        let raw_out = raw_in.apply_gain_offset(&gain, &offset).unwrap();
+        assert_eq!(0, *raw_out.pix(0, 0));
+        assert_eq!(100, *raw_out.pix(1, 0));
+
+        // gain・offsetのどちらのサイズ不一致もエラー
+        let result = raw_in.apply_gain_offset(&NDRaw::<f32>::new(2, 2), &offset);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+        let result = raw_in.apply_gain_offset(&gain, &NDRaw::<u16>::new(2, 2));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
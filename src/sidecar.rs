@@ -0,0 +1,105 @@
+use crate::error::SensorIoError;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+
+// 撮影メタデータ(露光時間・ゲイン・タイムスタンプ等)のJSONサイドカー
+//   NDRawのメタデータをbin画像とは別の<path>.jsonに保存する (画素フォーマットは変更しない)
+
+// サイドカーファイルのパス (<path>.json)
+pub(crate) fn sidecar_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut path = path.as_ref().as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+// サイドカー読み込み (ファイルがなければ空)
+pub(crate) fn read_sidecar(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<String, String>, SensorIoError> {
+    let path = sidecar_path(path);
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let f_read = BufReader::new(File::open(&path)?);
+    serde_json::from_reader(f_read)
+        .map_err(|e| SensorIoError::Parse(format!("{}: {}", path.display(), e)))
+}
+
+// サイドカー書き込み (メタデータが空でも{}として書き込む)
+pub(crate) fn write_sidecar(
+    path: impl AsRef<Path>,
+    metadata: &HashMap<String, String>,
+) -> Result<(), SensorIoError> {
+    let path = sidecar_path(path);
+    // キー順を固定して出力
+    let sorted: std::collections::BTreeMap<_, _> = metadata.iter().collect();
+    let f_write = BufWriter::new(File::create(&path)?);
+    serde_json::to_writer_pretty(f_write, &sorted)
+        .map_err(|e| SensorIoError::Parse(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_metadata_sidecar() {
+        println!("sidecar::test::test_metadata_sidecar()  {{");
+
+        let path =
+            std::env::temp_dir().join(format!("sensor_io_sidecar_{}.bin", std::process::id()));
+        let path_str = path.to_str().unwrap().to_string();
+        let path_json = format!("{}.json", path_str);
+
+        let mut raw = NDRaw::<u16>::new_from_vector2d(&[vec![1, 2], vec![3, 4]]);
+        assert!(raw.metadata().is_empty());
+        raw.set_metadata("exposure_us", "10000");
+        raw.set_metadata("gain", "2.0");
+        raw.set_metadata("timestamp", "2024-01-01T00:00:00Z");
+        raw.write_binimage(path_str.clone()).unwrap();
+        println!(
+            "  [sidecar][test_metadata_sidecar()] json = {}",
+            std::fs::read_to_string(&path_json).unwrap()
+        );
+
+        // 読み込み時にサイドカーを自動で読み込む
+        let loaded = NDRaw::<u16>::new_from_binimage(path_str.clone()).unwrap();
+        assert_eq!(raw.data(), loaded.data());
+        assert_eq!(raw.metadata(), loaded.metadata());
+        assert_eq!(Some("10000"), loaded.get_metadata("exposure_us"));
+        assert_eq!(Some("2.0"), loaded.get_metadata("gain"));
+        assert_eq!(None, loaded.get_metadata("temperature"));
+        let verified = NDRaw::<u16>::new_from_binimage_verified(path_str.clone()).unwrap();
+        assert_eq!(raw.metadata(), verified.metadata());
+        let mut reloaded = NDRaw::<u16>::new(1, 1);
+        reloaded.read_binimage(path_str.clone()).unwrap();
+        assert_eq!(Some("10000"), reloaded.get_metadata("exposure_us"));
+
+        // メタデータなしの書き込みは既存のサイドカーを削除・変更しない
+        NDRaw::<u16>::new(2, 2)
+            .write_binimage(path_str.clone())
+            .unwrap();
+        assert_eq!(
+            Some("10000"),
+            NDRaw::<u16>::new_from_binimage(path_str.clone())
+                .unwrap()
+                .get_metadata("exposure_us")
+        );
+
+        // 不正なサイドカーは読み込みエラー
+        std::fs::write(&path_json, "not json").unwrap();
+        let result = NDRaw::<u16>::new_from_binimage(path_str.clone());
+        assert!(matches!(result, Err(SensorIoError::Parse(_))));
+
+        // サイドカーがなければメタデータは空
+        std::fs::remove_file(&path_json).unwrap();
+        let loaded = NDRaw::<u16>::new_from_binimage(path_str).unwrap();
+        assert!(loaded.metadata().is_empty());
+        std::fs::remove_file(&path).unwrap();
+
+        println!("}}");
+    }
+}
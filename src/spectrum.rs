@@ -0,0 +1,117 @@
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+
+impl<T: PixelType> NDRaw<T> {
+    // 2D FFTの対数振幅スペクトル (ln(1 + |F|)を最大値で0〜1に正規化, 直流成分を中央に配置)
+    pub fn fft_magnitude(&self) -> NDRaw<f32> {
+        let (width, height) = (self.width(), self.height());
+        if width == 0 || height == 0 {
+            return NDRaw::<f32>::new(width, height);
+        }
+        let mut spectrum: Vec<Complex<f64>> = self
+            .data
+            .iter()
+            .map(|p| Complex::new(p.to_f64().unwrap(), 0.0))
+            .collect();
+        fft2d(&mut spectrum, width, height, false);
+
+        // fftshift
+        let magnitude = ndarray::Array2::from_shape_fn((height, width), |(y, x)| {
+            let sy = (y + height - height / 2) % height;
+            let sx = (x + width - width / 2) % width;
+            spectrum[sy * width + sx].norm().ln_1p()
+        });
+        let max = magnitude.fold(0.0f64, |m, v| m.max(*v));
+        let scale = if max > 0.0 { 1.0 / max } else { 0.0 };
+        let data = magnitude.mapv(|v| (v * scale) as f32);
+        NDRaw::from_ndarray(data)
+    }
+}
+
+// 2D FFT (行優先width x heightのバッファをその場変換, 逆変換は1/(width*height)で正規化)
+pub(crate) fn fft2d(buf: &mut [Complex<f64>], width: usize, height: usize, inverse: bool) {
+    let mut planner = FftPlanner::<f64>::new();
+    let (fft_row, fft_col) = if inverse {
+        (
+            planner.plan_fft_inverse(width),
+            planner.plan_fft_inverse(height),
+        )
+    } else {
+        (
+            planner.plan_fft_forward(width),
+            planner.plan_fft_forward(height),
+        )
+    };
+
+    // 行方向
+    for row in buf.chunks_exact_mut(width) {
+        fft_row.process(row);
+    }
+    // 列方向
+    let mut column = vec![Complex::new(0.0, 0.0); height];
+    for x in 0..width {
+        for y in 0..height {
+            column[y] = buf[y * width + x];
+        }
+        fft_col.process(&mut column);
+        for y in 0..height {
+            buf[y * width + x] = column[y];
+        }
+    }
+
+    if inverse {
+        let scale = 1.0 / (width * height) as f64;
+        buf.iter_mut().for_each(|v| *v *= scale);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_fft_magnitude() {
+        println!("spectrum::test::test_fft_magnitude()  {{");
+
+        // 横方向に周期8画素(32画素で4周期)の縞
+        let (width, height) = (32, 24);
+        let vec2d: Vec<Vec<u16>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|x| {
+                        let phase = 2.0 * std::f64::consts::PI * 4.0 * x as f64 / width as f64;
+                        (1000.0 + 200.0 * phase.sin()).round() as u16
+                    })
+                    .collect()
+            })
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let spectrum = raw_in.fft_magnitude();
+        assert_eq!((width, height), (spectrum.width(), spectrum.height()));
+
+        // 直流成分(中央)を除いた最大値の位置
+        let (cx, cy) = (width / 2, height / 2);
+        let mut peak = (0, 0);
+        let mut peak_value = f32::MIN;
+        for ((y, x), v) in spectrum.data().indexed_iter() {
+            if (x, y) != (cx, cy) && *v > peak_value {
+                peak = (x, y);
+                peak_value = *v;
+            }
+        }
+        println!(
+            "  [spectrum][test_fft_magnitude()] dc = {}, peak = {:?} ({})",
+            spectrum.pix(cx, cy),
+            peak,
+            peak_value
+        );
+        assert_eq!(1.0, *spectrum.pix(cx, cy));
+        assert_eq!((cx - 4, cy), peak);
+        assert_eq!(*spectrum.pix(cx - 4, cy), *spectrum.pix(cx + 4, cy));
+        assert!(*spectrum.pix(cx + 4, cy) > 10.0 * spectrum.pix(cx + 4, cy + 1));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,229 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::naraw::NARaw;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Statistics<S> {
+    pub count: usize,
+    pub min: S,
+    pub max: S,
+    pub mean: S,
+    pub std_dev: S,
+}
+
+// 統計量計算 (2パス, 対象画素なしの場合は全て0)
+pub(crate) fn statistics_of<I: Iterator<Item = f64> + Clone>(values: I) -> Statistics<f64> {
+    let mut count = 0;
+    let mut min = f64::MAX;
+    let mut max = f64::MIN;
+    let mut sum = 0.0;
+    for v in values.clone() {
+        count += 1;
+        min = min.min(v);
+        max = max.max(v);
+        sum += v;
+    }
+    if count == 0 {
+        return Statistics {
+            count,
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            std_dev: 0.0,
+        };
+    }
+    let mean = sum / count as f64;
+    let var = values.map(|v| (v - mean).powi(2)).sum::<f64>() / count as f64;
+
+    Statistics {
+        count,
+        min,
+        max,
+        mean,
+        std_dev: var.sqrt(),
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 統計量計算 (空画像の場合は全て0)
+    pub fn compute_statistics(&self) -> Statistics<f64> {
+        statistics_of(self.data.iter().map(|pix| pix.to_f64().unwrap()))
+    }
+
+    // 除外マスク付き統計量計算 (exclude: [[y, x]]がtrueの画素を除く, 対象画素なしの場合は全て0)
+    //   マスクと画像のサイズ不一致はエラー
+    pub fn compute_statistics_masked(
+        &self,
+        exclude: Option<&ndarray::Array2<bool>>,
+    ) -> Result<Statistics<f64>, SensorIoError> {
+        let Some(exclude) = exclude else {
+            return Ok(self.compute_statistics());
+        };
+        check_shape(
+            (self.width(), self.height()),
+            (exclude.ncols(), exclude.nrows()),
+        )?;
+        Ok(statistics_of(
+            self.data
+                .iter()
+                .zip(exclude.iter())
+                .filter(|(_, excluded)| !**excluded)
+                .map(|(pix, _)| pix.to_f64().unwrap()),
+        ))
+    }
+
+    // パーセンタイル値取得 (percentileは0.0〜1.0, 最近傍順位)
+    pub fn compute_percentile(&self, percentile: f32) -> T {
+        let mut values: Vec<T> = self.data.iter().copied().collect();
+        if values.is_empty() {
+            return T::zero();
+        }
+        let rank = (percentile.clamp(0.0, 1.0) as f64 * (values.len() - 1) as f64).round() as usize;
+        let (_, value, _) = values.select_nth_unstable_by(rank, |a, b| {
+            a.to_f64().unwrap().total_cmp(&b.to_f64().unwrap())
+        });
+        *value
+    }
+
+    // ヒストグラム (画素値のmin〜maxをbins等分)
+    pub fn histogram(&self, bins: usize) -> Vec<u64> {
+        let stats = self.compute_statistics();
+        self.histogram_in_range(bins, stats.min, stats.max)
+    }
+
+    // 範囲指定ヒストグラム (範囲外の値は両端のbinに含める)
+    pub(crate) fn histogram_in_range(&self, bins: usize, min: f64, max: f64) -> Vec<u64> {
+        let mut hist = vec![0u64; bins];
+        if bins == 0 {
+            return hist;
+        }
+        let scale = if max > min {
+            bins as f64 / (max - min)
+        } else {
+            0.0
+        };
+        for pix in self.data.iter() {
+            let bin = ((pix.to_f64().unwrap() - min) * scale).max(0.0) as usize;
+            hist[bin.min(bins - 1)] += 1;
+        }
+        hist
+    }
+}
+
+impl<T: PixelType> NARaw<T> {
+    // 統計量計算 (空画像の場合は全て0)
+    pub fn compute_statistics(&self) -> Statistics<f64> {
+        statistics_of(self.data.iter().map(|pix| pix.to_f64().unwrap()))
+    }
+
+    // 除外マスク付き統計量計算 (exclude: [[y, x]]がtrueの画素を除く, 対象画素なしの場合は全て0)
+    //   マスクと画像のサイズ不一致はエラー
+    pub fn compute_statistics_masked(
+        &self,
+        exclude: Option<&ndarray::Array2<bool>>,
+    ) -> Result<Statistics<f64>, SensorIoError> {
+        let Some(exclude) = exclude else {
+            return Ok(self.compute_statistics());
+        };
+        check_shape(
+            (self.width(), self.height()),
+            (exclude.ncols(), exclude.nrows()),
+        )?;
+        Ok(statistics_of(
+            exclude
+                .indexed_iter()
+                .filter(|(_, excluded)| !**excluded)
+                .map(|((y, x), _)| self.data[(y, x)].to_f64().unwrap()),
+        ))
+    }
+}
+
+// 中央値 (偶数個の場合は中央2値の平均)
+pub(crate) fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    if values.len().is_multiple_of(2) {
+        Some((values[mid - 1] + values[mid]) / 2.0)
+    } else {
+        Some(values[mid])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::median;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_compute_statistics() {
+        println!("statistics::test::test_compute_statistics()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![vec![2, 4, 4, 4], vec![5, 5, 7, 9]];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let stats = raw_in.compute_statistics();
+        println!(
+            "  [statistics][test_compute_statistics()] stats = {:?}",
+            stats
+        );
+        assert_eq!(8, stats.count);
+        assert_eq!(2.0, stats.min);
+        assert_eq!(9.0, stats.max);
+        assert_eq!(5.0, stats.mean);
+        assert_eq!(2.0, stats.std_dev);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_percentile() {
+        println!("statistics::test::test_compute_percentile()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![(0..10).rev().collect(), (10..20).collect()];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        assert_eq!(0, raw_in.compute_percentile(0.0));
+        assert_eq!(10, raw_in.compute_percentile(0.5));
+        assert_eq!(19, raw_in.compute_percentile(0.98));
+        assert_eq!(19, raw_in.compute_percentile(1.0));
+
+        // NaNは最大側に並ぶ (panicしない)
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&[vec![3.0, f32::NAN, 1.0, 2.0]]);
+        assert_eq!(1.0, raw_in.compute_percentile(0.0));
+        assert_eq!(3.0, raw_in.compute_percentile(0.5));
+        assert!(raw_in.compute_percentile(1.0).is_nan());
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_histogram() {
+        println!("statistics::test::test_histogram()  {{");
+
+        let vec2d: Vec<Vec<u16>> = vec![(0..10).collect(), (10..20).collect()];
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let hist = raw_in.histogram(4);
+        println!("  [statistics][test_histogram()] hist = {:?}", hist);
+        // 0〜19を4等分 (最大値は最終binに含める)
+        assert_eq!(vec![5, 5, 5, 5], hist);
+        assert!(raw_in.histogram(0).is_empty());
+
+        let flat = NDRaw::<u16>::new_from_vector2d(&[vec![7; 3]]);
+        assert_eq!(vec![3, 0], flat.histogram(2));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_median() {
+        println!("statistics::test::test_median()  {{");
+
+        assert_eq!(None, median(&mut []));
+        assert_eq!(Some(3.0), median(&mut [5.0, 1.0, 3.0]));
+        assert_eq!(Some(2.5), median(&mut [4.0, 1.0, 3.0, 2.0]));
+
+        println!("}}");
+    }
+}
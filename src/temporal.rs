@@ -0,0 +1,287 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use std::collections::VecDeque;
+
+// 移動平均フィルタ (直近window_size枚の時間平均)
+pub struct RollingMeanFilter<T: PixelType> {
+    sum: NDRaw<f64>,
+    count: usize,
+    capacity: usize,
+    ring: VecDeque<NDRaw<T>>,
+}
+
+impl<T: PixelType> RollingMeanFilter<T> {
+    // 画サイズ・窓サイズ指定コンストラクタ (窓サイズ0はエラー)
+    pub fn new(width: usize, height: usize, window_size: usize) -> Result<Self, SensorIoError> {
+        if window_size == 0 {
+            return Err(SensorIoError::InvalidArgument(
+                "window_size must be positive".to_string(),
+            ));
+        }
+        Ok(RollingMeanFilter {
+            sum: NDRaw::<f64>::new(width, height),
+            count: 0,
+            capacity: window_size,
+            ring: VecDeque::with_capacity(window_size),
+        })
+    }
+
+    // フレーム追加 (窓が満杯なら最古フレームを積算から除外)
+    pub fn push(&mut self, frame: NDRaw<T>) -> Result<(), SensorIoError> {
+        check_shape(
+            (self.sum.width(), self.sum.height()),
+            (frame.width(), frame.height()),
+        )?;
+
+        if self.ring.len() == self.capacity {
+            if let Some(oldest) = self.ring.pop_front() {
+                self.sum
+                    .data
+                    .zip_mut_with(&oldest.data, |s, p| *s -= Self::to_sum(p));
+            }
+        }
+        self.sum
+            .data
+            .zip_mut_with(&frame.data, |s, p| *s += Self::to_sum(p));
+        self.ring.push_back(frame);
+        self.count = self.ring.len();
+
+        Ok(())
+    }
+
+    // 現在の平均画像取得 (フレーム未追加の場合は0)
+    pub fn current_mean(&self) -> NDRaw<f64> {
+        let count = self.count.max(1) as f64;
+        let data = self.sum.data.mapv(|s| s / count);
+        NDRaw::from_ndarray(data)
+    }
+
+    // 窓内フレーム数取得
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    // 窓サイズ取得
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    // 積算値変換 (符号付き・浮動小数点もそのまま積算)
+    fn to_sum(p: &T) -> f64 {
+        p.to_f64().unwrap()
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 行・列固定パターン減算 (空スライスは減算しない)
+    pub fn subtract_temporal_pattern(
+        &self,
+        row_bias: &[f32],
+        col_bias: &[f32],
+    ) -> Result<NDRaw<f32>, SensorIoError> {
+        if !row_bias.is_empty() && row_bias.len() != self.height() {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "row_bias has {} entries, expected {}",
+                row_bias.len(),
+                self.height()
+            )));
+        }
+        if !col_bias.is_empty() && col_bias.len() != self.width() {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "col_bias has {} entries, expected {}",
+                col_bias.len(),
+                self.width()
+            )));
+        }
+
+        let data = ndarray::Array2::<f32>::from_shape_fn(self.data.dim(), |(y, x)| {
+            let mut v = self.data[[y, x]].to_f32().unwrap();
+            if !row_bias.is_empty() {
+                v -= row_bias[y];
+            }
+            if !col_bias.is_empty() {
+                v -= col_bias[x];
+            }
+            v
+        });
+        Ok(NDRaw::from_ndarray(data))
+    }
+
+    // 行毎の固定パターン推定 (ダークフレーム群, 行平均 - 全体平均)
+    //   行・列の両方を減算しても全体平均(黒レベル)は二重に引かれない
+    pub fn estimate_row_bias(dark_frames: &[NDRaw<T>]) -> Result<Vec<f32>, SensorIoError> {
+        Self::estimate_bias(dark_frames, ndarray::Axis(1))
+    }
+
+    // 列毎の固定パターン推定 (ダークフレーム群, 列平均 - 全体平均)
+    pub fn estimate_col_bias(dark_frames: &[NDRaw<T>]) -> Result<Vec<f32>, SensorIoError> {
+        Self::estimate_bias(dark_frames, ndarray::Axis(0))
+    }
+
+    fn estimate_bias(
+        dark_frames: &[NDRaw<T>],
+        axis: ndarray::Axis,
+    ) -> Result<Vec<f32>, SensorIoError> {
+        let Some(first) = dark_frames.first() else {
+            return Ok(Vec::new());
+        };
+        let mut sum =
+            ndarray::Array1::<f64>::zeros(first.data.len_of(ndarray::Axis(1 - axis.index())));
+        for frame in dark_frames {
+            check_shape(
+                (first.width(), first.height()),
+                (frame.width(), frame.height()),
+            )?;
+            for (i, lane) in frame.data.lanes(axis).into_iter().enumerate() {
+                sum[i] += lane.iter().map(|p| p.to_f64().unwrap()).sum::<f64>();
+            }
+        }
+        let count = (dark_frames.len() * first.data.len_of(axis)) as f64;
+        let means = sum.mapv(|s| s / count);
+        let global_mean = means.mean().unwrap_or(0.0);
+        Ok(means.iter().map(|m| (m - global_mean) as f32).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RollingMeanFilter;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    fn uniform(width: usize, height: usize, value: u16) -> NDRaw<u16> {
+        NDRaw::<u16>::new_from_vector2d(&vec![vec![value; width]; height])
+    }
+
+    #[test]
+    fn test_rolling_mean_full_window() {
+        println!("temporal::test::test_rolling_mean_full_window()  {{");
+
+        let mut filter = RollingMeanFilter::<u16>::new(4, 3, 3).unwrap();
+        filter.push(uniform(4, 3, 10)).unwrap();
+        filter.push(uniform(4, 3, 20)).unwrap();
+        filter.push(uniform(4, 3, 60)).unwrap();
+        let mean = filter.current_mean();
+        println!(
+            "  [temporal][test_rolling_mean_full_window()] mean = \n{}",
+            mean.data()
+        );
+        assert_eq!(3, filter.count());
+        assert!(mean.data().iter().all(|v| *v == 30.0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rolling_mean_evicts_oldest() {
+        println!("temporal::test::test_rolling_mean_evicts_oldest()  {{");
+
+        let mut filter = RollingMeanFilter::<u16>::new(4, 3, 3).unwrap();
+        for value in [10, 20, 60, 100] {
+            filter.push(uniform(4, 3, value)).unwrap();
+        }
+        let mean = filter.current_mean();
+        println!(
+            "  [temporal][test_rolling_mean_evicts_oldest()] mean = \n{}",
+            mean.data()
+        );
+        assert_eq!(3, filter.count());
+        assert!(mean.data().iter().all(|v| *v == 60.0));
+
+        let result = filter.push(uniform(3, 3, 0));
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_rolling_mean_signed() {
+        println!("temporal::test::test_rolling_mean_signed()  {{");
+
+        // 負値も0に丸めずに平均する
+        let mut filter = RollingMeanFilter::<i16>::new(2, 2, 2).unwrap();
+        filter
+            .push(NDRaw::<i16>::new_from_vector2d(&[
+                vec![-10, 4],
+                vec![0, -3],
+            ]))
+            .unwrap();
+        filter
+            .push(NDRaw::<i16>::new_from_vector2d(&[vec![-20, 6], vec![0, 1]]))
+            .unwrap();
+        let mean = filter.current_mean();
+        println!(
+            "  [temporal][test_rolling_mean_signed()] mean = \n{}",
+            mean.data()
+        );
+        assert_eq!(-15.0, *mean.pix(0, 0));
+        assert_eq!(5.0, *mean.pix(1, 0));
+        assert_eq!(-1.0, *mean.pix(1, 1));
+
+        let result = RollingMeanFilter::<u16>::new(4, 3, 0);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_subtract_temporal_pattern() {
+        println!("temporal::test::test_subtract_temporal_pattern()  {{");
+
+        let row_bias = [3.0f32, 0.0, 7.5];
+        let col_bias = [1.0f32, 2.0, 0.0, 4.0];
+        let vec2d: Vec<Vec<f32>> = (0..3)
+            .map(|y| (0..4).map(|x| 50.0 + row_bias[y] + col_bias[x]).collect())
+            .collect();
+        let raw_in = NDRaw::<f32>::new_from_vector2d(&vec2d);
+
+        let raw_out = raw_in
+            .subtract_temporal_pattern(&row_bias, &col_bias)
+            .unwrap();
+        println!(
+            "  [temporal][test_subtract_temporal_pattern()] raw_out = \n{}",
+            raw_out.data()
+        );
+        assert!(raw_out.data().iter().all(|v| *v == 50.0));
+
+        // 空スライスは減算しない
+        let raw_out = raw_in.subtract_temporal_pattern(&[], &col_bias).unwrap();
+        assert_eq!(53.0, *raw_out.pix(0, 0));
+
+        let result = raw_in.subtract_temporal_pattern(&[1.0], &[]);
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_estimate_bias() {
+        println!("temporal::test::test_estimate_bias()  {{");
+
+        let frame_a = NDRaw::<u16>::new_from_vector2d(&[vec![10, 12, 14], vec![20, 22, 24]]);
+        let frame_b = NDRaw::<u16>::new_from_vector2d(&[vec![12, 14, 16], vec![22, 24, 26]]);
+        let frames = [frame_a, frame_b];
+        let row_bias = NDRaw::<u16>::estimate_row_bias(&frames).unwrap();
+        let col_bias = NDRaw::<u16>::estimate_col_bias(&frames).unwrap();
+        println!(
+            "  [temporal][test_estimate_bias()] row_bias = {:?}, col_bias = {:?}",
+            row_bias, col_bias
+        );
+        assert_eq!(vec![-5.0, 5.0], row_bias);
+        assert_eq!(vec![-2.0, 0.0, 2.0], col_bias);
+        assert!(NDRaw::<u16>::estimate_row_bias(&[]).unwrap().is_empty());
+
+        // 行・列を両方減算すると全体平均だけが残る
+        let residual = frames[0]
+            .subtract_temporal_pattern(&row_bias, &col_bias)
+            .unwrap();
+        assert!(residual.data().iter().all(|v| *v == 17.0));
+
+        let mismatched = [frames[0].clone(), NDRaw::<u16>::new(2, 2)];
+        let result = NDRaw::<u16>::estimate_col_bias(&mismatched);
+        assert!(matches!(result, Err(SensorIoError::ShapeMismatch(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,200 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+
+// ColorChecker 24色 (sRGB 8bit, D65, 左上から行優先)
+const MACBETH_SRGB: [[u8; 3]; 24] = [
+    [115, 82, 68],
+    [194, 150, 130],
+    [98, 122, 157],
+    [87, 108, 67],
+    [133, 128, 177],
+    [103, 189, 170],
+    [214, 126, 44],
+    [80, 91, 166],
+    [193, 90, 99],
+    [94, 60, 108],
+    [157, 188, 64],
+    [224, 163, 46],
+    [56, 61, 150],
+    [70, 148, 73],
+    [175, 54, 60],
+    [231, 199, 31],
+    [187, 86, 149],
+    [8, 133, 161],
+    [243, 243, 242],
+    [200, 200, 200],
+    [160, 160, 160],
+    [122, 122, 121],
+    [85, 85, 85],
+    [52, 52, 52],
+];
+
+// 合成チャートのセンサ応答 (raw = black + reflectance * gain * (white - black))
+const CHART_BLACK_LEVEL: f64 = 64.0;
+const CHART_WHITE_LEVEL: f64 = 4095.0;
+// R, G, Bの感度 (D65下でのカメラ固有の白バランス相当)
+const CHART_CHANNEL_GAIN: [f64; 3] = [0.5, 0.9, 0.65];
+
+// sRGB 8bit => 線形反射率
+fn srgb_to_linear(v: u8) -> f64 {
+    let c = v as f64 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// ColorChecker風4x6パッチのBayer画像生成
+//   各セルの外周1/8は黒レベルの枠, パッチ値は線形センサ応答で変換
+pub fn generate_macbeth_bayer(width: usize, height: usize, pattern: BayerPattern) -> NDRaw<u16> {
+    let mut raw = NDRaw::<u16>::new(width, height);
+    for ((y, x), pix) in raw.data.indexed_iter_mut() {
+        let (col, row) = (x * 6 / width, y * 4 / height);
+        // セル内の相対位置 (0〜1)
+        let u = (x * 6) as f64 / width as f64 - col as f64;
+        let v = (y * 4) as f64 / height as f64 - row as f64;
+        let inside = (0.125..0.875).contains(&u) && (0.125..0.875).contains(&v);
+
+        let value = if inside {
+            let rgb = MACBETH_SRGB[row * 6 + col];
+            let c = match pattern.channel_at(x, y) {
+                BayerChannel::R => 0,
+                BayerChannel::Gr | BayerChannel::Gb => 1,
+                BayerChannel::B => 2,
+            };
+            CHART_BLACK_LEVEL
+                + srgb_to_linear(rgb[c])
+                    * CHART_CHANNEL_GAIN[c]
+                    * (CHART_WHITE_LEVEL - CHART_BLACK_LEVEL)
+        } else {
+            CHART_BLACK_LEVEL
+        };
+        *pix = value.round() as u16;
+    }
+    raw
+}
+
+// シーメンススター生成 (0.0/1.0のnum_spokes周期の扇形, 内接円外は0.5)
+pub fn generate_siemens_star(width: usize, height: usize, num_spokes: usize) -> NDRaw<f32> {
+    let (cx, cy) = ((width as f64 - 1.0) / 2.0, (height as f64 - 1.0) / 2.0);
+    let radius = width.min(height) as f64 / 2.0;
+    let mut raw = NDRaw::<f32>::new(width, height);
+    for ((y, x), pix) in raw.data.indexed_iter_mut() {
+        let (dx, dy) = (x as f64 - cx, y as f64 - cy);
+        *pix = if dx.hypot(dy) > radius {
+            0.5
+        } else if (num_spokes as f64 * dy.atan2(dx)).sin() >= 0.0 {
+            1.0
+        } else {
+            0.0
+        };
+    }
+    raw
+}
+
+// スラントエッジ生成 (中心を通り垂直からangle_deg傾いたエッジ, 左側0.0/右側1.0, 正の角度で下ほど右)
+//   エッジ上の画素は4x4のサブサンプリングで面積比の中間値
+pub fn generate_slanted_edge(width: usize, height: usize, angle_deg: f32) -> NDRaw<f32> {
+    const SUBSAMPLES: usize = 4;
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let angle = (angle_deg as f64).to_radians();
+    // エッジの法線 (右向き)
+    let (nx, ny) = (angle.cos(), -angle.sin());
+    let mut raw = NDRaw::<f32>::new(width, height);
+    for ((y, x), pix) in raw.data.indexed_iter_mut() {
+        let mut bright = 0;
+        for sy in 0..SUBSAMPLES {
+            for sx in 0..SUBSAMPLES {
+                let px = x as f64 + (sx as f64 + 0.5) / SUBSAMPLES as f64 - cx;
+                let py = y as f64 + (sy as f64 + 0.5) / SUBSAMPLES as f64 - cy;
+                if px * nx + py * ny >= 0.0 {
+                    bright += 1;
+                }
+            }
+        }
+        *pix = bright as f32 / (SUBSAMPLES * SUBSAMPLES) as f32;
+    }
+    raw
+}
+
+#[cfg(test)]
+mod test {
+    use super::{generate_macbeth_bayer, generate_siemens_star, generate_slanted_edge};
+    use crate::bayer::BayerPattern;
+
+    #[test]
+    fn test_generate_macbeth_bayer() {
+        println!("testcharts::test::test_generate_macbeth_bayer()  {{");
+
+        let raw = generate_macbeth_bayer(96, 64, BayerPattern::Rggb);
+        assert_eq!((96, 64), (raw.width(), raw.height()));
+
+        // 白パッチ(19番, 4行目1列目)中央の2x2
+        let (x, y) = (8, 56);
+        let (r, g, b) = (*raw.pix(x, y), *raw.pix(x + 1, y), *raw.pix(x + 1, y + 1));
+        println!(
+            "  [testcharts][test_generate_macbeth_bayer()] white patch (r, g, b) = ({}, {}, {})",
+            r, g, b
+        );
+        assert!(r < b && b < g);
+        assert_eq!(g, *raw.pix(x, y + 1));
+        // 枠は黒レベル
+        assert_eq!(64, *raw.pix(0, 0));
+        // グレーパッチは右ほど暗い
+        let gray: Vec<u16> = (0..6).map(|col| *raw.pix(col * 16 + 9, 56)).collect();
+        assert!(gray.windows(2).all(|w| w[0] > w[1]));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_generate_siemens_star() {
+        println!("testcharts::test::test_generate_siemens_star()  {{");
+
+        let num_spokes = 12;
+        let star = generate_siemens_star(128, 128, num_spokes);
+
+        // 半径40の円周上の明暗遷移数
+        let samples: Vec<f32> = (0..720)
+            .map(|i| {
+                let theta = 2.0 * std::f64::consts::PI * i as f64 / 720.0;
+                let x = (63.5 + 40.0 * theta.cos()).round() as usize;
+                let y = (63.5 + 40.0 * theta.sin()).round() as usize;
+                *star.pix(x, y)
+            })
+            .collect();
+        let transitions = (0..samples.len())
+            .filter(|i| samples[*i] != samples[(i + 1) % samples.len()])
+            .count();
+        println!(
+            "  [testcharts][test_generate_siemens_star()] transitions = {}",
+            transitions
+        );
+        assert_eq!(2 * num_spokes, transitions);
+        assert_eq!(0.5, *star.pix(0, 0));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_generate_slanted_edge() {
+        println!("testcharts::test::test_generate_slanted_edge()  {{");
+
+        let edge = generate_slanted_edge(64, 48, 5.0);
+        assert_eq!(0.0, *edge.pix(0, 24));
+        assert_eq!(1.0, *edge.pix(63, 24));
+
+        // 各行のエッジ位置(0.5を跨ぐ位置)は下の行ほど右
+        let crossing = |y: usize| (0..64).find(|x| *edge.pix(*x, y) >= 0.5).unwrap();
+        println!(
+            "  [testcharts][test_generate_slanted_edge()] crossing top = {}, bottom = {}",
+            crossing(0),
+            crossing(47)
+        );
+        assert!(crossing(0) < crossing(47));
+        assert!(edge.data().iter().any(|v| *v > 0.0 && *v < 1.0));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,206 @@
+use crate::config::Metadata;
+use crate::error::SensorIoError;
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::encoder::compression::{Compression, Lzw, Uncompressed};
+use tiff::encoder::{colortype, TiffEncoder};
+use tiff::tags::Tag;
+use tiff::ColorType;
+
+// Metadata(JSON)を格納するTIFFタグ
+const METADATA_TAG: Tag = Tag::Unknown(65000);
+
+// TIFF圧縮方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TiffCompression {
+    None,
+    Lzw,
+}
+
+fn tiff_error(e: tiff::TiffError) -> SensorIoError {
+    match e {
+        tiff::TiffError::IoError(e) => SensorIoError::Io(e),
+        e => SensorIoError::Parse(e.to_string()),
+    }
+}
+
+fn write_gray16<D: Compression>(
+    path: &Path,
+    width: usize,
+    height: usize,
+    pixels: &[u16],
+    compression: D,
+    metadata: Option<&Metadata>,
+) -> Result<(), SensorIoError> {
+    let mut encoder = TiffEncoder::new(BufWriter::new(File::create(path)?)).map_err(tiff_error)?;
+    let mut image = encoder
+        .new_image_with_compression::<colortype::Gray16, D>(
+            width as u32,
+            height as u32,
+            compression,
+        )
+        .map_err(tiff_error)?;
+    if let Some(metadata) = metadata {
+        let json =
+            serde_json::to_string(metadata).map_err(|e| SensorIoError::Parse(e.to_string()))?;
+        image
+            .encoder()
+            .write_tag(METADATA_TAG, json.as_str())
+            .map_err(tiff_error)?;
+    }
+    image.write_data(pixels).map_err(tiff_error)
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // 16bitグレースケールTIFF書き込み (u16に収まらない値は飽和, metadataはタグ65000にJSONで格納)
+    pub fn write_tiff_16bit(
+        &self,
+        path: impl AsRef<Path>,
+        compression: TiffCompression,
+        metadata: Option<&Metadata>,
+    ) -> Result<(), SensorIoError> {
+        let pixels: Vec<u16> = self
+            .data
+            .iter()
+            .map(|p| u16::from_f64_saturating(p.to_f64().unwrap()))
+            .collect();
+        let (path, width, height) = (path.as_ref(), self.width(), self.height());
+        match compression {
+            TiffCompression::None => {
+                write_gray16(path, width, height, &pixels, Uncompressed, metadata)
+            }
+            TiffCompression::Lzw => write_gray16(path, width, height, &pixels, Lzw, metadata),
+        }
+    }
+}
+
+impl NDRaw<u16> {
+    // 16bit TIFF読み込み (グレースケール, RGBは輝度(BT.709)に変換, それ以外の形式はエラー)
+    pub fn new_from_tiff_16bit(path: impl AsRef<Path>) -> Result<NDRaw<u16>, SensorIoError> {
+        let mut decoder = Decoder::new(BufReader::new(File::open(path)?)).map_err(tiff_error)?;
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let (width, height) = (width as usize, height as usize);
+        let color_type = decoder.colortype().map_err(tiff_error)?;
+        let DecodingResult::U16(pixels) = decoder.read_image().map_err(tiff_error)? else {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "TIFF color type {:?} is not 16-bit",
+                color_type
+            )));
+        };
+
+        let pixels = match color_type {
+            ColorType::Gray(16) => pixels,
+            ColorType::RGB(16) => pixels
+                .chunks_exact(3)
+                .map(|rgb| {
+                    let y =
+                        0.2126 * rgb[0] as f64 + 0.7152 * rgb[1] as f64 + 0.0722 * rgb[2] as f64;
+                    u16::from_f64_saturating(y)
+                })
+                .collect(),
+            _ => {
+                return Err(SensorIoError::InvalidArgument(format!(
+                    "unsupported TIFF color type {:?}",
+                    color_type
+                )))
+            }
+        };
+        let data = ndarray::Array2::from_shape_vec((height, width), pixels)
+            .map_err(|e| SensorIoError::Parse(e.to_string()))?;
+        Ok(NDRaw::from_ndarray(data))
+    }
+}
+
+// TIFFのMetadata読み込み (タグ65000がなければNone)
+pub fn read_tiff_metadata(path: impl AsRef<Path>) -> Result<Option<Metadata>, SensorIoError> {
+    let mut decoder = Decoder::new(BufReader::new(File::open(path)?)).map_err(tiff_error)?;
+    let Some(value) = decoder.find_tag(METADATA_TAG).map_err(tiff_error)? else {
+        return Ok(None);
+    };
+    let json = value.into_string().map_err(tiff_error)?;
+    let metadata = serde_json::from_str(&json).map_err(|e| SensorIoError::Parse(e.to_string()))?;
+    Ok(Some(metadata))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{read_tiff_metadata, TiffCompression};
+    use crate::config::Metadata;
+    use crate::error::SensorIoError;
+    use crate::ndraw::NDRaw;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("sensor_io_{}_{}.tiff", name, std::process::id()))
+    }
+
+    #[test]
+    fn test_tiff_roundtrip() {
+        println!("tiff_io::test::test_tiff_roundtrip()  {{");
+
+        let vec2d: Vec<Vec<u16>> = (0..6)
+            .map(|y| (0..8).map(|x| (x * 1000 + y * 7) as u16).collect())
+            .collect();
+        let raw_in = NDRaw::<u16>::new_from_vector2d(&vec2d);
+        let metadata = Metadata {
+            serial_number: Some(String::from("SN-0042")),
+            calibration_date: None,
+        };
+
+        for compression in [TiffCompression::None, TiffCompression::Lzw] {
+            let path = temp_path(&format!("{:?}", compression));
+            raw_in
+                .write_tiff_16bit(&path, compression, Some(&metadata))
+                .unwrap();
+            let raw_read = NDRaw::<u16>::new_from_tiff_16bit(&path).unwrap();
+            let metadata_read = read_tiff_metadata(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            println!(
+                "  [tiff_io][test_tiff_roundtrip()] {:?}: metadata = {:?}",
+                compression, metadata_read
+            );
+            assert_eq!(raw_in.data(), raw_read.data());
+            assert_eq!(Some(metadata.clone()), metadata_read);
+        }
+
+        // メタデータなし
+        let path = temp_path("no_metadata");
+        raw_in
+            .write_tiff_16bit(&path, TiffCompression::None, None)
+            .unwrap();
+        let metadata_read = read_tiff_metadata(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(None, metadata_read);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_tiff_rgb() {
+        println!("tiff_io::test::test_tiff_rgb()  {{");
+
+        // 16bit RGB => 輝度
+        let path = temp_path("rgb16");
+        let img = image::ImageBuffer::from_pixel(4, 2, image::Rgb([10000u16, 20000, 30000]));
+        img.save(&path).unwrap();
+        let raw = NDRaw::<u16>::new_from_tiff_16bit(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        println!("  [tiff_io][test_tiff_rgb()] raw = \n{}", raw.data());
+        assert_eq!((4, 2), (raw.width(), raw.height()));
+        // 0.2126 * 10000 + 0.7152 * 20000 + 0.0722 * 30000
+        assert_eq!(18596, *raw.pix(0, 0));
+
+        // 8bit画像はエラー
+        let path = temp_path("rgb8");
+        let img = image::ImageBuffer::from_pixel(4, 2, image::Rgb([10u8, 20, 30]));
+        img.save(&path).unwrap();
+        let result = NDRaw::<u16>::new_from_tiff_16bit(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(SensorIoError::InvalidArgument(_))));
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::{check_shape, SensorIoError};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+use crate::raw::RawImage;
+use crate::statistics::statistics_of;
+use num_traits::ToPrimitive;
+
+// 面内均一性の評価結果 (平均が0の場合の比率はNaN/無限大)
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct FlatnessReport {
+    pub mean: f64,
+    pub min: f64,
+    pub max: f64,
+    // (max - min) / mean [%]
+    pub peak_to_valley_percent: f64,
+    // 標準偏差 / mean [%]
+    pub rms_uniformity_percent: f64,
+    // 中央3x3の平均 / 四隅3x3の平均
+    pub center_to_corner_ratio: f64,
+}
+
+// 面内均一性の評価
+pub(crate) fn flatness_error<R: RawImage + ?Sized>(raw: &R) -> FlatnessReport {
+    let (width, height) = (raw.width(), raw.height());
+    let value = |x: usize, y: usize| raw.pix(x, y).to_f64().unwrap();
+    // 左上(x0, y0)から3x3 (画像外は除く) の和と画素数
+    let block = |x0: usize, y0: usize| {
+        let (mut sum, mut count) = (0.0, 0);
+        for y in y0..(y0 + 3).min(height) {
+            for x in x0..(x0 + 3).min(width) {
+                sum += value(x, y);
+                count += 1;
+            }
+        }
+        (sum, count)
+    };
+
+    let stats = statistics_of((0..height).flat_map(|y| (0..width).map(move |x| value(x, y))));
+
+    let (center_sum, center_count) = block(
+        (width / 2).saturating_sub(1),
+        (height / 2).saturating_sub(1),
+    );
+    let (right, bottom) = (width.saturating_sub(3), height.saturating_sub(3));
+    let (corner_sum, corner_count) = [(0, 0), (right, 0), (0, bottom), (right, bottom)]
+        .iter()
+        .map(|&(x0, y0)| block(x0, y0))
+        .fold((0.0, 0), |(s, c), (bs, bc)| (s + bs, c + bc));
+
+    FlatnessReport {
+        mean: stats.mean,
+        min: stats.min,
+        max: stats.max,
+        peak_to_valley_percent: (stats.max - stats.min) / stats.mean * 100.0,
+        rms_uniformity_percent: stats.std_dev / stats.mean * 100.0,
+        center_to_corner_ratio: (center_sum / center_count as f64)
+            / (corner_sum / corner_count as f64),
+    }
+}
+
+impl<T: PixelType> NDRaw<T> {
+    // DSNU (平均ダーク画像の空間標準偏差)
+    pub fn compute_dsnu(&self) -> f64 {
+        self.compute_statistics().std_dev
+    }
+
+    // PRNU => (PRNU [%], 画素毎の平均からの偏差 [%])
+    //   フラット画像群の時間平均からdark_meanを引いた画像の 標準偏差 / 平均
+    pub fn compute_prnu(
+        flat_frames: &[NDRaw<T>],
+        dark_mean: &NDRaw<T>,
+    ) -> Result<(f64, NDRaw<f64>), SensorIoError> {
+        if flat_frames.is_empty() {
+            return Err(SensorIoError::InvalidArgument(String::from(
+                "no flat frames given",
+            )));
+        }
+        let shape = (dark_mean.width(), dark_mean.height());
+        let mut sum = ndarray::Array2::<f64>::zeros(dark_mean.data.dim());
+        for (i, frame) in flat_frames.iter().enumerate() {
+            check_shape(shape, (frame.width(), frame.height()))
+                .map_err(|e| SensorIoError::ShapeMismatch(format!("flat frame {}: {}", i, e)))?;
+            sum.zip_mut_with(&frame.data, |s, p| *s += p.to_f64().unwrap());
+        }
+
+        let count = flat_frames.len() as f64;
+        let corrected = NDRaw::from_ndarray(ndarray::Array2::from_shape_fn(sum.dim(), |(y, x)| {
+            sum[[y, x]] / count - dark_mean.data[[y, x]].to_f64().unwrap()
+        }));
+        let stats = corrected.compute_statistics();
+        if stats.mean <= 0.0 {
+            return Err(SensorIoError::InvalidArgument(format!(
+                "dark-corrected flat mean {} is not positive",
+                stats.mean
+            )));
+        }
+
+        let map = corrected
+            .data
+            .mapv(|v| (v - stats.mean) / stats.mean * 100.0);
+        Ok((stats.std_dev / stats.mean * 100.0, NDRaw::from_ndarray(map)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::SensorIoError;
+    use crate::naraw::NARaw;
+    use crate::ndraw::NDRaw;
+    use crate::raw::RawImage;
+
+    // 市松に±1となるパターン (平均0, 標準偏差1)
+    fn checker(x: usize, y: usize) -> f64 {
+        if (x + y).is_multiple_of(2) {
+            1.0
+        } else {
+            -1.0
+        }
+    }
+
+    #[test]
+    fn test_compute_dsnu() {
+        println!("uniformity::test::test_compute_dsnu()  {{");
+
+        let vec2d: Vec<Vec<f64>> = (0..4)
+            .map(|y| (0..6).map(|x| 64.0 + 3.0 * checker(x, y)).collect())
+            .collect();
+        let dark = NDRaw::<f64>::new_from_vector2d(&vec2d);
+        let dsnu = dark.compute_dsnu();
+        println!("  [uniformity][test_compute_dsnu()] dsnu = {}", dsnu);
+        assert!((dsnu - 3.0).abs() < 1e-12);
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_prnu() {
+        println!("uniformity::test::test_compute_prnu()  {{");
+
+        // 画素感度のばらつき1%, 時間ノイズは2フレームで打ち消し合う
+        let (width, height) = (8, 6);
+        let dark_vec: Vec<Vec<f64>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| 60.0 + ((x + 2 * y) % 3) as f64)
+                    .collect()
+            })
+            .collect();
+        let flat = |noise: f64| {
+            let vec2d: Vec<Vec<f64>> = (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| dark_vec[y][x] + 1000.0 * (1.0 + 0.01 * checker(x, y)) + noise)
+                        .collect()
+                })
+                .collect();
+            NDRaw::<f64>::new_from_vector2d(&vec2d)
+        };
+        let dark = NDRaw::<f64>::new_from_vector2d(&dark_vec);
+        let (prnu, map) = NDRaw::compute_prnu(&[flat(5.0), flat(-5.0)], &dark).unwrap();
+        println!(
+            "  [uniformity][test_compute_prnu()] prnu = {} %, map = \n{}",
+            prnu,
+            map.data()
+        );
+        assert!((prnu - 1.0).abs() < 1e-9);
+        assert!((map.pix(0, 0) - 1.0).abs() < 1e-9);
+        assert!((map.pix(1, 0) + 1.0).abs() < 1e-9);
+
+        assert!(matches!(
+            NDRaw::compute_prnu(&[], &dark),
+            Err(SensorIoError::InvalidArgument(_))
+        ));
+        assert!(matches!(
+            NDRaw::compute_prnu(&[flat(0.0), NDRaw::<f64>::new(4, 4)], &dark),
+            Err(SensorIoError::ShapeMismatch(_))
+        ));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_compute_flatness_error() {
+        println!("uniformity::test::test_compute_flatness_error()  {{");
+
+        // 一様画像 (市松に±1)
+        let vec2d: Vec<Vec<u16>> = (0..12)
+            .map(|y| (0..16).map(|x| (1000.0 + checker(x, y)) as u16).collect())
+            .collect();
+        let report = NDRaw::<u16>::new_from_vector2d(&vec2d).compute_flatness_error();
+        println!(
+            "  [uniformity][test_compute_flatness_error()] uniform = {:?}",
+            report
+        );
+        assert_eq!(
+            (1000.0, 999.0, 1001.0),
+            (report.mean, report.min, report.max)
+        );
+        assert!((report.peak_to_valley_percent - 0.2).abs() < 1e-9);
+        assert!((report.rms_uniformity_percent - 0.1).abs() < 1e-9);
+        assert!((report.center_to_corner_ratio - 1.0).abs() < 1e-3);
+
+        // 周辺減光 (cos^4則)
+        let vec2d: Vec<Vec<f32>> = (0..12)
+            .map(|y| {
+                (0..16)
+                    .map(|x| {
+                        let r2 = (x as f32 - 7.5).powi(2) + (y as f32 - 5.5).powi(2);
+                        1000.0 / (1.0 + r2 / 100.0).powi(2)
+                    })
+                    .collect()
+            })
+            .collect();
+        let report = NARaw::<f32>::new_from_vector2d(&vec2d).compute_flatness_error();
+        println!(
+            "  [uniformity][test_compute_flatness_error()] vignetting = {:?}",
+            report
+        );
+        assert!(report.center_to_corner_ratio > 1.5);
+        assert!(report.peak_to_valley_percent > report.rms_uniformity_percent);
+        assert!(report.min < report.mean && report.mean < report.max);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,88 @@
+use crate::bayer::{BayerChannel, BayerPattern};
+use crate::ndraw::NDRaw;
+use crate::pixel::PixelType;
+
+impl<T: PixelType> NDRaw<T> {
+    // CFA疑似カラー表示 (R: (v, 0, 0), G: (0, v, 0), B: (0, 0, v), vは画素値 * scaleを0〜255にクランプ)
+    pub fn visualize_cfa(&self, pattern: BayerPattern, scale: f64) -> image::RgbImage {
+        self.render_cfa(pattern, scale, false)
+    }
+
+    // CFA疑似カラー表示 (Gr/Gbを色味で区別, Gr: (v/4, v, 0), Gb: (0, v, v/4))
+    pub fn visualize_cfa_tinted(&self, pattern: BayerPattern, scale: f64) -> image::RgbImage {
+        self.render_cfa(pattern, scale, true)
+    }
+
+    fn render_cfa(&self, pattern: BayerPattern, scale: f64, tint_greens: bool) -> image::RgbImage {
+        image::RgbImage::from_fn(self.width() as u32, self.height() as u32, |x, y| {
+            let (x, y) = (x as usize, y as usize);
+            let v = (self.data[[y, x]].to_f64().unwrap() * scale)
+                .clamp(0.0, 255.0)
+                .round() as u8;
+            let tint = if tint_greens { v / 4 } else { 0 };
+            image::Rgb(match pattern.channel_at(x, y) {
+                BayerChannel::R => [v, 0, 0],
+                BayerChannel::Gr => [tint, v, 0],
+                BayerChannel::Gb => [0, v, tint],
+                BayerChannel::B => [0, 0, v],
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::bayer::BayerPattern;
+    use crate::ndraw::NDRaw;
+
+    #[test]
+    fn test_visualize_cfa() {
+        println!("visualize::test::test_visualize_cfa()  {{");
+
+        // 一定値の画像 (1000 * 0.2 = 200)
+        let mut raw = NDRaw::<u16>::new(4, 4);
+        raw.data.fill(1000);
+        let expected_top_left = [
+            (
+                BayerPattern::Rggb,
+                [[200, 0, 0], [0, 200, 0], [0, 200, 0], [0, 0, 200]],
+            ),
+            (
+                BayerPattern::Grbg,
+                [[0, 200, 0], [200, 0, 0], [0, 0, 200], [0, 200, 0]],
+            ),
+            (
+                BayerPattern::Gbrg,
+                [[0, 200, 0], [0, 0, 200], [200, 0, 0], [0, 200, 0]],
+            ),
+            (
+                BayerPattern::Bggr,
+                [[0, 0, 200], [0, 200, 0], [0, 200, 0], [200, 0, 0]],
+            ),
+        ];
+        for (pattern, expected) in expected_top_left {
+            let img = raw.visualize_cfa(pattern, 0.2);
+            println!(
+                "  [visualize][test_visualize_cfa()] {:?}: {:?}",
+                pattern,
+                img.pixels().take(4).map(|p| p.0).collect::<Vec<[u8; 3]>>()
+            );
+            assert_eq!((4, 4), img.dimensions());
+            // 2x2周期で同じ配置
+            for y in 0..4u32 {
+                for x in 0..4u32 {
+                    let i = (y % 2 * 2 + x % 2) as usize;
+                    assert_eq!(expected[i], img.get_pixel(x, y).0);
+                }
+            }
+        }
+
+        // Gr/Gbの色味の区別と8bitへのクランプ
+        let img = raw.visualize_cfa_tinted(BayerPattern::Rggb, 1.0);
+        assert_eq!([255, 0, 0], img.get_pixel(0, 0).0);
+        assert_eq!([63, 255, 0], img.get_pixel(1, 0).0);
+        assert_eq!([0, 255, 63], img.get_pixel(0, 1).0);
+
+        println!("}}");
+    }
+}
@@ -0,0 +1,262 @@
+use crate::ndraw::NDRaw;
+use crate::rgb::RgbRaw;
+
+// YCbCr変換規格 (係数と値域)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum YuvStandard {
+    // BT.601 フルレンジ (Y, Cb, Cr: 0〜255)
+    Bt601Full,
+    // BT.601 リミテッドレンジ (Y: 16〜235, Cb, Cr: 16〜240)
+    Bt601Limited,
+    // BT.709 フルレンジ
+    Bt709Full,
+    // BT.709 リミテッドレンジ
+    Bt709Limited,
+}
+
+impl YuvStandard {
+    // (Kr, Kb)
+    fn coefficients(&self) -> (f64, f64) {
+        match self {
+            YuvStandard::Bt601Full | YuvStandard::Bt601Limited => (0.299, 0.114),
+            YuvStandard::Bt709Full | YuvStandard::Bt709Limited => (0.2126, 0.0722),
+        }
+    }
+
+    // (Yのスケール, Yのオフセット, Cb/Crのスケール)
+    fn range(&self) -> (f64, f64, f64) {
+        match self {
+            YuvStandard::Bt601Full | YuvStandard::Bt709Full => (1.0, 0.0, 1.0),
+            YuvStandard::Bt601Limited | YuvStandard::Bt709Limited => {
+                (219.0 / 255.0, 16.0, 224.0 / 255.0)
+            }
+        }
+    }
+}
+
+// YCbCrプレーン (8bit, フル解像度)
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct YcbcrPlanes {
+    pub(crate) y: NDRaw<u8>,
+    pub(crate) cb: NDRaw<u8>,
+    pub(crate) cr: NDRaw<u8>,
+}
+
+impl YcbcrPlanes {
+    // Yプレーン取得
+    pub fn y(&self) -> &NDRaw<u8> {
+        &self.y
+    }
+
+    // Cbプレーン取得
+    pub fn cb(&self) -> &NDRaw<u8> {
+        &self.cb
+    }
+
+    // Crプレーン取得
+    pub fn cr(&self) -> &NDRaw<u8> {
+        &self.cr
+    }
+
+    // width取得
+    pub fn width(&self) -> usize {
+        self.y.width()
+    }
+
+    // height取得
+    pub fn height(&self) -> usize {
+        self.y.height()
+    }
+}
+
+// YUV420プラナー (I420) バッファ
+//   Y(width * height) => Cb(chroma_width * chroma_height) => Cr の順に連続配置
+//   色差は切り上げサイズ ((width + 1) / 2, (height + 1) / 2) で, 奇数サイズの右端・下端は存在する画素のみで平均
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct Yuv420Buffer {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+}
+
+impl Yuv420Buffer {
+    // width取得
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    // height取得
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // 色差プレーンサイズ (width, height)
+    pub fn chroma_size(&self) -> (usize, usize) {
+        (self.width.div_ceil(2), self.height.div_ceil(2))
+    }
+
+    // バッファ全体
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    // Yプレーン
+    pub fn y_plane(&self) -> &[u8] {
+        &self.data[..self.width * self.height]
+    }
+
+    // Cbプレーン
+    pub fn cb_plane(&self) -> &[u8] {
+        let (luma, chroma) = self.plane_sizes();
+        &self.data[luma..luma + chroma]
+    }
+
+    // Crプレーン
+    pub fn cr_plane(&self) -> &[u8] {
+        let (luma, chroma) = self.plane_sizes();
+        &self.data[luma + chroma..]
+    }
+
+    fn plane_sizes(&self) -> (usize, usize) {
+        let (cw, ch) = self.chroma_size();
+        (self.width * self.height, cw * ch)
+    }
+}
+
+// RGB => YCbCr変換 (8bit RGB, 結果は四捨五入して0〜255に飽和)
+pub fn to_ycbcr(rgb: &RgbRaw<u8>, standard: YuvStandard) -> YcbcrPlanes {
+    let (kr, kb) = standard.coefficients();
+    let kg = 1.0 - kr - kb;
+    let (y_scale, y_offset, c_scale) = standard.range();
+    let to_u8 = |v: f64| v.round().clamp(0.0, 255.0) as u8;
+
+    let (width, height) = (rgb.width(), rgb.height());
+    let mut planes = YcbcrPlanes {
+        y: NDRaw::new(width, height),
+        cb: NDRaw::new(width, height),
+        cr: NDRaw::new(width, height),
+    };
+    for y in 0..height {
+        for x in 0..width {
+            let [r, g, b] = rgb.pix(x, y).map(|v| v as f64);
+            let luma = kr * r + kg * g + kb * b;
+            let cb = (b - luma) / (2.0 * (1.0 - kb));
+            let cr = (r - luma) / (2.0 * (1.0 - kr));
+            *planes.y.pix_mut(x, y) = to_u8(y_offset + y_scale * luma);
+            *planes.cb.pix_mut(x, y) = to_u8(128.0 + c_scale * cb);
+            *planes.cr.pix_mut(x, y) = to_u8(128.0 + c_scale * cr);
+        }
+    }
+    planes
+}
+
+// YCbCr => YUV420変換 (色差を2x2平均で間引き)
+pub fn to_yuv420(planes: &YcbcrPlanes) -> Yuv420Buffer {
+    let (width, height) = (planes.width(), planes.height());
+    let (cw, ch) = (width.div_ceil(2), height.div_ceil(2));
+    let mut data = Vec::with_capacity(width * height + 2 * cw * ch);
+    data.extend(planes.y.data.iter());
+    for plane in [&planes.cb, &planes.cr] {
+        for cy in 0..ch {
+            for cx in 0..cw {
+                let (mut sum, mut count) = (0u32, 0u32);
+                for y in 2 * cy..(2 * cy + 2).min(height) {
+                    for x in 2 * cx..(2 * cx + 2).min(width) {
+                        sum += *plane.pix(x, y) as u32;
+                        count += 1;
+                    }
+                }
+                data.push(((sum + count / 2) / count) as u8);
+            }
+        }
+    }
+    Yuv420Buffer {
+        width,
+        height,
+        data,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_ycbcr, to_yuv420, YuvStandard};
+    use crate::rgb::RgbRaw;
+
+    fn ycbcr_of(rgb: [u8; 3], standard: YuvStandard) -> [u8; 3] {
+        let mut raw = RgbRaw::<u8>::new(1, 1);
+        raw.set_pix(0, 0, rgb);
+        let planes = to_ycbcr(&raw, standard);
+        [
+            *planes.y().pix(0, 0),
+            *planes.cb().pix(0, 0),
+            *planes.cr().pix(0, 0),
+        ]
+    }
+
+    #[test]
+    fn test_to_ycbcr() {
+        println!("yuv::test::test_to_ycbcr()  {{");
+
+        // 白・黒
+        assert_eq!(
+            [255, 128, 128],
+            ycbcr_of([255, 255, 255], YuvStandard::Bt601Full)
+        );
+        assert_eq!([0, 128, 128], ycbcr_of([0, 0, 0], YuvStandard::Bt709Full));
+        assert_eq!(
+            [235, 128, 128],
+            ycbcr_of([255, 255, 255], YuvStandard::Bt601Limited)
+        );
+        assert_eq!(
+            [16, 128, 128],
+            ycbcr_of([0, 0, 0], YuvStandard::Bt709Limited)
+        );
+
+        // 赤
+        let red = [255, 0, 0];
+        println!(
+            "  [yuv][test_to_ycbcr()] red bt601 = {:?}, bt709 = {:?}",
+            ycbcr_of(red, YuvStandard::Bt601Full),
+            ycbcr_of(red, YuvStandard::Bt709Full)
+        );
+        assert_eq!([76, 85, 255], ycbcr_of(red, YuvStandard::Bt601Full));
+        assert_eq!([54, 99, 255], ycbcr_of(red, YuvStandard::Bt709Full));
+        assert_eq!([81, 90, 240], ycbcr_of(red, YuvStandard::Bt601Limited));
+
+        println!("}}");
+    }
+
+    #[test]
+    fn test_to_yuv420() {
+        println!("yuv::test::test_to_yuv420()  {{");
+
+        // 奇数サイズ (5x3)
+        let mut rgb = RgbRaw::<u8>::new(5, 3);
+        for y in 0..3 {
+            for x in 0..5 {
+                let v = if x % 2 == 0 { 255 } else { 0 };
+                rgb.set_pix(x, y, [v, 0, 0]);
+            }
+        }
+        let planes = to_ycbcr(&rgb, YuvStandard::Bt601Full);
+        let buffer = to_yuv420(&planes);
+        assert_eq!((3, 2), buffer.chroma_size());
+        assert_eq!(15, buffer.y_plane().len());
+        assert_eq!(6, buffer.cb_plane().len());
+        assert_eq!(6, buffer.cr_plane().len());
+        assert_eq!(15 + 6 + 6, buffer.as_bytes().len());
+        println!("  [yuv][test_to_yuv420()] cr = {:?}", buffer.cr_plane());
+
+        // Y はフル解像度のまま
+        assert_eq!(
+            planes.y().data().iter().copied().collect::<Vec<_>>(),
+            buffer.y_plane()
+        );
+        // 2x2平均: 赤(Cr 255)と黒(Cr 128)の平均
+        assert_eq!(192, buffer.cr_plane()[0]);
+        // 右端の列は赤のみ
+        assert_eq!(255, buffer.cr_plane()[2]);
+
+        println!("}}");
+    }
+}